@@ -0,0 +1,192 @@
+//! Command line reading shared by the dashboards and tools of the workspace.
+//!
+//! Flags take their value as the next argument or after `=`, e.g. `--value 120`
+//! or `--value=120`. Each program matches the flags it knows and reports what is
+//! wrong with an [`ArgError`].
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::vec;
+
+/// The arguments of a program, read one [`Arg`] at a time.
+#[derive(Debug, Clone)]
+pub struct Args {
+    args: vec::IntoIter<String>,
+}
+
+/// An argument, split at the first `=` when it is a flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arg {
+    arg: String,
+    flag: String,
+    inline: Option<String>,
+}
+
+impl Arg {
+    /// The flag without a value given after `=`, or the whole argument.
+    pub fn flag(&self) -> &str {
+        &self.flag
+    }
+
+    /// The argument as given, e.g. to hand it on to another parser.
+    pub fn into_string(self) -> String {
+        self.arg
+    }
+
+    pub fn unknown(&self) -> ArgError {
+        ArgError::UnknownArgument(self.arg.clone())
+    }
+
+    /// `expected` describes what the value should have been, e.g. `a positive number`.
+    pub fn invalid(&self, value: impl Into<String>, expected: &'static str) -> ArgError {
+        ArgError::InvalidValue {
+            flag: self.flag.clone(),
+            value: value.into(),
+            expected,
+        }
+    }
+}
+
+impl Args {
+    /// Takes the arguments without the program name.
+    pub fn new<I>(args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let args: Vec<String> = args.into_iter().map(Into::into).collect();
+        Self {
+            args: args.into_iter(),
+        }
+    }
+
+    /// The value of `arg`, given after `=` or as the next argument.
+    pub fn value(&mut self, arg: &Arg) -> Result<String, ArgError> {
+        arg.inline
+            .clone()
+            .or_else(|| self.args.next())
+            .ok_or_else(|| ArgError::MissingValue(arg.flag.clone()))
+    }
+
+    /// The value of `arg` parsed, when `valid` holds for it. `expected` describes
+    /// the values that do, see [`Arg::invalid`].
+    pub fn parse<T: FromStr>(
+        &mut self,
+        arg: &Arg,
+        expected: &'static str,
+        valid: impl FnOnce(&T) -> bool,
+    ) -> Result<T, ArgError> {
+        let value = self.value(arg)?;
+        match value.parse() {
+            Ok(parsed) if valid(&parsed) => Ok(parsed),
+            _ => Err(arg.invalid(value, expected)),
+        }
+    }
+
+    /// The arguments not read yet, e.g. those after `--`.
+    pub fn rest(self) -> Vec<String> {
+        self.args.collect()
+    }
+}
+
+impl Iterator for Args {
+    type Item = Arg;
+
+    fn next(&mut self) -> Option<Arg> {
+        let arg = self.args.next()?;
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg.clone(), None),
+        };
+
+        Some(Arg { arg, flag, inline })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    UnknownArgument(String),
+    MissingValue(String),
+    InvalidValue {
+        flag: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::UnknownArgument(arg) => write!(f, "unknown argument `{arg}`"),
+            ArgError::MissingValue(flag) => write!(f, "`{flag}` needs a value"),
+            ArgError::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(f, "`{value}` is not {expected} for `{flag}`"),
+        }
+    }
+}
+
+impl Error for ArgError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_values_after_the_flag_or_equals() {
+        let mut args = Args::new(["--size", "512", "--value=-20", "--gpu", "file=a"]);
+
+        let size = args.next().unwrap();
+        assert_eq!(size.flag(), "--size");
+        assert_eq!(args.parse(&size, "a size", |size: &u32| *size > 0), Ok(512));
+
+        let value = args.next().unwrap();
+        assert_eq!(value.flag(), "--value");
+        assert_eq!(args.value(&value).as_deref(), Ok("-20"));
+
+        assert_eq!(args.next().unwrap().flag(), "--gpu");
+        let positional = args.next().unwrap();
+        assert_eq!(positional.flag(), "file=a");
+        assert_eq!(positional.into_string(), "file=a");
+        assert_eq!(args.next(), None);
+    }
+
+    #[test]
+    fn reports_missing_and_invalid_values() {
+        let mut args = Args::new(["--rate", "fast", "--rate=0", "--rate"]);
+
+        let rate = args.next().unwrap();
+        let err = args
+            .parse(&rate, "a positive number", |rate: &f32| *rate > 0.0)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`fast` is not a positive number for `--rate`"
+        );
+
+        let rate = args.next().unwrap();
+        assert!(matches!(
+            args.parse(&rate, "a positive number", |rate: &f32| *rate > 0.0),
+            Err(ArgError::InvalidValue { value, .. }) if value == "0"
+        ));
+
+        let rate = args.next().unwrap();
+        assert_eq!(
+            args.value(&rate),
+            Err(ArgError::MissingValue("--rate".to_owned()))
+        );
+    }
+
+    #[test]
+    fn hands_on_the_rest() {
+        let mut args = Args::new(["--", "--source", "sweep"]);
+
+        assert_eq!(args.next().unwrap().flag(), "--");
+        assert_eq!(args.rest(), ["--source", "sweep"]);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::Dashboard;
+use cli_args::{ArgError, Args};
+use frame_metrics::{DEFAULT_TARGET_FPS, MAX_TARGET_FPS, MIN_TARGET_FPS};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_DURATION: Duration = Duration::from_secs(20);
+const MAX_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+/// Deterministic and always moving, so that every frame is an animation frame.
+const DEFAULT_SOURCE: [&str; 2] = ["--source", "sweep"];
+
+/// What to run and where to write the report, see [`BenchArgs::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchArgs {
+    pub dashboards: Vec<Dashboard>,
+    pub duration: Duration,
+    pub target_fps: f32,
+    pub gpu: bool,
+    pub bin_dir: Option<PathBuf>,
+    pub output: PathBuf,
+    /// Arguments passed to every dashboard to select the speed profile.
+    pub source: Vec<String>,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        Self {
+            dashboards: Dashboard::ALL.to_vec(),
+            duration: DEFAULT_DURATION,
+            target_fps: DEFAULT_TARGET_FPS,
+            gpu: false,
+            bin_dir: None,
+            output: "bench-report".into(),
+            source: DEFAULT_SOURCE.map(String::from).to_vec(),
+        }
+    }
+}
+
+impl BenchArgs {
+    /// Reads the arguments, without the program name:
+    ///
+    /// - `--frameworks <names>`, comma separated, `egui,iced,slint` by default;
+    /// - `--duration <seconds>` each dashboard runs, `20` by default and up to a
+    ///   day;
+    /// - `--target-fps <1-1000>` frames are dropped against, `60` by default;
+    /// - `--gpu` to keep the default renderers rather than the software ones;
+    /// - `--bin-dir <path>` holding the demo binaries, the directory of this one
+    ///   by default;
+    /// - `--output <path>` of the report without extension, `bench-report` by
+    ///   default;
+    /// - `--` followed by the source arguments of the dashboards, `--source sweep`
+    ///   by default.
+    ///
+    /// Values can also follow the flag after `=`, e.g. `--duration=30`.
+    pub fn parse<I>(args: I) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            match arg.flag() {
+                "--frameworks" => {
+                    parsed.dashboards = args
+                        .value(&arg)?
+                        .split(',')
+                        .map(|name| name.trim().parse())
+                        .collect::<Result<_, _>>()?;
+                }
+                "--duration" => {
+                    let seconds = args.parse(&arg, "a duration up to a day", |seconds: &f32| {
+                        *seconds > 0.0
+                            && Duration::try_from_secs_f32(*seconds)
+                                .is_ok_and(|duration| duration <= MAX_DURATION)
+                    })?;
+                    parsed.duration = Duration::from_secs_f32(seconds);
+                }
+                "--target-fps" => {
+                    parsed.target_fps =
+                        args.parse(&arg, "a refresh rate from 1 to 1000", |fps| {
+                            (MIN_TARGET_FPS..=MAX_TARGET_FPS).contains(fps)
+                        })?;
+                }
+                "--gpu" => parsed.gpu = true,
+                "--bin-dir" => parsed.bin_dir = Some(args.value(&arg)?.into()),
+                "--output" => parsed.output = args.value(&arg)?.into(),
+                "--" => {
+                    parsed.source = args.rest();
+                    break;
+                }
+                _ => return Err(arg.unknown().into()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// `--bin-dir`, or the directory of this executable, where Cargo puts the
+    /// demos of the workspace too.
+    pub fn bin_dir(&self) -> PathBuf {
+        self.bin_dir.clone().unwrap_or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(PathBuf::from))
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    Args(ArgError),
+    UnknownFramework(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Args(err) => err.fmt(f),
+            ArgsError::UnknownFramework(name) => {
+                let names: Vec<_> = Dashboard::ALL.iter().map(|d| d.name()).collect();
+                write!(
+                    f,
+                    "unknown framework `{name}`, expected some of: {}",
+                    names.join(",")
+                )
+            }
+        }
+    }
+}
+
+impl Error for ArgsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArgsError::Args(err) => Some(err),
+            ArgsError::UnknownFramework(_) => None,
+        }
+    }
+}
+
+impl From<ArgError> for ArgsError {
+    fn from(err: ArgError) -> Self {
+        ArgsError::Args(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_every_option() {
+        let args = BenchArgs::parse([
+            "--frameworks",
+            "slint,egui",
+            "--duration=5",
+            "--target-fps",
+            "144",
+            "--gpu",
+            "--output",
+            "out/report",
+            "--",
+            "--replay",
+            "drive.csv",
+            "--loop",
+        ])
+        .unwrap();
+
+        assert_eq!(args.dashboards, [Dashboard::Slint, Dashboard::Egui]);
+        assert_eq!(args.duration, Duration::from_secs(5));
+        assert_eq!(args.target_fps, 144.0);
+        assert!(args.gpu);
+        assert_eq!(args.output, PathBuf::from("out/report"));
+        assert_eq!(args.source, ["--replay", "drive.csv", "--loop"]);
+
+        assert_eq!(
+            BenchArgs::parse([] as [&str; 0]).unwrap().source,
+            DEFAULT_SOURCE
+        );
+        assert!(matches!(
+            BenchArgs::parse(["--frameworks", "egui,gtk"]),
+            Err(ArgsError::UnknownFramework(_))
+        ));
+        for duration in ["0", "90000", "1e30", "inf"] {
+            assert!(matches!(
+                BenchArgs::parse(["--duration", duration]),
+                Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+            ));
+        }
+        assert!(matches!(
+            BenchArgs::parse(["--target-fps=1e10"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Runs the egui, iced and Slint dashboards one after the other on the same
+//! scripted speed profile and compares how they fare.
+//!
+//! Every dashboard is started as its own process with `--exit-after` and
+//! `--metrics` (see `frame_metrics::MetricsArgs`) so that its frame timings and
+//! startup time come back as JSON, while the runner measures the peak resident
+//! memory of the process and the size of its binary.
+//!
+//! Unless `--gpu` is given the dashboards are told through the environment to
+//! render on the CPU: egui's OpenGL through Mesa's llvmpipe, iced with tiny-skia
+//! and Slint with its software renderer. They still open a window, `xvfb-run`
+//! provides a display on a headless machine.
+
+mod args;
+mod process;
+mod report;
+
+pub use args::{ArgsError, BenchArgs};
+pub use report::{Measurement, Percentiles, Report};
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Time a dashboard gets to start and write its metrics on top of `--duration`.
+const GRACE: Duration = Duration::from_secs(30);
+/// Lines of a failing dashboard's output kept for the report.
+const ERROR_LINES: usize = 5;
+
+/// Demo binary of one toolkit, picked with `--frameworks <names>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dashboard {
+    Egui,
+    Iced,
+    Slint,
+}
+
+impl Dashboard {
+    pub const ALL: [Dashboard; 3] = [Dashboard::Egui, Dashboard::Iced, Dashboard::Slint];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dashboard::Egui => "egui",
+            Dashboard::Iced => "iced",
+            Dashboard::Slint => "slint",
+        }
+    }
+
+    /// Executable built by the workspace.
+    pub fn binary(self, dir: &Path) -> PathBuf {
+        dir.join(format!(
+            "demo-{}{}",
+            self.name(),
+            std::env::consts::EXE_SUFFIX
+        ))
+    }
+
+    /// Environment that moves the rendering to the CPU.
+    fn software_rendering(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Dashboard::Egui => &[("LIBGL_ALWAYS_SOFTWARE", "1")],
+            Dashboard::Iced => &[("ICED_BACKEND", "tiny-skia")],
+            Dashboard::Slint => &[("SLINT_BACKEND", "winit-software")],
+        }
+    }
+}
+
+impl FromStr for Dashboard {
+    type Err = ArgsError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|dashboard| dashboard.name() == name)
+            .ok_or_else(|| ArgsError::UnknownFramework(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Dashboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Runs `dashboard` for `args.duration` and collects its numbers.
+pub fn run(dashboard: Dashboard, args: &BenchArgs) -> Result<Measurement, BenchError> {
+    let binary = dashboard.binary(&args.bin_dir());
+    let binary_size = std::fs::metadata(&binary)
+        .map_err(|_| BenchError::MissingBinary(binary.clone()))?
+        .len();
+
+    let scratch = std::env::temp_dir().join(format!(
+        "dashboard-bench-{}-{}",
+        std::process::id(),
+        dashboard
+    ));
+    let metrics = scratch.with_extension("json");
+    let log = scratch.with_extension("log");
+
+    let mut command = Command::new(&binary);
+    command
+        .args(&args.source)
+        .arg("--metrics")
+        .arg(&metrics)
+        .arg("--target-fps")
+        .arg(args.target_fps.to_string())
+        .arg("--exit-after")
+        .arg(args.duration.as_secs_f32().to_string())
+        // The panic message rather than a backtrace ends the output of a crash.
+        .env("RUST_BACKTRACE", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(std::fs::File::create(&log)?);
+    if !args.gpu {
+        command.envs(dashboard.software_rendering().iter().copied());
+    }
+
+    let timeout = args.duration.checked_add(GRACE).unwrap_or(Duration::MAX);
+    let exit = process::run(&mut command, timeout);
+    let output = std::fs::read_to_string(&log).unwrap_or_default();
+    let _ = std::fs::remove_file(&log);
+    let exit = exit?;
+    if !exit.status.success() {
+        let lines: Vec<_> = output.lines().collect();
+        return Err(BenchError::Failed {
+            status: exit.status,
+            output: lines[lines.len().saturating_sub(ERROR_LINES)..].join("\n"),
+        });
+    }
+
+    let json = std::fs::read_to_string(&metrics)
+        .map_err(|err| BenchError::NoMetrics(metrics.clone(), err))?;
+    let _ = std::fs::remove_file(&metrics);
+
+    Measurement::from_metrics(dashboard, &json, args.duration)
+        .map(|measurement| Measurement {
+            binary_size,
+            peak_rss: exit.peak_rss,
+            ..measurement
+        })
+        .ok_or(BenchError::InvalidMetrics(metrics))
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    /// The workspace was not built, or `--bin-dir` points elsewhere.
+    MissingBinary(PathBuf),
+    Io(io::Error),
+    /// The dashboard was still running after this long and was killed.
+    TimedOut(Duration),
+    /// The exit status and the last lines the dashboard printed.
+    Failed {
+        status: ExitStatus,
+        output: String,
+    },
+    NoMetrics(PathBuf, io::Error),
+    InvalidMetrics(PathBuf),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BenchError::MissingBinary(path) => write!(
+                f,
+                "{} not found, build the dashboards with `cargo build --release --workspace`",
+                path.display()
+            ),
+            BenchError::Io(err) => write!(f, "cannot run the dashboard: {err}"),
+            BenchError::TimedOut(after) => {
+                write!(f, "still running after {} s, killed", after.as_secs())
+            }
+            BenchError::Failed { status, output } if output.is_empty() => {
+                write!(f, "exited with {status}")
+            }
+            BenchError::Failed { status, output } => write!(f, "exited with {status}:\n{output}"),
+            BenchError::NoMetrics(path, err) => {
+                write!(f, "no frame metrics in {}: {err}", path.display())
+            }
+            BenchError::InvalidMetrics(path) => {
+                write!(f, "unexpected frame metrics in {}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for BenchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BenchError::Io(err) | BenchError::NoMetrics(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BenchError {
+    fn from(err: io::Error) -> Self {
+        BenchError::Io(err)
+    }
+}
@@ -0,0 +1,60 @@
+use dashboard_bench::{run, BenchArgs, Report};
+
+const USAGE: &str = "\
+usage: bench [--frameworks egui,iced,slint] [--duration <seconds>]
+             [--target-fps <1-1000>] [--gpu] [--bin-dir <path>]
+             [--output <path>] [-- <source arguments>]
+
+Runs every demo dashboard for the same time on the same speed profile, with
+software rendering unless --gpu is given, and writes <output>.json and
+<output>.md comparing frame times, dropped frames, peak memory, startup time and
+binary size. Build the dashboards first, e.g. with
+`cargo build --release --workspace`. On a machine without a display run it
+under `xvfb-run`.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let args = BenchArgs::parse(args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        std::process::exit(2);
+    });
+
+    let runs: Vec<_> = args
+        .dashboards
+        .iter()
+        .map(|&dashboard| {
+            eprintln!("running {dashboard} for {} s", args.duration.as_secs_f32());
+            (dashboard, run(dashboard, &args))
+        })
+        .collect();
+    let failed = runs.iter().any(|(_, run)| run.is_err());
+
+    let report = Report {
+        duration: args.duration,
+        target_fps: args.target_fps,
+        source: args.source.clone(),
+        gpu: args.gpu,
+        runs,
+    };
+    match report.write(&args.output) {
+        Ok(paths) => {
+            println!("{}", report.to_markdown());
+            for path in paths {
+                println!("{}", path.display());
+            }
+        }
+        Err(err) => {
+            eprintln!("cannot write the report: {err}");
+            std::process::exit(1);
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::BenchError;
+use std::process::{Child, Command, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running dashboard is checked on.
+const POLL_PERIOD: Duration = Duration::from_millis(20);
+
+#[derive(Debug)]
+pub struct Exit {
+    pub status: ExitStatus,
+    /// Bytes, `None` where the system doesn't tell.
+    pub peak_rss: Option<u64>,
+}
+
+/// Runs `command` to completion, killing it after `timeout`.
+pub fn run(command: &mut Command, timeout: Duration) -> Result<Exit, BenchError> {
+    let mut child = command.spawn()?;
+    let started = Instant::now();
+
+    loop {
+        if let Some(exit) = try_wait(&mut child)? {
+            return Ok(exit);
+        }
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(BenchError::TimedOut(timeout));
+        }
+        thread::sleep(POLL_PERIOD);
+    }
+}
+
+/// Reaps the child with `wait4` to get its resource usage along with the status.
+#[cfg(unix)]
+fn try_wait(child: &mut Child) -> Result<Option<Exit>, BenchError> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    // SAFETY: both out-pointers are valid for the duration of the call.
+    let (pid, usage) = unsafe {
+        let mut usage: libc::rusage = std::mem::zeroed();
+        let pid = libc::wait4(
+            child.id() as libc::pid_t,
+            &mut status,
+            libc::WNOHANG,
+            &mut usage,
+        );
+        (pid, usage)
+    };
+
+    match pid {
+        0 => Ok(None),
+        pid if pid < 0 => Err(std::io::Error::last_os_error().into()),
+        _ => Ok(Some(Exit {
+            status: ExitStatus::from_raw(status),
+            peak_rss: u64::try_from(usage.ru_maxrss).ok().map(max_rss_bytes),
+        })),
+    }
+}
+
+#[cfg(not(unix))]
+fn try_wait(child: &mut Child) -> Result<Option<Exit>, BenchError> {
+    Ok(child.try_wait()?.map(|status| Exit {
+        status,
+        peak_rss: None,
+    }))
+}
+
+/// `ru_maxrss` is in kibibytes on Linux and in bytes on macOS.
+#[cfg(unix)]
+fn max_rss_bytes(max_rss: u64) -> u64 {
+    if cfg!(target_os = "macos") {
+        max_rss
+    } else {
+        max_rss * 1024
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_status_and_memory() {
+        let exit = run(
+            Command::new("sh").args(["-c", "exit 3"]),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        assert_eq!(exit.status.code(), Some(3));
+        assert!(exit.peak_rss.is_some_and(|bytes| bytes > 0));
+        assert!(matches!(
+            run(Command::new("sleep").arg("10"), Duration::from_millis(100)),
+            Err(BenchError::TimedOut(_))
+        ));
+    }
+}
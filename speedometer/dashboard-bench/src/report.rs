@@ -0,0 +1,281 @@
+use crate::{BenchError, Dashboard};
+pub use frame_metrics::Percentiles;
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Formats one row of the Markdown table.
+type Cell = fn(&Measurement) -> String;
+
+/// What one dashboard did during a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub dashboard: Dashboard,
+    /// Bytes.
+    pub binary_size: u64,
+    /// From `main` to the first frame.
+    pub startup: Option<Duration>,
+    /// Bytes, `None` where the system doesn't tell.
+    pub peak_rss: Option<u64>,
+    pub frames: u64,
+    pub dropped: u64,
+    pub fps: f32,
+    /// `None` when nothing animated.
+    pub frame_time: Option<Percentiles>,
+    pub cpu_time: Option<Percentiles>,
+    pub mean_cpu_time: Duration,
+}
+
+impl Measurement {
+    /// Reads the export of `frame_metrics::FrameMetrics::to_json` written by a
+    /// dashboard that ran for `duration`, the process numbers are left at zero.
+    pub fn from_metrics(dashboard: Dashboard, json: &str, duration: Duration) -> Option<Self> {
+        let metrics: Value = serde_json::from_str(json).ok()?;
+        let frames = metrics["frames"].as_u64()?;
+
+        Some(Self {
+            dashboard,
+            binary_size: 0,
+            startup: metrics["startup_ms"].as_f64().map(from_millis),
+            peak_rss: None,
+            frames,
+            dropped: metrics["dropped"].as_u64()?,
+            fps: frames as f32 / duration.as_secs_f32(),
+            frame_time: percentiles(&metrics["frame_time_ms"]),
+            cpu_time: percentiles(&metrics["cpu_time_ms"]),
+            mean_cpu_time: from_millis(metrics["cpu_time_ms"]["mean"].as_f64()?),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        let percentiles = |percentiles: Option<Percentiles>| {
+            percentiles.map(|percentiles| {
+                json!({
+                    "p50": millis(percentiles.p50),
+                    "p95": millis(percentiles.p95),
+                    "p99": millis(percentiles.p99),
+                })
+            })
+        };
+
+        json!({
+            "dashboard": self.dashboard.name(),
+            "binary_size_bytes": self.binary_size,
+            "startup_ms": self.startup.map(millis),
+            "peak_rss_bytes": self.peak_rss,
+            "frames": self.frames,
+            "dropped": self.dropped,
+            "fps": self.fps,
+            "frame_time_ms": percentiles(self.frame_time),
+            "cpu_time_ms": percentiles(self.cpu_time),
+            "mean_cpu_time_ms": millis(self.mean_cpu_time),
+        })
+    }
+}
+
+/// The runs of every dashboard on the same profile.
+#[derive(Debug)]
+pub struct Report {
+    pub duration: Duration,
+    pub target_fps: f32,
+    /// Arguments selecting the speed profile, the same for every dashboard.
+    pub source: Vec<String>,
+    pub gpu: bool,
+    pub runs: Vec<(Dashboard, Result<Measurement, BenchError>)>,
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        let runs: Vec<_> = self
+            .runs
+            .iter()
+            .map(|(dashboard, run)| match run {
+                Ok(measurement) => measurement.to_json(),
+                Err(err) => json!({
+                    "dashboard": dashboard.name(),
+                    "error": err.to_string(),
+                }),
+            })
+            .collect();
+
+        let report = json!({
+            "duration_s": self.duration.as_secs_f64(),
+            "target_fps": self.target_fps,
+            "source": self.source,
+            "renderer": self.renderer(),
+            "dashboards": runs,
+        });
+        serde_json::to_string_pretty(&report).expect("the report is plain JSON")
+    }
+
+    /// A table with a column per dashboard, failures listed below it.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Dashboard benchmark\n\n");
+        let _ = writeln!(
+            markdown,
+            "{} s of `{}` per dashboard, {} rendering, {} FPS target.\n",
+            self.duration.as_secs_f32(),
+            self.source.join(" "),
+            self.renderer(),
+            self.target_fps
+        );
+
+        let rows: [(&str, Cell); 8] = [
+            ("Binary size", |m| mebibytes(m.binary_size)),
+            ("Startup", |m| m.startup.map_or("-".into(), format_millis)),
+            ("Peak RSS", |m| m.peak_rss.map_or("-".into(), mebibytes)),
+            ("Frames", |m| m.frames.to_string()),
+            ("Average FPS", |m| format!("{:.1}", m.fps)),
+            ("Dropped frames", |m| m.dropped.to_string()),
+            ("Frame time p50 / p95 / p99", |m| {
+                m.frame_time.map_or("-".into(), format_percentiles)
+            }),
+            ("CPU time mean / p95", |m| match m.cpu_time {
+                Some(cpu_time) => format!(
+                    "{:.2} / {:.2} ms",
+                    millis(m.mean_cpu_time),
+                    millis(cpu_time.p95)
+                ),
+                None => "-".into(),
+            }),
+        ];
+
+        let mut header = String::from("|");
+        let mut align = String::from("|---");
+        for (dashboard, _) in &self.runs {
+            let _ = write!(header, " | {dashboard}");
+            align.push_str("|---:");
+        }
+        let _ = writeln!(markdown, "{header} |\n{align}|");
+        for (name, cell) in rows {
+            let _ = write!(markdown, "| {name}");
+            for (_, run) in &self.runs {
+                let cell = run.as_ref().map_or("failed".into(), cell);
+                let _ = write!(markdown, " | {cell}");
+            }
+            markdown.push_str(" |\n");
+        }
+
+        let failures: Vec<_> = self
+            .runs
+            .iter()
+            .filter_map(|(dashboard, run)| run.as_ref().err().map(|err| (dashboard, err)))
+            .collect();
+        if !failures.is_empty() {
+            markdown.push_str("\n## Failures\n\n");
+            for (dashboard, err) in failures {
+                let _ = writeln!(
+                    markdown,
+                    "- {dashboard}: {}",
+                    err.to_string().replace('\n', "\n  ")
+                );
+            }
+        }
+
+        markdown
+    }
+
+    /// Writes `<output>.json` and `<output>.md` and returns their paths.
+    pub fn write(&self, output: &Path) -> io::Result<[PathBuf; 2]> {
+        let json = output.with_extension("json");
+        let markdown = output.with_extension("md");
+        std::fs::write(&json, self.to_json())?;
+        std::fs::write(&markdown, self.to_markdown())?;
+        Ok([json, markdown])
+    }
+
+    fn renderer(&self) -> &'static str {
+        if self.gpu {
+            "default"
+        } else {
+            "software"
+        }
+    }
+}
+
+fn percentiles(summary: &Value) -> Option<Percentiles> {
+    let at = |key: &str| summary[key].as_f64().map(from_millis);
+    Some(Percentiles {
+        p50: at("p50")?,
+        p95: at("p95")?,
+        p99: at("p99")?,
+    })
+}
+
+fn from_millis(millis: f64) -> Duration {
+    Duration::from_secs_f64(millis.max(0.0) / 1e3)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e3
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.0} ms", millis(duration))
+}
+
+fn format_percentiles(percentiles: Percentiles) -> String {
+    format!(
+        "{:.2} / {:.2} / {:.2} ms",
+        millis(percentiles.p50),
+        millis(percentiles.p95),
+        millis(percentiles.p99)
+    )
+}
+
+fn mebibytes(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = r#"{"name":"egui","target_fps":60.0,"startup_ms":183.5,"frames":600,
+        "dropped":2,"frame_time_ms":{"p50":16.5,"p95":17.0,"p99":33.25,"histogram":[]},
+        "cpu_time_ms":{"p50":1.0,"p95":2.5,"p99":4.0,"mean":1.25,"histogram":[]}}"#;
+
+    #[test]
+    fn compares_the_dashboards() {
+        let duration = Duration::from_secs(10);
+        let measurement = Measurement {
+            binary_size: 12 * 1024 * 1024,
+            peak_rss: Some(80 * 1024 * 1024),
+            ..Measurement::from_metrics(Dashboard::Egui, METRICS, duration).unwrap()
+        };
+        assert_eq!(measurement.startup, Some(Duration::from_micros(183_500)));
+        assert_eq!(measurement.fps, 60.0);
+        assert_eq!(
+            measurement.frame_time.unwrap().p99,
+            Duration::from_micros(33_250)
+        );
+
+        let report = Report {
+            duration,
+            target_fps: 60.0,
+            source: vec!["--source".into(), "sweep".into()],
+            gpu: false,
+            runs: vec![
+                (Dashboard::Egui, Ok(measurement)),
+                (
+                    Dashboard::Iced,
+                    Err(BenchError::TimedOut(Duration::from_secs(40))),
+                ),
+            ],
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| | egui | iced |\n|---|---:|---:|\n"));
+        assert!(markdown.contains("| Binary size | 12.0 MiB | failed |\n"));
+        assert!(markdown.contains("| Frame time p50 / p95 / p99 | 16.50 / 17.00 / 33.25 ms |"));
+        assert!(markdown.contains("- iced: still running after 40 s, killed\n"));
+
+        let json: Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["renderer"], "software");
+        assert_eq!(json["dashboards"][0]["peak_rss_bytes"], 80 * 1024 * 1024);
+        assert_eq!(json["dashboards"][0]["dropped"], 2);
+        assert!(json["dashboards"][1]["error"].is_string());
+    }
+}
@@ -1,32 +0,0 @@
-use eframe::emath::History;
-
-pub struct FramesHistory {
-    frame_times: History<f32>,
-}
-
-impl FramesHistory {
-    pub fn new() -> Self {
-        let max_age: f32 = 1.0;
-        let max_len = (max_age * 300.0).round() as usize;
-
-        FramesHistory {
-            frame_times: History::new(0..max_len, max_age),
-        }
-    }
-
-    pub fn on_new_frame(&mut self, now: f64, previous_frame_time: Option<f32>) {
-        let previous_frame_time = previous_frame_time.unwrap_or_default();
-        if let Some(latest) = self.frame_times.latest_mut() {
-            *latest = previous_frame_time;
-        }
-        self.frame_times.add(now, previous_frame_time);
-    }
-
-    pub fn fps(&self) -> f32 {
-        1.0 / self.frame_times.mean_time_interval().unwrap_or_default()
-    }
-
-    pub fn mean_frame_time(&self) -> f32 {
-        self.frame_times.average().unwrap_or_default()
-    }
-}
@@ -0,0 +1,9 @@
+//! The egui speedometer, shared by the dashboard and the `gauge-render` tool.
+
+mod mesh_callback;
+pub mod shape;
+pub mod speedometer;
+pub mod style;
+
+pub use speedometer::{Speedometer, SpeedometerUi};
+pub use style::SpeedometerStyle;
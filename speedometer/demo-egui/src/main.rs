@@ -1,70 +1,149 @@
-mod frame_history;
-mod shape;
-mod speedometer;
-
-use crate::frame_history::FramesHistory;
-use crate::speedometer::SpeedometerUi;
+use demo_egui::SpeedometerUi;
 
 use eframe::egui::{self, Theme};
+use frame_metrics::{FrameMetrics, MetricsArgs};
 use std::time::{Duration, Instant};
+use vehicle_data::{
+    source_from_args, Playback, Sample, SourceStatus, VehicleDataSource, PLAYBACK_RATES,
+    SAMPLE_PERIOD,
+};
 
-const MAX_SPEED: u32 = 200;
-const MIN_SPEED: u32 = 0;
+const MAX_SPEED: f32 = 200.0;
+const MIN_SPEED: f32 = 0.0;
 
 fn main() -> eframe::Result {
-    let options = eframe::NativeOptions {
+    let (options, args) = MetricsArgs::split(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let metrics = options.metrics("egui");
+    let source = source_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
+    let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([800.0, 600.0]),
         ..Default::default()
     };
 
     eframe::run_native(
         "Egui Dashboard",
-        options,
-        Box::new(|cc| Ok(Box::new(Dashboard::new(cc)))),
+        native_options,
+        Box::new(move |cc| {
+            Ok(Box::new(Dashboard::new(
+                cc,
+                source,
+                metrics,
+                options.exit_after,
+            )))
+        }),
     )
 }
 
 struct Dashboard {
-    frame_history: FramesHistory,
-    speed: u32,
-    last_update: Instant,
+    metrics: FrameMetrics,
+    source: Box<dyn VehicleDataSource>,
+    sample: Sample,
+    started: Instant,
+    exit_after: Option<Duration>,
 }
 
 impl Dashboard {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        source: Box<dyn VehicleDataSource>,
+        metrics: FrameMetrics,
+        exit_after: Option<Duration>,
+    ) -> Self {
         cc.egui_ctx.set_theme(Theme::Dark);
 
         Self {
-            frame_history: FramesHistory::new(),
-            speed: 0,
-            last_update: Instant::now(),
+            metrics,
+            source,
+            sample: Sample::default(),
+            started: Instant::now(),
+            exit_after,
         }
     }
 }
 
 impl eframe::App for Dashboard {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        self.frame_history
-            .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
-
-        let mean_frame_time_string = format!(
-            "Mean CPU usage: {:.2} ms / frame",
-            1e3 * self.frame_history.mean_frame_time()
-        );
+        // egui only knows the CPU time of the previous frame, it is counted with this one.
+        let cpu = Duration::from_secs_f32(frame.info().cpu_usage.unwrap_or_default());
+        self.metrics
+            .record(Instant::now(), cpu, ctx.requested_repaint_last_pass());
 
-        let fps_string = format!("FPS: {:.0}", self.frame_history.fps());
-
-        if self.last_update.elapsed() > Duration::from_secs(1) {
-            self.speed += 1;
-            self.last_update = Instant::now();
+        let elapsed = self.started.elapsed();
+        if self
+            .exit_after
+            .is_some_and(|exit_after| elapsed >= exit_after)
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if let Some(sample) = self.source.poll(elapsed) {
+            self.sample = sample;
         }
+        let status = self.source.status(elapsed);
+        let live = status == SourceStatus::Live;
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.label(mean_frame_time_string);
-            ui.label(fps_string);
-            ui.speedometer(self.speed, MIN_SPEED, MAX_SPEED);
+            ui.label(self.metrics.stats().to_string());
+            ui.label(self.sample.to_string());
+            if let Some(playback) = self.source.playback_mut() {
+                playback_controls(ui, playback);
+            }
+            if !live {
+                ui.colored_label(ui.visuals().warn_fg_color, status.to_string());
+            }
+            // While the source is silent or has no signal, the gauge shows the last value greyed out.
+            ui.add_enabled_ui(live, |ui| {
+                ui.speedometer(self.sample.speed, MIN_SPEED, MAX_SPEED)
+            });
         });
 
-        ctx.request_repaint(); // Нужен только для правильного подсчёта fps.
+        // The sensors are polled 10 times a second, the animation moves the needle in between.
+        ctx.request_repaint_after(SAMPLE_PERIOD);
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(err) = self.metrics.export() {
+            eprintln!("cannot write frame metrics: {err}");
+        }
+    }
+}
+
+fn playback_controls(ui: &mut egui::Ui, playback: &mut dyn Playback) {
+    ui.horizontal(|ui| {
+        let paused = playback.is_paused();
+        if ui.button(if paused { "Play" } else { "Pause" }).clicked() {
+            playback.set_paused(!paused);
+        }
+
+        let mut position = playback.position();
+        let seek = egui::Slider::new(&mut position, 0.0..=playback.duration())
+            .fixed_decimals(1)
+            .suffix(" s");
+        if ui.add(seek).changed() {
+            playback.seek(position);
+        }
+
+        let mut rate = playback.rate();
+        egui::ComboBox::from_id_salt("playback_rate")
+            .selected_text(format!("{rate}×"))
+            .show_ui(ui, |ui| {
+                for option in PLAYBACK_RATES {
+                    ui.selectable_value(&mut rate, option, format!("{option}×"));
+                }
+            });
+        if rate != playback.rate() {
+            playback.set_rate(rate);
+        }
+
+        let mut looping = playback.is_looping();
+        if ui.checkbox(&mut looping, "Loop").changed() {
+            playback.set_looping(looping);
+        }
+    });
 }
@@ -0,0 +1,318 @@
+//! A mesh painted from an `Arc` by eframe's glow backend.
+//!
+//! egui 0.29 only takes a [`Shape::Mesh`] by value, so a cached mesh would be
+//! copied every frame to hand it over. A [`MeshCallback`] instead keeps the mesh
+//! in GL buffers of its own, uploaded again only when it is given another mesh,
+//! and draws it with the shaders of `egui_glow`.
+
+use eframe::egui::epaint::{Mesh, PaintCallback, PaintCallbackInfo, Vertex};
+use eframe::egui::{Rect, Shape};
+use eframe::egui_glow::{CallbackFn, Painter, ShaderVersion};
+use eframe::glow::{self, HasContext};
+use std::mem;
+use std::sync::{Arc, Mutex, PoisonError};
+
+const VERTEX_SHADER: &str = r#"
+#if NEW_SHADER_INTERFACE
+    #define I in
+    #define O out
+#else
+    #define I attribute
+    #define O varying
+#endif
+#ifdef GL_ES
+    precision mediump float;
+#endif
+
+uniform vec2 u_screen_size;
+I vec2 a_pos;
+I vec2 a_tc;
+I vec4 a_srgba;
+O vec4 v_rgba_in_gamma;
+O vec2 v_tc;
+
+void main() {
+    gl_Position = vec4(
+        2.0 * a_pos.x / u_screen_size.x - 1.0,
+        1.0 - 2.0 * a_pos.y / u_screen_size.y,
+        0.0,
+        1.0);
+    v_rgba_in_gamma = a_srgba / 255.0;
+    v_tc = a_tc;
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+#ifdef GL_ES
+    precision mediump float;
+#endif
+
+uniform sampler2D u_sampler;
+
+#if NEW_SHADER_INTERFACE
+    in vec4 v_rgba_in_gamma;
+    in vec2 v_tc;
+    out vec4 f_color;
+    #define gl_FragColor f_color
+    #define texture2D texture
+#else
+    varying vec4 v_rgba_in_gamma;
+    varying vec2 v_tc;
+#endif
+
+vec3 srgb_gamma_from_linear(vec3 rgb) {
+    bvec3 cutoff = lessThan(rgb, vec3(0.0031308));
+    vec3 lower = rgb * vec3(12.92);
+    vec3 higher = vec3(1.055) * pow(rgb, vec3(1.0 / 2.4)) - vec3(0.055);
+    return mix(higher, lower, vec3(cutoff));
+}
+
+void main() {
+    vec4 texture_rgba = texture2D(u_sampler, v_tc);
+#if SRGB_TEXTURES
+    texture_rgba.rgb = srgb_gamma_from_linear(texture_rgba.rgb);
+#endif
+    // Multiplied in gamma space like egui does, text looks wrong otherwise.
+    gl_FragColor = v_rgba_in_gamma * texture_rgba;
+}
+"#;
+
+/// Paints one mesh at a time, each frame from the same GL buffers until it is
+/// given another mesh. The buffers live as long as the GL context.
+///
+/// Shaders that fail to build are reported once on stderr, nothing is painted then.
+#[derive(Clone, Default)]
+pub struct MeshCallback {
+    /// `Some(None)` once the shaders have failed to build.
+    gl: Arc<Mutex<Option<Option<GlMesh>>>>,
+}
+
+impl MeshCallback {
+    /// Shape painting `mesh`, whose vertices are in screen points, clipped to `rect`.
+    pub fn shape(&self, rect: Rect, mesh: Arc<Mesh>) -> Shape {
+        let gl = Arc::clone(&self.gl);
+        let callback = CallbackFn::new(move |info, painter| {
+            let mut gl = gl.lock().unwrap_or_else(PoisonError::into_inner);
+            // SAFETY: egui_glow calls back on its current context, between its own
+            // draws, and restores its state afterwards.
+            unsafe {
+                let gl = gl.get_or_insert_with(|| {
+                    GlMesh::new(painter)
+                        .map_err(|err| eprintln!("cannot build the mesh shaders: {err}"))
+                        .ok()
+                });
+                if let Some(gl) = gl {
+                    gl.paint(painter, &info, &mesh);
+                }
+            }
+        });
+
+        Shape::Callback(PaintCallback {
+            rect,
+            callback: Arc::new(callback),
+        })
+    }
+}
+
+/// Program and buffers holding the last mesh painted.
+struct GlMesh {
+    program: glow::Program,
+    u_screen_size: glow::UniformLocation,
+    u_sampler: glow::UniformLocation,
+    attributes: [(u32, i32, u32, usize); 3],
+    /// `None` where vertex array objects aren't supported, the attributes are
+    /// then pointed at the buffer on each paint.
+    vao: Option<glow::VertexArray>,
+    vbo: glow::Buffer,
+    ebo: glow::Buffer,
+    mesh: Option<Arc<Mesh>>,
+}
+
+impl GlMesh {
+    /// Compiles the shaders the way `egui_glow` does for this context.
+    unsafe fn new(painter: &Painter) -> Result<Self, String> {
+        let gl = painter.gl();
+        let version = ShaderVersion::get(gl);
+        let srgb_textures = version == ShaderVersion::Es300
+            || gl
+                .supported_extensions()
+                .iter()
+                .any(|extension| extension.contains("sRGB"));
+        let header = format!(
+            "{}\n#define NEW_SHADER_INTERFACE {}\n#define SRGB_TEXTURES {}\n",
+            version.version_declaration(),
+            i32::from(version.is_new_shader_interface()),
+            i32::from(srgb_textures),
+        );
+
+        let program = gl.create_program()?;
+        let mut shaders = Vec::new();
+        for (kind, source) in [
+            (glow::VERTEX_SHADER, VERTEX_SHADER),
+            (glow::FRAGMENT_SHADER, FRAGMENT_SHADER),
+        ] {
+            let shader = gl.create_shader(kind)?;
+            gl.shader_source(shader, &format!("{header}{source}"));
+            gl.compile_shader(shader);
+            if !gl.get_shader_compile_status(shader) {
+                return Err(gl.get_shader_info_log(shader));
+            }
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+        gl.link_program(program);
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+        if !gl.get_program_link_status(program) {
+            return Err(gl.get_program_info_log(program));
+        }
+
+        let uniform = |name| {
+            gl.get_uniform_location(program, name)
+                .ok_or_else(|| format!("no uniform `{name}`"))
+        };
+        let attribute = |name| {
+            gl.get_attrib_location(program, name)
+                .ok_or_else(|| format!("no attribute `{name}`"))
+        };
+        let attributes = [
+            (
+                attribute("a_pos")?,
+                2,
+                glow::FLOAT,
+                mem::offset_of!(Vertex, pos),
+            ),
+            (
+                attribute("a_tc")?,
+                2,
+                glow::FLOAT,
+                mem::offset_of!(Vertex, uv),
+            ),
+            (
+                attribute("a_srgba")?,
+                4,
+                glow::UNSIGNED_BYTE,
+                mem::offset_of!(Vertex, color),
+            ),
+        ];
+
+        let mut gl_mesh = Self {
+            program,
+            u_screen_size: uniform("u_screen_size")?,
+            u_sampler: uniform("u_sampler")?,
+            attributes,
+            vao: None,
+            vbo: gl.create_buffer()?,
+            ebo: gl.create_buffer()?,
+            mesh: None,
+        };
+        if supports_vao(gl) {
+            let vao = gl.create_vertex_array()?;
+            gl.bind_vertex_array(Some(vao));
+            gl_mesh.point_attributes(gl);
+            gl.bind_vertex_array(None);
+            gl_mesh.vao = Some(vao);
+        }
+
+        Ok(gl_mesh)
+    }
+
+    unsafe fn point_attributes(&self, gl: &glow::Context) {
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        for (location, size, kind, offset) in self.attributes {
+            gl.vertex_attrib_pointer_f32(
+                location,
+                size,
+                kind,
+                false,
+                mem::size_of::<Vertex>() as i32,
+                offset as i32,
+            );
+            gl.enable_vertex_attrib_array(location);
+        }
+    }
+
+    /// Draws `mesh` over the whole screen, egui has already set the blending and
+    /// the scissor of the clip rect. Its state is restored after the callback.
+    unsafe fn paint(&mut self, painter: &Painter, info: &PaintCallbackInfo, mesh: &Arc<Mesh>) {
+        let gl = painter.gl();
+        let Some(texture) = painter.texture(mesh.texture_id) else {
+            return;
+        };
+
+        match self.vao {
+            Some(vao) => gl.bind_vertex_array(Some(vao)),
+            None => self.point_attributes(gl),
+        }
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.ebo));
+        if !self
+            .mesh
+            .as_ref()
+            .is_some_and(|last| Arc::ptr_eq(last, mesh))
+        {
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes(&mesh.vertices), glow::STATIC_DRAW);
+            gl.buffer_data_u8_slice(
+                glow::ELEMENT_ARRAY_BUFFER,
+                bytes(&mesh.indices),
+                glow::STATIC_DRAW,
+            );
+            self.mesh = Some(Arc::clone(mesh));
+        }
+
+        let [width, height] = info.screen_size_px;
+        gl.viewport(0, 0, width as i32, height as i32);
+        gl.use_program(Some(self.program));
+        gl.uniform_2_f32(
+            Some(&self.u_screen_size),
+            width as f32 / info.pixels_per_point,
+            height as f32 / info.pixels_per_point,
+        );
+        gl.uniform_1_i32(Some(&self.u_sampler), 0);
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+
+        gl.draw_elements(
+            glow::TRIANGLES,
+            mesh.indices.len() as i32,
+            glow::UNSIGNED_INT,
+            0,
+        );
+
+        match self.vao {
+            Some(_) => gl.bind_vertex_array(None),
+            None => {
+                for (location, ..) in self.attributes {
+                    gl.disable_vertex_attrib_array(location);
+                }
+            }
+        }
+    }
+}
+
+/// Whether `egui_glow` draws with a vertex array object on this context. Without
+/// one of its own, a callback would point the attributes of egui's.
+unsafe fn supports_vao(gl: &glow::Context) -> bool {
+    let version = gl.get_parameter_string(glow::VERSION);
+    let extension = |name: &str| {
+        let extensions = gl.supported_extensions();
+        extensions.contains(name) || extensions.contains(&format!("GL_{name}"))
+    };
+
+    if let Some((_, webgl)) = version.rsplit_once("WebGL ") {
+        !webgl.contains("1.0") || extension("OES_vertex_array_object")
+    } else if version.contains("OpenGL ES ") {
+        !version.contains("2.0") || extension("OES_vertex_array_object")
+    } else {
+        !version.starts_with('2') || extension("ARB_vertex_array_object")
+    }
+}
+
+/// The bytes of vertices or indices, for a GL buffer.
+fn bytes<T: Copy>(values: &[T]) -> &[u8] {
+    // SAFETY: only called with `u32` and `Vertex`, a `repr(C)` struct of floats and
+    // bytes without padding.
+    unsafe { std::slice::from_raw_parts(values.as_ptr().cast(), mem::size_of_val(values)) }
+}
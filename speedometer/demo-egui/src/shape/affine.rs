@@ -0,0 +1,207 @@
+use eframe::egui::{Pos2, Vec2};
+use eframe::emath::Rot2;
+
+/// Relative tolerance of the checks telling which kind of map an [`Affine`] is.
+const EPSILON: f32 = 1e-5;
+
+/// Affine map of the screen, `p ↦ x·p.x + y·p.y + translation`.
+///
+/// Angles are in radians and turn clockwise on screen, where y points down, like
+/// [`Rot2`]. Maps combine with [`Affine::then`] and are moved to a pivot with
+/// [`Affine::around`], e.g. a rotation about the centre of a dial:
+///
+/// ```
+/// # use demo_egui::shape::Affine;
+/// # use eframe::egui::{pos2, Vec2};
+/// let tilt = Affine::rotation(std::f32::consts::FRAC_PI_2).around(pos2(100.0, 100.0));
+/// let tip = tilt.apply(pos2(100.0, 20.0));
+/// assert!((tip - pos2(180.0, 100.0)).length() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    /// Where the unit x vector goes.
+    pub x: Vec2,
+    /// Where the unit y vector goes.
+    pub y: Vec2,
+    pub translation: Vec2,
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self {
+        x: Vec2::X,
+        y: Vec2::Y,
+        translation: Vec2::ZERO,
+    };
+
+    pub fn translation(offset: Vec2) -> Self {
+        Self {
+            translation: offset,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn rotation(angle: f32) -> Self {
+        let rotation = Rot2::from_angle(angle);
+        Self {
+            x: rotation * Vec2::X,
+            y: rotation * Vec2::Y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Scales by `factor.x` horizontally and `factor.y` vertically, negative
+    /// factors mirror.
+    pub fn scaling(factor: Vec2) -> Self {
+        Self {
+            x: Vec2::new(factor.x, 0.0),
+            y: Vec2::new(0.0, factor.y),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Leans vertical lines by `angles.x` and horizontal lines by `angles.y`.
+    pub fn skew(angles: Vec2) -> Self {
+        Self {
+            x: Vec2::new(1.0, angles.y.tan()),
+            y: Vec2::new(angles.x.tan(), 1.0),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// The same map with `pivot` as its fixed point instead of the origin.
+    pub fn around(self, pivot: Pos2) -> Self {
+        Self::translation(-pivot.to_vec2())
+            .then(self)
+            .then(Self::translation(pivot.to_vec2()))
+    }
+
+    /// `self` followed by `next`.
+    pub fn then(self, next: Affine) -> Self {
+        Self {
+            x: next.apply_vec(self.x),
+            y: next.apply_vec(self.y),
+            translation: next.apply(self.translation.to_pos2()).to_vec2(),
+        }
+    }
+
+    pub fn apply(&self, pos: Pos2) -> Pos2 {
+        (self.x * pos.x + self.y * pos.y + self.translation).to_pos2()
+    }
+
+    /// Maps a direction or an offset, which the translation leaves alone.
+    pub fn apply_vec(&self, vec: Vec2) -> Vec2 {
+        self.x * vec.x + self.y * vec.y
+    }
+
+    /// Factor areas are scaled by, negative when the map mirrors.
+    pub fn determinant(&self) -> f32 {
+        self.x.x * self.y.y - self.y.x * self.x.y
+    }
+
+    /// `None` when the map flattens the plane onto a line or a point.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant.abs() <= f32::EPSILON * self.norm() {
+            return None;
+        }
+
+        let x = Vec2::new(self.y.y, -self.x.y) / determinant;
+        let y = Vec2::new(-self.y.x, self.x.x) / determinant;
+        let linear = Self {
+            x,
+            y,
+            ..Self::IDENTITY
+        };
+        Some(Self {
+            translation: -linear.apply_vec(self.translation),
+            ..linear
+        })
+    }
+
+    pub fn is_translation(&self) -> bool {
+        self.x == Vec2::X && self.y == Vec2::Y
+    }
+
+    /// Whether horizontal and vertical lines stay so, i.e. no rotation nor skew.
+    pub fn is_axis_aligned(&self) -> bool {
+        self.x.y.abs() <= EPSILON * self.norm() && self.y.x.abs() <= EPSILON * self.norm()
+    }
+
+    /// The angle and the scale factor when the map only rotates, scales evenly
+    /// and moves, so that shapes keep their proportions without being mirrored.
+    pub fn as_similarity(&self) -> Option<(f32, f32)> {
+        let tolerance = EPSILON * self.norm();
+        // A quarter turn of x, which is where y goes under such a map.
+        let turned_x = Vec2::new(-self.x.y, self.x.x);
+        let similar = (turned_x - self.y).length() <= tolerance && self.determinant() > 0.0;
+
+        similar.then(|| (self.x.angle(), self.x.length()))
+    }
+
+    /// Geometric mean of the scale factors, what a stroke width is scaled by.
+    pub fn mean_scale(&self) -> f32 {
+        self.determinant().abs().sqrt()
+    }
+
+    fn norm(&self) -> f32 {
+        self.x.length().max(self.y.length()).max(1.0)
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::pos2;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Pos2, expected: Pos2) {
+        assert!(
+            (actual - expected).length() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn composes_around_a_pivot() {
+        let pivot = pos2(10.0, 20.0);
+        let map = Affine::scaling(Vec2::new(2.0, 3.0))
+            .then(Affine::rotation(FRAC_PI_2))
+            .around(pivot);
+
+        assert_near(map.apply(pivot), pivot);
+        // (1, 0) from the pivot is scaled to (2, 0), then turned to (0, 2).
+        assert_near(map.apply(pos2(11.0, 20.0)), pos2(10.0, 22.0));
+        assert_eq!(map.determinant(), 6.0);
+        assert_near(
+            map.inverse().unwrap().apply(map.apply(pos2(-4.0, 7.0))),
+            pos2(-4.0, 7.0),
+        );
+        assert_eq!(Affine::scaling(Vec2::new(0.0, 1.0)).inverse(), None);
+
+        let skew = Affine::skew(Vec2::new(FRAC_PI_2 / 2.0, 0.0));
+        assert_near(skew.apply(pos2(0.0, 1.0)), pos2(1.0, 1.0));
+        assert!(!skew.is_axis_aligned());
+    }
+
+    #[test]
+    fn tells_similarities_apart() {
+        let (angle, scale) = Affine::rotation(0.5)
+            .then(Affine::scaling(Vec2::splat(2.0)))
+            .then(Affine::translation(Vec2::new(3.0, 4.0)))
+            .as_similarity()
+            .unwrap();
+        assert!((angle - 0.5).abs() < 1e-6);
+        assert!((scale - 2.0).abs() < 1e-6);
+
+        assert_eq!(Affine::scaling(Vec2::new(1.0, 2.0)).as_similarity(), None);
+        assert_eq!(Affine::scaling(Vec2::new(-1.0, 1.0)).as_similarity(), None);
+        assert_eq!(Affine::skew(Vec2::new(0.1, 0.0)).as_similarity(), None);
+        assert!(Affine::translation(Vec2::X).is_translation());
+    }
+}
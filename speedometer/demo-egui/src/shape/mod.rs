@@ -1 +1,8 @@
-pub mod rotate;
+//! Affine transforms of egui shapes: rotate, scale, skew and move them around a
+//! pivot.
+
+mod affine;
+mod transform;
+
+pub use affine::Affine;
+pub use transform::{Transform, TransformError};
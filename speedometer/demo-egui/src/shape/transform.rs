@@ -0,0 +1,409 @@
+use super::Affine;
+use eframe::egui::epaint::{
+    tessellator::path::rounded_rectangle, CircleShape, ColorMode, EllipseShape, Mesh, PathShape,
+    PathStroke, RectShape, Shape, Stroke, TextShape,
+};
+use eframe::egui::{Pos2, Rect, Rounding, Vec2};
+use std::error::Error;
+use std::fmt;
+
+/// Affine transforms of egui shapes, on top of the translation egui has.
+///
+/// Shapes egui cannot express after the transform are converted: a skewed circle
+/// becomes a polygon and a rotated rectangle a path, or a mesh when textured.
+/// What cannot be converted is refused with a [`TransformError`] and leaves the
+/// shape as it was.
+pub trait Transform {
+    fn transform(&mut self, affine: &Affine) -> Result<(), TransformError>;
+
+    fn rotate_around(&mut self, angle: f32, pivot: Pos2) -> Result<(), TransformError> {
+        self.transform(&Affine::rotation(angle).around(pivot))
+    }
+
+    fn scale_around(&mut self, factor: Vec2, pivot: Pos2) -> Result<(), TransformError> {
+        self.transform(&Affine::scaling(factor).around(pivot))
+    }
+
+    fn skew_around(&mut self, angles: Vec2, pivot: Pos2) -> Result<(), TransformError> {
+        self.transform(&Affine::skew(angles).around(pivot))
+    }
+}
+
+impl Transform for Shape {
+    fn transform(&mut self, affine: &Affine) -> Result<(), TransformError> {
+        *self = transformed(self, affine)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformError {
+    /// Text is laid out at its font size, it can only be moved and rotated.
+    Text,
+    /// The blur of a rectangle only follows axis-aligned transforms.
+    BlurredRect,
+    /// Textured rectangles with rounded corners only follow axis-aligned
+    /// transforms that don't mirror them.
+    TexturedRect,
+    /// Gradient colours are computed in screen space, such strokes can only be
+    /// moved.
+    Gradient,
+    /// Paint callbacks draw into a screen rectangle, they can only be moved.
+    Callback,
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransformError::Text => "text can only be moved and rotated",
+            TransformError::BlurredRect => "blurred rectangles cannot be rotated nor skewed",
+            TransformError::TexturedRect => {
+                "textured rectangles with rounded corners cannot be rotated, skewed nor mirrored"
+            }
+            TransformError::Gradient => "gradient strokes can only be moved",
+            TransformError::Callback => "paint callbacks can only be moved",
+        })
+    }
+}
+
+impl Error for TransformError {}
+
+fn transformed(shape: &Shape, affine: &Affine) -> Result<Shape, TransformError> {
+    Ok(match shape {
+        Shape::Noop => Shape::Noop,
+        Shape::Vec(shapes) => Shape::Vec(
+            shapes
+                .iter()
+                .map(|shape| transformed(shape, affine))
+                .collect::<Result<_, _>>()?,
+        ),
+        Shape::LineSegment { points, stroke } => Shape::LineSegment {
+            points: points.map(|point| affine.apply(point)),
+            stroke: path_stroke(stroke, affine)?,
+        },
+        Shape::Path(path) => Shape::Path(PathShape {
+            points: map_points(&path.points, path.closed, affine),
+            stroke: path_stroke(&path.stroke, affine)?,
+            ..path.clone()
+        }),
+        Shape::QuadraticBezier(bezier) => {
+            let mut bezier = bezier.clone();
+            bezier.points = map_points(&bezier.points, bezier.closed, affine)
+                .try_into()
+                .expect("as many points");
+            bezier.stroke = path_stroke(&bezier.stroke, affine)?;
+            Shape::QuadraticBezier(bezier)
+        }
+        Shape::CubicBezier(bezier) => {
+            let mut bezier = bezier.clone();
+            bezier.points = map_points(&bezier.points, bezier.closed, affine)
+                .try_into()
+                .expect("as many points");
+            bezier.stroke = path_stroke(&bezier.stroke, affine)?;
+            Shape::CubicBezier(bezier)
+        }
+        Shape::Circle(circle) => match affine.as_similarity() {
+            Some((_, scale)) => Shape::Circle(CircleShape {
+                center: affine.apply(circle.center),
+                radius: circle.radius * scale,
+                stroke: stroke(circle.stroke, affine),
+                ..*circle
+            }),
+            None => ellipse(
+                circle.center,
+                Vec2::splat(circle.radius),
+                circle.fill,
+                circle.stroke,
+                affine,
+            ),
+        },
+        Shape::Ellipse(shape) => {
+            ellipse(shape.center, shape.radius, shape.fill, shape.stroke, affine)
+        }
+        Shape::Rect(rect) => self::rect(rect, affine)?,
+        Shape::Text(text) => {
+            let (angle, scale) = affine.as_similarity().ok_or(TransformError::Text)?;
+            if (scale - 1.0).abs() > 1e-4 {
+                return Err(TransformError::Text);
+            }
+            Shape::Text(TextShape {
+                pos: affine.apply(text.pos),
+                angle: text.angle + angle,
+                ..text.clone()
+            })
+        }
+        Shape::Mesh(mesh) => {
+            let mut mesh = mesh.clone();
+            for vertex in &mut mesh.vertices {
+                vertex.pos = affine.apply(vertex.pos);
+            }
+            Shape::Mesh(mesh)
+        }
+        Shape::Callback(callback) => {
+            if !affine.is_translation() {
+                return Err(TransformError::Callback);
+            }
+            let mut callback = callback.clone();
+            callback.rect = callback.rect.translate(affine.translation);
+            Shape::Callback(callback)
+        }
+    })
+}
+
+/// Mapped points. The stroke of a closed path goes inside or outside depending on
+/// the winding, which a mirroring map reverses, so the order is reversed too.
+fn map_points(points: &[Pos2], closed: bool, affine: &Affine) -> Vec<Pos2> {
+    let mut points: Vec<_> = points.iter().map(|point| affine.apply(*point)).collect();
+    if closed && affine.determinant() < 0.0 {
+        points.reverse();
+    }
+    points
+}
+
+fn stroke(stroke: Stroke, affine: &Affine) -> Stroke {
+    Stroke::new(stroke.width * affine.mean_scale(), stroke.color)
+}
+
+fn path_stroke(stroke: &PathStroke, affine: &Affine) -> Result<PathStroke, TransformError> {
+    if matches!(stroke.color, ColorMode::UV(_)) && !affine.is_translation() {
+        return Err(TransformError::Gradient);
+    }
+    Ok(PathStroke {
+        width: stroke.width * affine.mean_scale(),
+        ..stroke.clone()
+    })
+}
+
+/// Ellipses stay so under maps without rotation nor skew, other maps turn them
+/// into polygons.
+fn ellipse(
+    center: Pos2,
+    radius: Vec2,
+    fill: eframe::egui::Color32,
+    outline: Stroke,
+    affine: &Affine,
+) -> Shape {
+    if affine.is_axis_aligned() {
+        return Shape::Ellipse(EllipseShape {
+            center: affine.apply(center),
+            radius: Vec2::new(radius.x * affine.x.x.abs(), radius.y * affine.y.y.abs()),
+            fill,
+            stroke: stroke(outline, affine),
+        });
+    }
+
+    // Enough segments to stay within a tenth of a pixel of the curve.
+    let extent = affine
+        .apply_vec(Vec2::new(radius.x, 0.0))
+        .length()
+        .max(affine.apply_vec(Vec2::new(0.0, radius.y)).length());
+    let segments = (extent.sqrt() * 8.0).clamp(16.0, 256.0) as usize;
+    let points: Vec<_> = (0..segments)
+        .map(|i| {
+            let (sin, cos) = (i as f32 * std::f32::consts::TAU / segments as f32).sin_cos();
+            center + Vec2::new(radius.x * cos, radius.y * sin)
+        })
+        .collect();
+
+    Shape::Path(PathShape {
+        points: map_points(&points, true, affine),
+        closed: true,
+        fill,
+        stroke: stroke(outline, affine).into(),
+    })
+}
+
+fn rect(shape: &RectShape, affine: &Affine) -> Result<Shape, TransformError> {
+    let textured = shape.uv.is_positive();
+    let mirrored = affine.determinant() < 0.0;
+
+    if affine.is_axis_aligned() && !(textured && mirrored) {
+        let (sx, sy) = (affine.x.x, affine.y.y);
+        let mut rounding = shape.rounding * sx.abs().min(sy.abs());
+        if sx < 0.0 {
+            rounding = Rounding {
+                nw: rounding.ne,
+                ne: rounding.nw,
+                sw: rounding.se,
+                se: rounding.sw,
+            };
+        }
+        if sy < 0.0 {
+            rounding = Rounding {
+                nw: rounding.sw,
+                ne: rounding.se,
+                sw: rounding.nw,
+                se: rounding.ne,
+            };
+        }
+
+        return Ok(Shape::Rect(RectShape {
+            rect: Rect::from_two_pos(affine.apply(shape.rect.min), affine.apply(shape.rect.max)),
+            rounding,
+            stroke: stroke(shape.stroke, affine),
+            blur_width: shape.blur_width * affine.mean_scale(),
+            ..*shape
+        }));
+    }
+
+    if shape.blur_width > 0.0 {
+        return Err(TransformError::BlurredRect);
+    }
+    if textured && shape.rounding != Rounding::ZERO {
+        return Err(TransformError::TexturedRect);
+    }
+
+    let mut points = Vec::new();
+    rounded_rectangle(&mut points, shape.rect, shape.rounding);
+    // Like egui, the outline of a rectangle is painted outside of it.
+    let outline = PathStroke::from(stroke(shape.stroke, affine)).outside();
+
+    if !textured {
+        return Ok(Shape::Path(PathShape {
+            points: map_points(&points, true, affine),
+            closed: true,
+            fill: shape.fill,
+            stroke: outline,
+        }));
+    }
+
+    let mut mesh = Mesh::with_texture(shape.fill_texture_id);
+    mesh.add_rect_with_uv(shape.rect, shape.uv, shape.fill);
+    let mesh = transformed(&Shape::Mesh(mesh), affine)?;
+    if shape.stroke.is_empty() {
+        return Ok(mesh);
+    }
+    Ok(Shape::Vec(vec![
+        mesh,
+        Shape::Path(PathShape {
+            points: map_points(&points, true, affine),
+            closed: true,
+            fill: Default::default(),
+            stroke: outline,
+        }),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::epaint::CubicBezierShape;
+    use eframe::egui::{pos2, Color32, FontId};
+    use std::f32::consts::FRAC_PI_2;
+
+    const PIVOT: Pos2 = pos2(10.0, 10.0);
+
+    fn assert_near(actual: Pos2, expected: Pos2) {
+        assert!(
+            (actual - expected).length() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn maps_points_and_strokes() {
+        let mut line =
+            Shape::line_segment([pos2(10.0, 0.0), PIVOT], Stroke::new(2.0, Color32::RED));
+        line.rotate_around(FRAC_PI_2, PIVOT).unwrap();
+        let Shape::LineSegment { points, stroke } = &line else {
+            panic!("{line:?}");
+        };
+        assert_near(points[0], pos2(20.0, 10.0));
+        assert_near(points[1], PIVOT);
+        assert_eq!(stroke.width, 2.0);
+
+        let mut bezier = Shape::CubicBezier(CubicBezierShape::from_points_stroke(
+            [
+                pos2(0.0, 0.0),
+                pos2(1.0, 0.0),
+                pos2(2.0, 1.0),
+                pos2(3.0, 3.0),
+            ],
+            false,
+            Color32::TRANSPARENT,
+            Stroke::new(1.0, Color32::RED),
+        ));
+        bezier.scale_around(Vec2::splat(2.0), Pos2::ZERO).unwrap();
+        let Shape::CubicBezier(bezier) = bezier else {
+            panic!("{bezier:?}");
+        };
+        assert_eq!(bezier.points[3], pos2(6.0, 6.0));
+        assert_eq!(bezier.stroke.width, 2.0);
+    }
+
+    #[test]
+    fn converts_what_egui_cannot_express() {
+        let mut circle = Shape::circle_filled(PIVOT, 5.0, Color32::RED);
+        circle.rotate_around(1.0, Pos2::ZERO).unwrap();
+        assert!(
+            matches!(circle, Shape::Circle(CircleShape { radius, .. }) if (radius - 5.0).abs() < 1e-4)
+        );
+        circle
+            .scale_around(Vec2::new(2.0, 1.0), Pos2::ZERO)
+            .unwrap();
+        assert!(matches!(circle, Shape::Ellipse(_)));
+        circle.skew_around(Vec2::new(0.3, 0.0), PIVOT).unwrap();
+        assert!(matches!(
+            circle,
+            Shape::Path(PathShape { closed: true, .. })
+        ));
+
+        let square = Rect::from_min_max(Pos2::ZERO, PIVOT);
+        let mut rect = Shape::rect_filled(square, 2.0, Color32::RED);
+        rect.scale_around(Vec2::new(-1.0, 2.0), Pos2::ZERO).unwrap();
+        let Shape::Rect(mirrored) = &rect else {
+            panic!("{rect:?}");
+        };
+        assert_eq!(
+            mirrored.rect,
+            Rect::from_min_max(pos2(-10.0, 0.0), pos2(0.0, 20.0))
+        );
+        rect.rotate_around(FRAC_PI_2, Pos2::ZERO).unwrap();
+        assert!(matches!(rect, Shape::Path(PathShape { closed: true, .. })));
+    }
+
+    #[test]
+    fn refuses_what_it_cannot_convert() {
+        let ctx = eframe::egui::Context::default();
+        let mut text = Shape::Noop;
+        let _ = ctx.run(Default::default(), |ctx| {
+            text = ctx.fonts(|fonts| {
+                Shape::text(
+                    fonts,
+                    PIVOT,
+                    eframe::egui::Align2::LEFT_TOP,
+                    "km/h",
+                    FontId::default(),
+                    Color32::WHITE,
+                )
+            });
+        });
+
+        let mut shapes = Shape::Vec(vec![Shape::circle_filled(PIVOT, 5.0, Color32::RED), text]);
+        let before = format!("{shapes:?}");
+        assert_eq!(
+            shapes.scale_around(Vec2::splat(2.0), PIVOT),
+            Err(TransformError::Text)
+        );
+        assert_eq!(format!("{shapes:?}"), before);
+
+        shapes.rotate_around(FRAC_PI_2, Pos2::ZERO).unwrap();
+        let Shape::Vec(shapes) = &shapes else {
+            panic!("{shapes:?}");
+        };
+        let Shape::Text(text) = &shapes[1] else {
+            panic!("{shapes:?}");
+        };
+        assert_eq!(text.angle, FRAC_PI_2);
+        assert_near(text.pos, pos2(-10.0, 10.0));
+
+        let mut blurred = Shape::Rect(RectShape {
+            blur_width: 4.0,
+            ..RectShape::filled(Rect::from_min_max(Pos2::ZERO, PIVOT), 0.0, Color32::RED)
+        });
+        assert_eq!(
+            blurred.rotate_around(0.5, PIVOT),
+            Err(TransformError::BlurredRect)
+        );
+    }
+}
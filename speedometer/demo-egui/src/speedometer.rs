@@ -1,153 +1,761 @@
-use crate::shape::rotate::Rotate;
+use crate::mesh_callback::MeshCallback;
+use crate::shape::Affine;
+use crate::SpeedometerStyle;
 use eframe::egui::{
-    self,
-    epaint::{CircleShape, Shape},
-    Align2, Color32, FontFamily, FontId, Pos2, Response, Stroke, Ui, Vec2, Widget,
+    epaint::{CircleShape, Mesh, Shape, Tessellator},
+    Align2, Color32, EventFilter, FontId, Id, Key, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2,
+    Widget, WidgetInfo,
 };
-use std::f32::consts::PI;
+use eframe::emath::Numeric;
+use gauge_core::{format_value, tint, Needle, NeedleDynamics, Scale, Tick, TickSpec, Zone};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::Not;
-
-const BACKGROUND_COLOR: Color32 = Color32::from_rgb(0x20u8, 0x22u8, 0x25u8);
-const BACKGROUND_BORDER_COLOR: Color32 = Color32::from_rgb(0x44u8, 0x44u8, 0x46u8);
-const TICK_COLOR: Color32 = Color32::from_rgb(0xFF, 0x45, 0x3A);
-const SECTOR_DEGREES: f32 = 100.0;
+use std::sync::Arc;
 
 pub trait SpeedometerUi {
-    fn speedometer(&mut self, speed: u32, min_speed: u32, max_speed: u32) -> Response;
+    fn speedometer<Num: Numeric>(&mut self, speed: Num, min_speed: Num, max_speed: Num)
+        -> Response;
 }
 
 impl SpeedometerUi for Ui {
-    fn speedometer(&mut self, speed: u32, min_speed: u32, max_speed: u32) -> Response {
-        Speedometer {
-            speed,
-            min_speed,
-            max_speed,
-        }
-        .ui(self)
+    fn speedometer<Num: Numeric>(
+        &mut self,
+        speed: Num,
+        min_speed: Num,
+        max_speed: Num,
+    ) -> Response {
+        Speedometer::new(speed, min_speed, max_speed).ui(self)
     }
 }
 
-struct Speedometer {
-    speed: u32,
-    min_speed: u32,
-    max_speed: u32,
+type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<f64>) -> f64>;
+
+pub struct Speedometer<'a> {
+    speed: f64,
+    get_set_value: Option<GetSetValue<'a>>,
+    step: Option<f64>,
+    scale: Scale,
+    ticks: TickSpec,
+    zones: Vec<Zone<Color32>>,
+    dynamics: Option<NeedleDynamics>,
+    decimals: usize,
+    formatter: Option<Box<dyn 'a + Fn(f64) -> String>>,
+    formatter_generation: u64,
+    style: Option<SpeedometerStyle>,
+    paint_callback: bool,
+}
+
+impl<'a> Speedometer<'a> {
+    pub fn new<Num: Numeric>(speed: Num, min_speed: Num, max_speed: Num) -> Self {
+        let (min_speed, max_speed) = (min_speed.to_f64() as f32, max_speed.to_f64() as f32);
+
+        Self {
+            speed: speed.to_f64(),
+            get_set_value: None,
+            step: None,
+            scale: Scale::new(min_speed, max_speed),
+            ticks: TickSpec::for_range(min_speed, max_speed),
+            zones: Vec::new(),
+            dynamics: Some(NeedleDynamics::for_range(min_speed, max_speed)),
+            decimals: 0,
+            formatter: None,
+            formatter_generation: 0,
+            style: None,
+            paint_callback: true,
+        }
+    }
+
+    /// A gauge that sets `value`, like [`egui::Slider`](eframe::egui::Slider): drag the needle, scroll over
+    /// the dial or use the arrow keys once it has the focus. The response is
+    /// [`Response::changed`] when the value changes.
+    pub fn editable<Num: Numeric>(value: &'a mut Num, min_speed: Num, max_speed: Num) -> Self {
+        let speed = value.to_f64();
+        let mut speedometer = Self::new(speed, min_speed.to_f64(), max_speed.to_f64());
+        speedometer.get_set_value = Some(Box::new(move |set: Option<f64>| {
+            if let Some(set) = set {
+                *value = Num::from_f64(set);
+            }
+            value.to_f64()
+        }));
+        speedometer
+    }
+
+    /// Change of an editable value per arrow key press or wheel notch, by default
+    /// the minor tick step. Dragging the needle is not stepped, a step that is not
+    /// finite is ignored.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step.is_finite().then_some(step).or(self.step);
+        self
+    }
+
+    /// Value the needle rests at, marked with a detent. Use `0` for bipolar scales.
+    pub fn origin<Num: Numeric>(mut self, origin: Num) -> Self {
+        self.scale = self.scale.with_origin(origin.to_f64() as f32);
+        self
+    }
+
+    /// Tick layout, by default about ten labeled major ticks over the range.
+    pub fn ticks(mut self, ticks: TickSpec) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Adds a coloured band, later zones are drawn on top of earlier ones.
+    pub fn zone(mut self, zone: Zone<Color32>) -> Self {
+        self.zones.push(zone);
+        self
+    }
+
+    /// How the needle follows the speed, by default it crosses the whole dial in half a second.
+    pub fn dynamics(mut self, dynamics: NeedleDynamics) -> Self {
+        self.dynamics = Some(dynamics);
+        self
+    }
+
+    /// Turns the needle animation off, the needle then jumps to the speed.
+    pub fn animate(mut self, animate: bool) -> Self {
+        let default = NeedleDynamics::for_range(self.scale.min(), self.scale.max());
+        self.dynamics = animate.then(|| self.dynamics.unwrap_or(default));
+        self
+    }
+
+    /// Number of decimals shown in the tick labels.
+    pub fn fixed_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Formats the tick labels, e.g. to add a unit. Overrides [`Self::fixed_decimals`].
+    ///
+    /// The labels are drawn with the cached dial and a formatter can't be compared,
+    /// so they are only formatted again when [`Self::formatter_generation`] changes.
+    pub fn custom_formatter(mut self, formatter: impl 'a + Fn(f64) -> String) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Tells the dial cache that the labels of the custom formatter have changed,
+    /// e.g. with a unit it captures. Pass a new generation each time, `0` by default.
+    pub fn formatter_generation(mut self, generation: u64) -> Self {
+        self.formatter_generation = generation;
+        self
+    }
+
+    /// Colours and proportions, by default those of [`SpeedometerStyle::from_visuals`]
+    /// with the visuals of the `Ui`.
+    pub fn style(mut self, style: SpeedometerStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Paints the cached dial through a glow paint callback, so that frames don't
+    /// copy its mesh. On by default, turn it off with another renderer than eframe's
+    /// glow one, e.g. to rasterise the tessellated output.
+    pub fn paint_callback(mut self, paint_callback: bool) -> Self {
+        self.paint_callback = paint_callback;
+        self
+    }
+
+    fn format_label(&self, value: f32) -> String {
+        match &self.formatter {
+            Some(formatter) => formatter(value as f64),
+            None => format_value(value as f64, self.decimals),
+        }
+    }
 }
 
-impl Widget for Speedometer {
-    fn ui(self, ui: &mut Ui) -> Response {
-        let (rect, response) =
-            ui.allocate_exact_size(ui.available_size(), egui::Sense::focusable_noninteractive());
+impl Widget for Speedometer<'_> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let sense = if self.get_set_value.is_some() {
+            Sense::click_and_drag()
+        } else {
+            Sense::focusable_noninteractive()
+        };
+        let editable = self.get_set_value.is_some();
+        let (rect, mut response) = ui.allocate_exact_size(ui.available_size(), sense);
+        let dragged = self.edit(ui, &mut response, rect);
+
+        let speed = self.speed;
+        response.widget_info(|| WidgetInfo::slider(ui.is_enabled(), speed, ""));
+        let response = if editable {
+            response.on_hover_text(exact_value(speed))
+        } else {
+            response
+        };
 
         if ui.is_rect_visible(rect).not() {
             return response;
         };
 
+        let style = self
+            .style
+            .take()
+            .unwrap_or_else(|| SpeedometerStyle::from_visuals(ui.visuals()));
         let radius = rect.width().min(rect.height()) / 2.0;
         let center = rect.center();
+        let needle = self.needle_value(ui, response.id, dragged);
 
-        draw_background(ui, center, radius);
-        self.draw_ticks(ui, center, radius, radius);
-        self.draw_needle(ui, center, radius);
+        ui.painter().add(self.dial(ui, &style, response.id, rect));
+        self.draw_needle(ui, &style, needle, center, radius);
 
         response
     }
 }
 
-impl Speedometer {
-    fn draw_needle(&self, ui: &mut Ui, center: Pos2, scale_by_radius: f32) {
-        let mut line = Shape::line_segment(
-            [
-                Pos2::new(0.0, 0.12 * scale_by_radius),
-                Pos2::new(0.0, -0.88 * scale_by_radius),
-            ],
-            Stroke::new(scale_by_radius / 60.0, TICK_COLOR),
+/// The dial tessellated once, kept in the widget memory until [`Speedometer::dial_key`]
+/// changes. The callback outlives the mesh, so that a new one goes into the same
+/// GL buffers.
+#[derive(Clone)]
+struct DialCache {
+    key: u64,
+    mesh: Arc<Mesh>,
+    callback: MeshCallback,
+}
+
+impl Speedometer<'_> {
+    /// Change per arrow key press or wheel notch: the given step, else the minor
+    /// tick step, else a hundredth of the range when the ticks have no step.
+    fn key_step(&self) -> f64 {
+        let minor = self.ticks.minor_step() as f64;
+        let range = (self.scale.max() - self.scale.min()).abs() as f64;
+
+        self.step.unwrap_or(if minor.is_finite() && minor > 0.0 {
+            minor
+        } else {
+            range / 100.0
+        })
+    }
+
+    /// Applies the drag, scroll and key input of an editable gauge to its value.
+    /// Returns whether the needle is being dragged, so that it follows the pointer
+    /// without easing. A gauge whose bounds are not finite can't be edited.
+    fn edit(&mut self, ui: &Ui, response: &mut Response, rect: Rect) -> bool {
+        let (min, max) = (self.scale.min() as f64, self.scale.max() as f64);
+        let step = self.key_step();
+        let Some(get_set_value) = self
+            .get_set_value
+            .as_mut()
+            .filter(|_| min.is_finite() && max.is_finite())
+        else {
+            return false;
+        };
+        let current = get_set_value(None);
+        let mut value = current;
+
+        let pointer = response.interact_pointer_pos();
+        if let Some(pointer) = pointer {
+            let offset = pointer - rect.center();
+            value = self.scale.value_at(offset.x, offset.y) as f64;
+        }
+
+        if response.hovered() {
+            // A step per wheel notch, which egui turns into this many points.
+            let notch = ui.ctx().options(|options| options.line_scroll_speed);
+            let scroll = ui.input_mut(|input| {
+                // Keeps a surrounding scroll area still.
+                input.smooth_scroll_delta = Vec2::ZERO;
+                input.raw_scroll_delta.y
+            });
+            value += step * (scroll / notch) as f64;
+        }
+
+        if response.has_focus() {
+            ui.memory_mut(|memory| {
+                memory.set_focus_lock_filter(
+                    response.id,
+                    EventFilter {
+                        horizontal_arrows: true,
+                        vertical_arrows: true,
+                        ..Default::default()
+                    },
+                )
+            });
+            let presses = ui.input(|input| {
+                input.num_presses(Key::ArrowUp) as i32 + input.num_presses(Key::ArrowRight) as i32
+                    - input.num_presses(Key::ArrowDown) as i32
+                    - input.num_presses(Key::ArrowLeft) as i32
+            });
+            value += step * presses as f64;
+        }
+
+        let value = value.clamp(min.min(max), max.max(min));
+        if value != current && !value.is_nan() {
+            get_set_value(Some(value));
+            response.mark_changed();
+        }
+        self.speed = get_set_value(None);
+
+        pointer.is_some()
+    }
+
+    /// Everything but the needle as one mesh, tessellated again only when the rect,
+    /// the theme or the scale changes.
+    ///
+    /// egui 0.29 only takes a mesh shape by value, so without the paint callback
+    /// the shape gets a copy of the mesh.
+    fn dial(&self, ui: &Ui, style: &SpeedometerStyle, id: Id, rect: Rect) -> Shape {
+        let id = id.with("dial");
+        let key = self.dial_key(ui, style, rect);
+        let cached = ui.data(|data| data.get_temp::<DialCache>(id));
+
+        let cache = match cached {
+            Some(cache) if cache.key == key => cache,
+            cached => {
+                let cache = DialCache {
+                    key,
+                    mesh: Arc::new(self.tessellate_dial(ui, style, rect)),
+                    callback: cached.map(|cache| cache.callback).unwrap_or_default(),
+                };
+                ui.data_mut(|data| data.insert_temp(id, cache.clone()));
+                cache
+            }
+        };
+
+        if self.paint_callback {
+            cache.callback.shape(rect, cache.mesh)
+        } else {
+            Shape::mesh(Mesh::clone(&cache.mesh))
+        }
+    }
+
+    /// Hash of the inputs of the dial: where it is, its style, scale, ticks, zones
+    /// and label format. The font atlas is hashed since the glyphs move when egui
+    /// starts a new one.
+    fn dial_key(&self, ui: &Ui, style: &SpeedometerStyle, rect: Rect) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        let (atlas, atlas_size) = ui.fonts(|fonts| {
+            (
+                Arc::as_ptr(&fonts.texture_atlas()) as usize,
+                fonts.font_image_size(),
+            )
+        });
+        (atlas, atlas_size).hash(&mut hasher);
+        ui.ctx().pixels_per_point().to_bits().hash(&mut hasher);
+        style.hash(&mut hasher);
+        [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+            .map(f32::to_bits)
+            .hash(&mut hasher);
+
+        let scale = &self.scale;
+        [
+            scale.min(),
+            scale.max(),
+            scale.start_angle(),
+            scale.sweep_angle(),
+        ]
+        .map(f32::to_bits)
+        .hash(&mut hasher);
+        scale.origin().map(f32::to_bits).hash(&mut hasher);
+        let ticks = &self.ticks;
+        ticks.major_step().to_bits().hash(&mut hasher);
+        (
+            ticks.minor_subdivisions(),
+            ticks.label_every(),
+            ticks.end_caps(),
+        )
+            .hash(&mut hasher);
+        for zone in &self.zones {
+            [zone.from, zone.to, zone.thickness]
+                .map(f32::to_bits)
+                .hash(&mut hasher);
+            zone.color.hash(&mut hasher);
+        }
+
+        self.formatter
+            .as_ref()
+            .map(|_| self.formatter_generation)
+            .hash(&mut hasher);
+        self.decimals.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Lays the dial out and tessellates it the way egui does at the end of the frame.
+    fn tessellate_dial(&self, ui: &Ui, style: &SpeedometerStyle, rect: Rect) -> Mesh {
+        let radius = rect.width().min(rect.height()) / 2.0;
+        let center = rect.center();
+
+        let mut shapes = vec![background(style, center, radius)];
+        if style.cap_radius > 0.0 {
+            shapes.push(Shape::circle_filled(
+                center,
+                style.cap_radius * radius,
+                style.cap_color,
+            ));
+        }
+        self.add_zones(style, &mut shapes, center, radius);
+        self.add_ticks(ui, style, &mut shapes, center, radius);
+
+        let ctx = ui.ctx();
+        let (font_tex_size, prepared_discs) = ui.fonts(|fonts| {
+            let atlas = fonts.texture_atlas();
+            let atlas = atlas.lock();
+            (atlas.size(), atlas.prepared_discs())
+        });
+        let mut tessellator = Tessellator::new(
+            ctx.pixels_per_point(),
+            ctx.tessellation_options(|options| *options),
+            font_tex_size,
+            prepared_discs,
         );
 
-        let angle = needle_rotation(self.speed, self.max_speed);
+        let mut mesh = Mesh::default();
+        for shape in shapes {
+            tessellator.tessellate_shape(shape, &mut mesh);
+        }
+        mesh
+    }
+
+    /// Steps the needle kept in the widget memory and repaints until it settles.
+    /// A `jump` moves it to the speed at once.
+    fn needle_value(&self, ui: &Ui, id: Id, jump: bool) -> f32 {
+        let target = self.scale.clamp(self.speed as f32);
+        let Some(dynamics) = self.dynamics else {
+            return target;
+        };
+
+        let dt = ui.input(|i| i.stable_dt);
+        let (value, moving) = ui.data_mut(|data| {
+            let needle = data.get_temp_mut_or_insert_with(id, || Needle::new(target, dynamics));
+            *needle = needle.with_dynamics(dynamics);
+            if jump {
+                needle.jump_to(target);
+            }
+            let moving = needle.step(target, dt);
+            (needle.value(), moving)
+        });
 
-        line.rotate(angle);
-        line.translate(Vec2::new(center.x, center.y));
+        if moving {
+            ui.ctx().request_repaint();
+        }
+
+        value
+    }
+
+    fn draw_needle(
+        &self,
+        ui: &mut Ui,
+        style: &SpeedometerStyle,
+        value: f32,
+        center: Pos2,
+        radius: f32,
+    ) {
+        let line = radial_segment(
+            center,
+            self.scale.angle(value),
+            [style.needle_tail * radius, -style.needle_length * radius],
+            Stroke::new(style.needle_width * radius, style.needle_color),
+        );
 
         ui.painter().add(line);
     }
 
-    fn draw_ticks(&self, ui: &mut Ui, center: Pos2, scale_by_radius: f32, radius: f32) {
-        for tick in (self.min_speed..=self.max_speed).step_by(10) {
-            if tick % 20 == 0 {
-                let mut line = Shape::line_segment(
-                    [
-                        Pos2::new(0.0, 16.0 - scale_by_radius),
-                        Pos2::new(0.0, -0.85 * scale_by_radius),
-                    ],
-                    Stroke::new(scale_by_radius / 50.0, Color32::WHITE),
-                );
-                let angle = needle_rotation(tick, self.max_speed);
-                line.rotate(angle);
-                line.translate(Vec2::new(center.x, center.y));
+    fn add_zones(
+        &self,
+        style: &SpeedometerStyle,
+        shapes: &mut Vec<Shape>,
+        center: Pos2,
+        radius: f32,
+    ) {
+        for zone in &self.zones {
+            let thickness = zone.thickness * radius;
+            let points = self
+                .scale
+                .arc_points(zone.from, zone.to, radius - style.padding - thickness / 2.0)
+                .into_iter()
+                .map(|(x, y)| Pos2::new(center.x + x, center.y + y))
+                .collect();
 
-                ui.painter().add(line);
+            shapes.push(Shape::line(points, Stroke::new(thickness, zone.color)));
+        }
+    }
 
-                self.draw_tick_label(ui, tick, center, scale_by_radius, radius);
+    fn add_ticks(
+        &self,
+        ui: &Ui,
+        style: &SpeedometerStyle,
+        shapes: &mut Vec<Shape>,
+        center: Pos2,
+        radius: f32,
+    ) {
+        for tick in self.scale.ticks(&self.ticks) {
+            let width = if tick.major {
+                style.major_tick_width
             } else {
-                let mut line = Shape::line_segment(
-                    [
-                        Pos2::new(0.0, 16.0 - scale_by_radius),
-                        Pos2::new(0.0, -0.85 * scale_by_radius),
-                    ],
-                    Stroke::new(scale_by_radius / 100.0, Color32::WHITE),
-                );
-                let angle = needle_rotation(tick, self.max_speed);
-                line.rotate(angle);
-                line.translate(Vec2::new(center.x, center.y));
-
-                ui.painter().add(line);
+                style.minor_tick_width
+            };
+
+            shapes.push(radial_segment(
+                center,
+                tick.angle,
+                [style.padding - radius, -style.tick_end * radius],
+                Stroke::new(width * radius, self.tick_color(style, tick.value)),
+            ));
+
+            if tick.labeled {
+                shapes.push(self.tick_label(ui, style, &tick, center, radius));
             }
         }
+
+        // The detent is a major tick, wider, longer and in the colour of the needle.
+        if let Some(origin) = self.scale.origin() {
+            shapes.push(radial_segment(
+                center,
+                self.scale.angle(origin),
+                [style.padding - radius, (0.05 - style.tick_end) * radius],
+                Stroke::new(1.25 * style.major_tick_width * radius, style.needle_color),
+            ));
+        }
     }
 
-    fn draw_tick_label(
+    fn tick_label(
         &self,
-        ui: &mut Ui,
-        tick: u32,
+        ui: &Ui,
+        style: &SpeedometerStyle,
+        tick: &Tick,
         center: Pos2,
-        scale_by_radius: f32,
         radius: f32,
-    ) {
-        let angle = tick_label_rotation(tick, self.max_speed);
-        let scale = scale_by_radius / 347.0;
-
-        ui.painter().text(
-            Pos2::new(
-                center.x + 0.76 * radius * angle.cos(),
-                center.y + 0.76 * radius * angle.sin(),
-            ),
-            Align2::CENTER_CENTER,
-            format!("{tick:<3}"),
-            FontId::new(24.0 * scale, FontFamily::default()),
-            Color32::WHITE,
+    ) -> Shape {
+        let (x, y) = self.scale.point_at(tick.value, style.label_radius * radius);
+        let color = self.tick_color(style, tick.value);
+
+        let galley = ui.painter().layout_no_wrap(
+            format!("{:<3}", self.format_label(tick.value)),
+            FontId::new(style.label_size * radius, style.label_font.clone()),
+            color,
         );
+        let rect =
+            Align2::CENTER_CENTER.anchor_size(Pos2::new(center.x + x, center.y + y), galley.size());
+        Shape::galley(rect.min, galley, color)
+    }
+
+    fn tick_color(&self, style: &SpeedometerStyle, value: f32) -> Color32 {
+        tint(&self.zones, value)
+            .copied()
+            .unwrap_or(style.tick_color)
     }
 }
 
-fn draw_background(ui: &mut Ui, center: Pos2, radius: f32) {
-    ui.painter().add(CircleShape {
-        center,
-        radius,
-        fill: BACKGROUND_COLOR,
-        stroke: Stroke::new(9.0, BACKGROUND_BORDER_COLOR),
-    });
+/// Segment between the heights `ys` of the upright dial, turned by `angle`
+/// degrees about its `center`.
+/// The value for the tooltip, to six decimals without the trailing zeros, so that
+/// a value dragged off the step shows as it is.
+fn exact_value(value: f64) -> String {
+    let text = format_value(value, 6);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        text
+    }
 }
 
-fn needle_rotation(n: u32, total: u32) -> f32 {
-    let turns = n as f32 / total as f32;
+fn radial_segment(center: Pos2, angle: f32, ys: [f32; 2], stroke: Stroke) -> Shape {
+    let placement =
+        Affine::rotation(angle.to_radians()).then(Affine::translation(center.to_vec2()));
 
-    let degrees = (360.0 - SECTOR_DEGREES) * turns - (180.0 - SECTOR_DEGREES / 2.0);
-    degrees * PI / 180.0
+    Shape::line_segment(ys.map(|y| placement.apply(Pos2::new(0.0, y))), stroke)
 }
 
-fn tick_label_rotation(n: u32, total: u32) -> f32 {
-    let turns = n as f32 / total as f32;
+/// The dial face, with the border inside the radius so the widget doesn't clip it.
+/// egui strokes circles on the outside.
+fn background(style: &SpeedometerStyle, center: Pos2, radius: f32) -> Shape {
+    Shape::Circle(CircleShape {
+        center,
+        radius: radius - style.border.width,
+        fill: style.background,
+        stroke: style.border,
+    })
+}
 
-    let degrees = (360.0 - SECTOR_DEGREES) * turns - 220.0;
-    degrees * PI / 180.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::{
+        CentralPanel, Context, Event, Frame, Modifiers, MouseWheelUnit, PointerButton, RawInput,
+    };
+
+    const CENTER: Pos2 = Pos2::new(100.0, 100.0);
+
+    /// One pass with an editable gauge over the whole screen, returns whether it
+    /// reported a change.
+    fn run(ctx: &Context, value: &mut f32, events: Vec<Event>) -> bool {
+        run_gauge(
+            ctx,
+            events,
+            Speedometer::editable(value, 0.0, 200.0).step(5.0),
+        )
+    }
+
+    fn run_gauge(ctx: &Context, events: Vec<Event>, gauge: Speedometer) -> bool {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_center_size(CENTER, Vec2::splat(200.0))),
+            events,
+            ..RawInput::default()
+        };
+        let mut gauge = Some(gauge);
+        let mut changed = false;
+        let _ = ctx.run(input, |ctx| {
+            CentralPanel::default()
+                .frame(Frame::none())
+                .show(ctx, |ui| {
+                    if let Some(gauge) = gauge.take() {
+                        changed = ui.add(gauge).changed();
+                    }
+                });
+        });
+        changed
+    }
+
+    fn button(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    fn key(key: Key) -> Event {
+        Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn paints_the_cached_dial_through_a_callback() {
+        let ctx = Context::default();
+        let callbacks = |paint_callback| {
+            let input = RawInput {
+                screen_rect: Some(Rect::from_center_size(CENTER, Vec2::splat(200.0))),
+                ..RawInput::default()
+            };
+            let output = ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.add(Speedometer::new(50.0, 0.0, 200.0).paint_callback(paint_callback))
+                });
+            });
+            output
+                .shapes
+                .iter()
+                .filter(|clipped| matches!(clipped.shape, Shape::Callback(_)))
+                .count()
+        };
+
+        assert_eq!(callbacks(true), 1);
+        assert_eq!(callbacks(false), 0);
+    }
+
+    #[test]
+    fn formats_the_labels_again_only_for_a_new_generation() {
+        let ctx = Context::default();
+        let calls = std::cell::Cell::new(0);
+        let frame = |generation| {
+            let gauge = Speedometer::new(50.0, 0.0, 200.0)
+                .custom_formatter(|value| {
+                    calls.set(calls.get() + 1);
+                    format!("{value} km/h")
+                })
+                .formatter_generation(generation);
+            run_gauge(&ctx, vec![], gauge);
+            calls.replace(0)
+        };
+
+        assert!(frame(0) > 0);
+        assert_eq!(frame(0), 0);
+        assert!(frame(1) > 0);
+    }
+
+    #[test]
+    fn shows_the_value_off_the_step() {
+        assert_eq!(exact_value(100.0), "100");
+        assert_eq!(exact_value(102.5), "102.5");
+        assert_eq!(exact_value(123.456_78), "123.45678");
+        assert_eq!(exact_value(-0.000_000_1), "0");
+    }
+
+    #[test]
+    fn drags_the_needle() {
+        let ctx = Context::default();
+        let mut value = 20.0;
+        let top = CENTER - Vec2::new(0.0, 60.0);
+        assert!(!run(&ctx, &mut value, vec![Event::PointerMoved(top)]));
+
+        assert!(run(&ctx, &mut value, vec![button(top, true)]));
+        assert!((value - 100.0).abs() < 1e-3, "{value}");
+
+        // Straight down is in the empty sector, nearer the end on its side.
+        let below = CENTER + Vec2::new(1.0, 60.0);
+        assert!(run(&ctx, &mut value, vec![Event::PointerMoved(below)]));
+        assert_eq!(value, 200.0);
+
+        assert!(!run(&ctx, &mut value, vec![button(below, false)]));
+        assert!(!run(&ctx, &mut value, vec![]));
+        assert_eq!(value, 200.0);
+    }
+
+    #[test]
+    fn steps_with_the_wheel_and_the_keys() {
+        let ctx = Context::default();
+        let mut value = 100.0;
+        assert!(!run(&ctx, &mut value, vec![Event::PointerMoved(CENTER)]));
+
+        let notch = Event::MouseWheel {
+            unit: MouseWheelUnit::Line,
+            delta: Vec2::new(0.0, 2.0),
+            modifiers: Modifiers::NONE,
+        };
+        assert!(run(&ctx, &mut value, vec![notch]));
+        assert_eq!(value, 110.0);
+
+        assert!(!run(&ctx, &mut value, vec![key(Key::Tab)]));
+        assert!(run(
+            &ctx,
+            &mut value,
+            vec![key(Key::ArrowDown), key(Key::ArrowLeft)]
+        ));
+        assert_eq!(value, 100.0);
+        assert!(run(&ctx, &mut value, vec![key(Key::ArrowUp)]));
+        assert_eq!(value, 105.0);
+    }
+
+    #[test]
+    fn falls_back_to_a_hundredth_of_the_range() {
+        let ctx = Context::default();
+        let mut value = 100.0;
+        let empty = TickSpec::new(0.0);
+        assert!(!run(&ctx, &mut value, vec![key(Key::Tab)]));
+        assert!(run_gauge(
+            &ctx,
+            vec![key(Key::ArrowUp)],
+            Speedometer::editable(&mut value, 0.0, 200.0).ticks(empty)
+        ));
+        assert_eq!(value, 102.0);
+
+        // The minor tick step, 10 on this scale, rather than a step that is not finite.
+        assert!(run_gauge(
+            &ctx,
+            vec![key(Key::ArrowUp)],
+            Speedometer::editable(&mut value, 0.0, 200.0).step(f64::NAN)
+        ));
+        assert_eq!(value, 112.0);
+    }
+
+    #[test]
+    fn leaves_gauges_with_bounds_that_are_not_finite() {
+        let ctx = Context::default();
+        let mut value = 100.0;
+        assert!(!run(&ctx, &mut value, vec![key(Key::Tab)]));
+        let events = vec![key(Key::ArrowUp), button(CENTER, true)];
+        assert!(!run_gauge(
+            &ctx,
+            events,
+            Speedometer::editable(&mut value, f32::NAN, f32::NAN)
+        ));
+        assert_eq!(value, 100.0);
+    }
 }
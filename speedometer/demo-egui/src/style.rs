@@ -0,0 +1,174 @@
+use eframe::egui::{Color32, FontFamily, Stroke, Visuals};
+use std::hash::{Hash, Hasher};
+
+/// Colours and proportions of a [`Speedometer`](crate::Speedometer).
+///
+/// [`SpeedometerStyle::from_visuals`] picks the colours of the egui theme, which the
+/// widget does by itself when no style is given. Lengths are fractions of the dial
+/// radius, so the dial keeps its proportions at any size, except the border and the
+/// padding, in points.
+///
+/// ```
+/// # use demo_egui::{Speedometer, SpeedometerStyle};
+/// # use eframe::egui::{Color32, Visuals};
+/// let style = SpeedometerStyle::from_visuals(&Visuals::dark())
+///     .needle_color(Color32::from_rgb(0xFF, 0x45, 0x3A))
+///     .needle_length(0.9)
+///     .cap_radius(0.0);
+/// let speedometer = Speedometer::new(87.5, 0.0, 200.0).style(style);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedometerStyle {
+    pub(crate) background: Color32,
+    pub(crate) border: Stroke,
+    pub(crate) padding: f32,
+    pub(crate) tick_color: Color32,
+    pub(crate) major_tick_width: f32,
+    pub(crate) minor_tick_width: f32,
+    pub(crate) tick_end: f32,
+    pub(crate) label_font: FontFamily,
+    pub(crate) label_size: f32,
+    pub(crate) label_radius: f32,
+    pub(crate) needle_color: Color32,
+    pub(crate) needle_width: f32,
+    pub(crate) needle_length: f32,
+    pub(crate) needle_tail: f32,
+    pub(crate) cap_color: Color32,
+    pub(crate) cap_radius: f32,
+}
+
+impl SpeedometerStyle {
+    pub fn from_visuals(visuals: &Visuals) -> Self {
+        Self {
+            background: visuals.extreme_bg_color,
+            border: Stroke::new(9.0, visuals.widgets.noninteractive.bg_stroke.color),
+            padding: 16.0,
+            tick_color: visuals.strong_text_color(),
+            major_tick_width: 1.0 / 50.0,
+            minor_tick_width: 1.0 / 100.0,
+            tick_end: 0.85,
+            label_font: FontFamily::Proportional,
+            label_size: 24.0 / 347.0,
+            label_radius: 0.76,
+            needle_color: visuals.error_fg_color,
+            needle_width: 1.0 / 60.0,
+            needle_length: 0.88,
+            needle_tail: 0.12,
+            cap_color: visuals.widgets.inactive.bg_fill,
+            cap_radius: 0.1,
+        }
+    }
+
+    /// Fill of the dial face.
+    pub fn background(mut self, color: Color32) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Rim of the dial, its width in points.
+    pub fn border(mut self, stroke: impl Into<Stroke>) -> Self {
+        self.border = stroke.into();
+        self
+    }
+
+    /// Points between the rim and the outer end of the ticks and zones.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Colour of the ticks and labels outside the zones.
+    pub fn tick_color(mut self, color: Color32) -> Self {
+        self.tick_color = color;
+        self
+    }
+
+    /// Widths of the major and minor ticks.
+    pub fn tick_widths(mut self, major: f32, minor: f32) -> Self {
+        self.major_tick_width = major;
+        self.minor_tick_width = minor;
+        self
+    }
+
+    /// Distance from the centre at which the ticks end.
+    pub fn tick_end(mut self, end: f32) -> Self {
+        self.tick_end = end;
+        self
+    }
+
+    /// Font of the tick labels, and their size.
+    pub fn label_font(mut self, family: FontFamily, size: f32) -> Self {
+        self.label_font = family;
+        self.label_size = size;
+        self
+    }
+
+    /// Distance from the centre of the middle of the tick labels.
+    pub fn label_radius(mut self, radius: f32) -> Self {
+        self.label_radius = radius;
+        self
+    }
+
+    /// Colour of the needle and of the origin detent.
+    pub fn needle_color(mut self, color: Color32) -> Self {
+        self.needle_color = color;
+        self
+    }
+
+    pub fn needle_width(mut self, width: f32) -> Self {
+        self.needle_width = width;
+        self
+    }
+
+    /// Distance from the centre to the tip of the needle.
+    pub fn needle_length(mut self, length: f32) -> Self {
+        self.needle_length = length;
+        self
+    }
+
+    /// How far the needle sticks out behind the centre.
+    pub fn needle_tail(mut self, tail: f32) -> Self {
+        self.needle_tail = tail;
+        self
+    }
+
+    /// Disc under the pivot of the needle, `0.0` for none.
+    pub fn cap_radius(mut self, radius: f32) -> Self {
+        self.cap_radius = radius;
+        self
+    }
+
+    pub fn cap_color(mut self, color: Color32) -> Self {
+        self.cap_color = color;
+        self
+    }
+}
+
+impl Default for SpeedometerStyle {
+    /// The style of the default dark theme.
+    fn default() -> Self {
+        Self::from_visuals(&Visuals::dark())
+    }
+}
+
+impl Hash for SpeedometerStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.background, self.border).hash(state);
+        (self.tick_color, self.needle_color, self.cap_color).hash(state);
+        self.label_font.hash(state);
+        [
+            self.padding,
+            self.major_tick_width,
+            self.minor_tick_width,
+            self.tick_end,
+            self.label_size,
+            self.label_radius,
+            self.needle_width,
+            self.needle_length,
+            self.needle_tail,
+            self.cap_radius,
+        ]
+        .map(f32::to_bits)
+        .hash(state);
+    }
+}
@@ -0,0 +1,90 @@
+//! CPU time per frame of the speedometer alone, without a window: a new
+//! `Speedometer` every frame the way `view` builds it, its widget geometry, and
+//! the rasterisation with iced's tiny-skia renderer, timed apart.
+//!
+//! The numbers are the ones of the dashboard overlay, over the last five seconds
+//! of simulated 60 FPS frames.
+//!
+//! ```sh
+//! cargo run --release -p demo-iced --example iced_dial_cpu -- [frames]
+//! ```
+
+use demo_iced::speedometer;
+use frame_metrics::FrameMetrics;
+use iced::advanced::graphics::Viewport;
+use iced::advanced::widget::Tree;
+use iced::advanced::{layout, mouse, renderer, Layout, Renderer as _, Widget};
+use iced::{Color, Font, Pixels, Rectangle, Size, Theme};
+use std::time::{Duration, Instant};
+use tiny_skia::{Mask, Pixmap};
+
+const FRAME: f64 = 1.0 / 60.0;
+const SIZE: u32 = 600;
+
+fn main() {
+    let frames: u32 = std::env::args()
+        .nth(1)
+        .and_then(|frames| frames.parse().ok())
+        .unwrap_or(300);
+
+    let theme = Theme::Dark;
+    let bounds = Rectangle::with_size(Size::new(SIZE as f32, SIZE as f32));
+    let mut renderer =
+        iced::Renderer::Secondary(iced_tiny_skia::Renderer::new(Font::DEFAULT, Pixels(16.0)));
+    let mut pixmap = Pixmap::new(SIZE, SIZE).expect("a valid size");
+    let mut clip_mask = Mask::new(SIZE, SIZE).expect("a valid size");
+    let viewport = Viewport::with_physical_size(Size::new(SIZE, SIZE), 1.0);
+
+    let node = layout::Node::new(bounds.size());
+    // Kept across frames like the user interface keeps it across `view` calls.
+    let mut tree = None;
+    let mut geometry = FrameMetrics::new("iced");
+    let mut raster = FrameMetrics::new("iced");
+    let start = Instant::now();
+
+    for frame in 0..frames {
+        let time = frame as f64 * FRAME;
+        // The needle swings over the whole dial every four seconds.
+        let speed = 100.0 - 100.0 * (time * std::f64::consts::PI / 2.0).cos();
+        let now = start + Duration::from_secs_f64(time);
+
+        let started = Instant::now();
+        let speedometer = speedometer(speed as f32, 0.0, 200.0);
+        let widget: &dyn Widget<(), Theme, _> = &speedometer;
+        let tree = tree.get_or_insert_with(|| Tree::new(widget));
+        renderer.clear();
+        widget.draw(
+            tree,
+            &mut renderer,
+            &theme,
+            &renderer::Style::default(),
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &bounds,
+        );
+        geometry.record(now, started.elapsed(), true);
+
+        let iced::Renderer::Secondary(tiny_skia) = &mut renderer else {
+            unreachable!("the renderer was built as tiny-skia");
+        };
+        let started = Instant::now();
+        tiny_skia.draw(
+            &mut pixmap.as_mut(),
+            &mut clip_mask,
+            &viewport,
+            &[bounds],
+            Color::BLACK,
+            &[] as &[&str],
+        );
+        raster.record(now, started.elapsed(), true);
+    }
+
+    for (name, metrics) in [("geometry", &geometry), ("raster", &raster)] {
+        let stats = metrics.stats();
+        println!(
+            "{name}: CPU {:.3} ms / frame, {}",
+            stats.mean_cpu_time.as_secs_f64() * 1e3,
+            stats.cpu_time.expect("frames were recorded")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::follow::Follow;
+use crate::theme::Theme;
+use cli_args::{ArgError, Args};
+use std::error::Error;
+use std::fmt;
+
+/// Theme options of the dashboard, see [`ThemeArgs::split`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThemeArgs {
+    /// The theme at start, until the surroundings are read.
+    pub theme: Theme,
+    pub follow: Option<Follow>,
+}
+
+impl ThemeArgs {
+    /// Takes the theme options out of the arguments and returns the others:
+    ///
+    /// - `--theme <dark|light|high-contrast|night|auto>`, where `auto` follows the
+    ///   local time of day;
+    /// - `--light-sensor <path>` to follow an ambient light sensor instead.
+    pub fn split<I>(args: I) -> Result<(Self, Vec<String>), ArgsError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut rest = Vec::new();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            match arg.flag() {
+                "--theme" => match args.value(&arg)?.as_str() {
+                    "auto" => {
+                        parsed.follow.get_or_insert(Follow::Clock);
+                    }
+                    name => parsed.theme = parse_theme(name)?,
+                },
+                "--light-sensor" => {
+                    parsed.follow = Some(Follow::LightSensor(args.value(&arg)?.into()));
+                }
+                _ => rest.push(arg.into_string()),
+            }
+        }
+
+        Ok((parsed, rest))
+    }
+}
+
+fn parse_theme(name: &str) -> Result<Theme, ArgsError> {
+    match name {
+        "dark" => Ok(Theme::Dark),
+        "light" => Ok(Theme::Light),
+        "high-contrast" => Ok(Theme::HighContrast),
+        "night" => Ok(Theme::Night),
+        _ => Err(ArgsError::UnknownTheme(name.to_owned())),
+    }
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    Args(ArgError),
+    UnknownTheme(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Args(err) => err.fmt(f),
+            ArgsError::UnknownTheme(name) => write!(
+                f,
+                "unknown theme `{name}`, expected dark, light, high-contrast, night or auto"
+            ),
+        }
+    }
+}
+
+impl Error for ArgsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArgsError::Args(err) => Some(err),
+            ArgsError::UnknownTheme(_) => None,
+        }
+    }
+}
+
+impl From<ArgError> for ArgsError {
+    fn from(err: ArgError) -> Self {
+        ArgsError::Args(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_the_other_arguments() {
+        let (args, rest) = ThemeArgs::split([
+            "--source",
+            "sweep",
+            "--theme=night",
+            "--light-sensor",
+            "/sys/bus/iio/devices/iio:device0/in_illuminance_input",
+            "--loop",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args,
+            ThemeArgs {
+                theme: Theme::Night,
+                follow: Some(Follow::LightSensor(
+                    "/sys/bus/iio/devices/iio:device0/in_illuminance_input".into()
+                )),
+            }
+        );
+        assert_eq!(rest, ["--source", "sweep", "--loop"]);
+
+        let (args, _) = ThemeArgs::split(["--theme", "auto"]).unwrap();
+        assert_eq!(args.follow, Some(Follow::Clock));
+        assert!(matches!(
+            ThemeArgs::split(["--theme", "sepia"]),
+            Err(ArgsError::UnknownTheme(_))
+        ));
+        assert!(matches!(
+            ThemeArgs::split(["--light-sensor"]),
+            Err(ArgsError::Args(ArgError::MissingValue(_)))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::theme::Theme;
+use chrono::Timelike;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Themes from the darkest to the brightest surroundings they suit.
+const BY_BRIGHTNESS: [Theme; 4] = [Theme::Night, Theme::Dark, Theme::Light, Theme::HighContrast];
+
+/// Ambient light in lux between the themes of [`BY_BRIGHTNESS`]: street lights,
+/// daylight indoors or under clouds, and direct sunlight.
+const LUX_THRESHOLDS: [f32; 3] = [10.0, 400.0, 20_000.0];
+
+/// Factor the light must cross a threshold by to change the theme, so that a
+/// passing shadow or a street light doesn't make the dashboard flicker.
+const HYSTERESIS: f32 = 1.5;
+
+/// What the dashboard picks its theme from on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Follow {
+    /// The local time: light by day, dark at dusk and dawn, night in between.
+    Clock,
+    /// A file holding the ambient light in lux, such as the `in_illuminance_input`
+    /// of a Linux IIO light sensor.
+    LightSensor(PathBuf),
+}
+
+impl Follow {
+    /// How often the surroundings are read.
+    pub const PERIOD: Duration = Duration::from_secs(1);
+
+    /// The theme for the surroundings now, given the `current` one.
+    pub fn theme(&self, current: Theme) -> io::Result<Theme> {
+        match self {
+            Follow::Clock => {
+                let now = chrono::Local::now().time();
+                Ok(for_time_of_day(
+                    now.hour() as f32 + now.minute() as f32 / 60.0,
+                ))
+            }
+            Follow::LightSensor(path) => {
+                let lux = fs::read_to_string(path)?
+                    .trim()
+                    .parse()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                Ok(for_ambient_light(current, lux))
+            }
+        }
+    }
+}
+
+/// The theme for `hour`, local time from 0 to 24.
+pub fn for_time_of_day(hour: f32) -> Theme {
+    match hour {
+        7.0..19.0 => Theme::Light,
+        5.0..7.0 | 19.0..22.0 => Theme::Dark,
+        _ => Theme::Night,
+    }
+}
+
+/// The theme for an ambient light of `lux`, which stays `current` until the light
+/// is clearly past the threshold to the next one.
+pub fn for_ambient_light(current: Theme, lux: f32) -> Theme {
+    let mut level = BY_BRIGHTNESS
+        .iter()
+        .position(|theme| *theme == current)
+        .unwrap_or(1);
+
+    while level < LUX_THRESHOLDS.len() && lux > LUX_THRESHOLDS[level] * HYSTERESIS {
+        level += 1;
+    }
+    while level > 0 && lux < LUX_THRESHOLDS[level - 1] / HYSTERESIS {
+        level -= 1;
+    }
+
+    BY_BRIGHTNESS[level]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_clock() {
+        assert_eq!(for_time_of_day(12.0), Theme::Light);
+        assert_eq!(for_time_of_day(20.5), Theme::Dark);
+        assert_eq!(for_time_of_day(6.0), Theme::Dark);
+        assert_eq!(for_time_of_day(23.0), Theme::Night);
+        assert_eq!(for_time_of_day(0.0), Theme::Night);
+    }
+
+    #[test]
+    fn follows_the_light_without_flickering() {
+        assert_eq!(
+            for_ambient_light(Theme::Dark, 100_000.0),
+            Theme::HighContrast
+        );
+        assert_eq!(for_ambient_light(Theme::HighContrast, 0.5), Theme::Night);
+
+        // Around the threshold between dark and light, the theme stays.
+        assert_eq!(for_ambient_light(Theme::Dark, 500.0), Theme::Dark);
+        assert_eq!(for_ambient_light(Theme::Light, 300.0), Theme::Light);
+        assert_eq!(for_ambient_light(Theme::Dark, 700.0), Theme::Light);
+        assert_eq!(for_ambient_light(Theme::Light, 200.0), Theme::Dark);
+
+        // A theme picked by hand is left for the nearest one that suits the light.
+        assert_eq!(for_ambient_light(Theme::Night, 50.0), Theme::Dark);
+    }
+
+    #[test]
+    fn reads_the_light_sensor() {
+        let path = std::env::temp_dir().join(format!("lux-{}", std::process::id()));
+        fs::write(&path, "3.5\n").unwrap();
+        let sensor = Follow::LightSensor(path.clone());
+        assert_eq!(sensor.theme(Theme::Light).unwrap(), Theme::Night);
+
+        fs::write(&path, "bright").unwrap();
+        let err = sensor.theme(Theme::Light).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(path).unwrap();
+    }
+}
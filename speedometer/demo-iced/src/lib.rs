@@ -0,0 +1,11 @@
+//! The iced speedometer and dashboard themes, shared by the dashboard and the
+//! `gauge-render` tool.
+
+pub mod args;
+pub mod follow;
+pub mod speedometer;
+pub mod theme;
+
+pub use args::ThemeArgs;
+pub use follow::Follow;
+pub use speedometer::{speedometer, Animation, Speedometer};
@@ -1,58 +1,321 @@
-use iced::widget::{column, Column};
-use iced::{Center, Length};
+use frame_metrics::{FrameMetrics, MetricsArgs};
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::{self, Duration, Instant};
+use iced::widget::{button, checkbox, pick_list, row, slider, text, Column, Row};
+use iced::{Center, Length, Subscription, Task};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use vehicle_data::{
+    source_from_args, Playback, Sample, SourceStatus, VehicleDataSource, PLAYBACK_RATES,
+    SAMPLE_PERIOD,
+};
 
-use speedometer::speedometer;
-use theme::Theme as DashboardTheme;
+use demo_iced::theme::Theme as DashboardTheme;
+use demo_iced::{speedometer, Animation, Follow, ThemeArgs};
 
-mod speedometer;
-mod theme;
-
-const MAX_SPEED: u32 = 200;
-const MIN_SPEED: u32 = 0;
+const MAX_SPEED: f32 = 200.0;
+const MIN_SPEED: f32 = 0.0;
 
 pub fn main() -> iced::Result {
+    let (options, args) = MetricsArgs::split(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let metrics = options.metrics("iced");
+    let (theme, args) = ThemeArgs::split(args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let source = source_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
     iced::application("Iced Dashboard", Dashboard::update, Dashboard::view)
+        .subscription(Dashboard::subscription)
         .theme(Dashboard::theme)
         .antialiasing(true)
-        .run()
+        .run_with(move || {
+            (
+                Dashboard::new(source, theme, metrics, options.exit_after),
+                Task::none(),
+            )
+        })
 }
 
-#[derive(Default)]
 struct Dashboard {
-    speed: u32,
+    source: Box<dyn VehicleDataSource>,
+    sample: Sample,
+    status: SourceStatus,
+    started: Instant,
+    speed: f32,
+    /// Whether the speed was set on the gauge, which holds it against the source.
+    held: bool,
+    needle: Animation,
     theme: DashboardTheme,
+    /// What the theme can follow, from the command line.
+    follow: Option<Follow>,
+    following: bool,
+    /// Why the surroundings could not be read the last time.
+    follow_error: Option<String>,
+    // iced does not report its frames, but it draws and presents one after each
+    // `view`, so that is where they are recorded.
+    metrics: RefCell<FrameMetrics>,
+    // The CPU time is what `update` and `view` took since the previous frame, layout
+    // and drawing aside.
+    work: Cell<Duration>,
+    // Whether the previous frame asked for the next one.
+    animating: Cell<bool>,
+    exit_after: Option<Duration>,
 }
 
 impl Dashboard {
-    fn update(&mut self, message: Message) {
+    fn new(
+        source: Box<dyn VehicleDataSource>,
+        theme: ThemeArgs,
+        metrics: FrameMetrics,
+        exit_after: Option<Duration>,
+    ) -> Self {
+        let mut dashboard = Self {
+            source,
+            sample: Sample::default(),
+            status: SourceStatus::default(),
+            started: Instant::now(),
+            speed: MIN_SPEED,
+            held: false,
+            needle: Animation::new(MIN_SPEED, MIN_SPEED, MAX_SPEED),
+            theme: theme.theme,
+            following: theme.follow.is_some(),
+            follow: theme.follow,
+            follow_error: None,
+            metrics: RefCell::new(metrics),
+            work: Cell::default(),
+            animating: Cell::default(),
+            exit_after,
+        };
+        dashboard.handle(Message::Surroundings);
+        dashboard
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let started = Instant::now();
+        self.handle(message);
+        self.work.set(self.work.get() + started.elapsed());
+
+        if self
+            .exit_after
+            .is_some_and(|exit_after| self.started.elapsed() >= exit_after)
+        {
+            if let Err(err) = self.metrics.borrow().export() {
+                eprintln!("cannot write frame metrics: {err}");
+            }
+            return iced::exit();
+        }
+        Task::none()
+    }
+
+    fn handle(&mut self, message: Message) {
         match message {
             Message::SpeedChanged(value) => {
                 self.speed = value;
+                self.held = true;
+            }
+            Message::Release => {
+                self.speed = self.sample.speed;
+                self.held = false;
+            }
+            Message::Poll(now) => {
+                let elapsed = now.duration_since(self.started);
+                if let Some(sample) = self.source.poll(elapsed) {
+                    self.sample = sample;
+                    if !self.held {
+                        self.speed = sample.speed;
+                    }
+                }
+                self.status = self.source.status(elapsed);
+            }
+            Message::TogglePause => {
+                if let Some(playback) = self.source.playback_mut() {
+                    playback.set_paused(!playback.is_paused());
+                }
+            }
+            Message::Seek(position) => {
+                if let Some(playback) = self.source.playback_mut() {
+                    playback.seek(position);
+                }
+            }
+            Message::RateSelected(Rate(rate)) => {
+                if let Some(playback) = self.source.playback_mut() {
+                    playback.set_rate(rate);
+                }
+            }
+            Message::LoopToggled(looping) => {
+                if let Some(playback) = self.source.playback_mut() {
+                    playback.set_looping(looping);
+                }
+            }
+            Message::ThemeSelected(theme) => {
+                // A theme picked by hand holds until following is turned back on.
+                self.theme = theme;
+                self.following = false;
+            }
+            Message::NextTheme => self.handle(Message::ThemeSelected(self.theme.next())),
+            Message::FollowToggled(following) => {
+                self.following = following;
+                self.handle(Message::Surroundings);
+            }
+            Message::Surroundings => {
+                let Some(follow) = self.follow.as_ref().filter(|_| self.following) else {
+                    return;
+                };
+                match follow.theme(self.theme) {
+                    Ok(theme) => {
+                        self.theme = theme;
+                        self.follow_error = None;
+                    }
+                    Err(err) => self.follow_error = Some(format!("cannot follow the theme: {err}")),
+                }
             }
-            Message::None => {}
+            Message::Frame(now) => self.needle.tick(self.speed, now),
         }
     }
 
-    fn view(&self) -> Column<Message> {
-        column![speedometer(MIN_SPEED, MAX_SPEED, self.speed as u32)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .view()
-            .map(|_| Message::None),]
-        .padding(20)
-        .align_x(Center)
+    fn view(&self) -> Column<'_, Message> {
+        let started = Instant::now();
+        let live = self.status == SourceStatus::Live;
+        let theme = self.theme;
+
+        let view = Column::new()
+            .push(text(self.metrics.borrow().stats().to_string()).size(12))
+            .push(text(self.sample.to_string()))
+            .push(theme_controls(
+                self.theme,
+                self.follow.is_some(),
+                self.following,
+            ))
+            .push_maybe(
+                self.follow_error
+                    .as_deref()
+                    .map(|err| text(err).style(text::danger)),
+            )
+            .push_maybe(self.source.playback().map(playback_controls))
+            .push_maybe((!live).then(|| text(self.status.to_string()).style(text::danger)))
+            .push_maybe(
+                self.held
+                    .then(|| button("Follow the source").on_press(Message::Release)),
+            )
+            .push(
+                // Dragging the needle sets the speed until the source is followed again.
+                speedometer(self.speed, MIN_SPEED, MAX_SPEED)
+                    .needle(self.needle.value())
+                    .on_change(Message::SpeedChanged)
+                    .active(live)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(move |_, status| theme.speedometer(status)),
+            )
+            .spacing(10)
+            .padding(20)
+            .align_x(Center);
+
+        let work = self.work.take() + started.elapsed();
+        let animating = self.animating.replace(self.needle.is_moving(self.speed));
+        self.metrics
+            .borrow_mut()
+            .record(Instant::now(), work, animating);
+        view
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            time::every(SAMPLE_PERIOD).map(Message::Poll),
+            self.needle.subscription(self.speed).map(Message::Frame),
+            keyboard::on_key_press(shortcut),
+            if self.following {
+                time::every(Follow::PERIOD).map(|_| Message::Surroundings)
+            } else {
+                Subscription::none()
+            },
+        ])
     }
 
     fn theme(&self) -> iced::Theme {
-        iced::Theme::custom_with_fn(String::from("Custom"), self.theme.palette(), |_| {
-            self.theme.extended_palette()
-        })
+        self.theme.iced()
     }
 }
 
 #[derive(Debug, Clone, Copy)]
 enum Message {
-    None,
-    #[allow(dead_code)]
-    SpeedChanged(u32),
+    SpeedChanged(f32),
+    /// Lets the source drive the speed again after it was set on the gauge.
+    Release,
+    Poll(Instant),
+    TogglePause,
+    Seek(f32),
+    RateSelected(Rate),
+    LoopToggled(bool),
+    ThemeSelected(DashboardTheme),
+    NextTheme,
+    FollowToggled(bool),
+    /// Time to read the surroundings the theme follows.
+    Surroundings,
+    Frame(Instant),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rate(f32);
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}×", self.0)
+    }
+}
+
+/// Ctrl+T, or Cmd+T on macOS, switches to the next theme.
+fn shortcut(key: Key, modifiers: Modifiers) -> Option<Message> {
+    match key.as_ref() {
+        Key::Character("t") if modifiers.command() => Some(Message::NextTheme),
+        _ => None,
+    }
+}
+
+fn theme_controls(
+    theme: DashboardTheme,
+    can_follow: bool,
+    following: bool,
+) -> Row<'static, Message> {
+    row![pick_list(
+        DashboardTheme::ALL,
+        Some(theme),
+        Message::ThemeSelected
+    )]
+    .push_maybe(
+        can_follow.then(|| checkbox("Automatic", following).on_toggle(Message::FollowToggled)),
+    )
+    .spacing(10)
+    .align_y(Center)
+}
+
+fn playback_controls(playback: &dyn Playback) -> Row<'_, Message> {
+    let rates = PLAYBACK_RATES.map(Rate);
+
+    row![
+        button(if playback.is_paused() {
+            "Play"
+        } else {
+            "Pause"
+        })
+        .on_press(Message::TogglePause),
+        slider(
+            0.0..=playback.duration(),
+            playback.position(),
+            Message::Seek
+        )
+        .step(0.1),
+        text(format!("{:.1} s", playback.position())),
+        pick_list(rates, Some(Rate(playback.rate())), Message::RateSelected),
+        checkbox("Loop", playback.is_looping()).on_toggle(Message::LoopToggled),
+    ]
+    .spacing(10)
+    .align_y(Center)
 }
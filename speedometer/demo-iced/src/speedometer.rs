@@ -1,34 +1,130 @@
 use iced::{
-    advanced::{graphics::gradient, mouse},
-    alignment,
+    advanced::{
+        graphics::{geometry::Renderer as _, gradient},
+        layout, mouse, renderer,
+        widget::{self, operation, tree, Operation, Tree},
+        Clipboard, Layout, Renderer as _, Shell, Widget,
+    },
+    alignment, event,
     font::Weight,
-    theme::palette::Extended,
-    widget::canvas,
-    widget::canvas::{stroke, Cache, Frame, LineCap, Path, Stroke, Style, Text},
-    Color, Degrees, Element, Font, Length, Point, Radians, Rectangle, Renderer, Theme, Vector,
+    keyboard::{self, key::Named},
+    time::Instant,
+    touch,
+    widget::canvas::{self, path::Arc, stroke, Cache, Frame, Path, Stroke, Text},
+    window, Color, Degrees, Element, Event, Font, Length, Point, Rectangle, Renderer, Size,
+    Subscription, Theme, Vector,
 };
 
-pub struct Speedometer {
-    speed: u32,
-    min_speed: u32,
-    max_speed: u32,
+use gauge_core::{
+    format_value, step_decimals, tint, Needle, NeedleDynamics, Scale, Tick, TickSpec, Zone,
+};
+use std::cell::Cell;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Pixels of a touchpad scroll worth one wheel notch, i.e. one step.
+const PIXELS_PER_LINE: f32 = 40.0;
+
+/// The gauge widget. With [`Speedometer::on_change`] it is also an input: drag the
+/// needle, scroll over the dial, or focus it and use the arrow keys.
+///
+/// Its colours come from the [`Catalog`] of the theme, like those of the built-in
+/// widgets, and can be set with [`Speedometer::style`].
+pub struct Speedometer<'a, Message = (), Theme = iced::Theme>
+where
+    Theme: Catalog,
+{
+    id: Option<widget::Id>,
+    speed: f32,
+    needle: Option<f32>,
+    on_change: Option<Box<dyn Fn(f32) -> Message + 'a>>,
+    step: Option<f32>,
+    scale: Scale,
+    ticks: TickSpec,
+    zones: Vec<Zone<Color>>,
+    decimals: usize,
+    formatter: Option<Box<dyn Fn(f64) -> String + 'a>>,
+    active: bool,
     width: Length,
     height: Length,
-    cache: Cache,
+    class: Theme::Class<'a>,
 }
 
-impl Speedometer {
-    pub fn new(min_speed: u32, max_speed: u32, speed: u32) -> Speedometer {
+impl<'a, Theme: Catalog> Speedometer<'a, (), Theme> {
+    pub fn new<T>(speed: T, min_speed: T, max_speed: T) -> Self
+    where
+        T: Copy,
+        f64: From<T>,
+    {
+        let (min_speed, max_speed) = (f64::from(min_speed) as f32, f64::from(max_speed) as f32);
+
         Self {
-            speed: speed.min(max_speed),
-            min_speed,
-            max_speed,
+            id: None,
+            speed: f64::from(speed) as f32,
+            needle: None,
+            on_change: None,
+            step: None,
+            scale: Scale::new(min_speed, max_speed),
+            ticks: TickSpec::for_range(min_speed, max_speed),
+            zones: Vec::new(),
+            decimals: 0,
+            formatter: None,
+            active: true,
             width: Length::Fixed(20.0),
             height: Length::Fixed(20.0),
-            cache: Default::default(),
+            class: Theme::default(),
         }
     }
 
+    /// Makes the gauge an input producing the message of the value the user sets.
+    pub fn on_change<Message>(
+        self,
+        on_change: impl Fn(f32) -> Message + 'a,
+    ) -> Speedometer<'a, Message, Theme> {
+        Speedometer {
+            id: self.id,
+            speed: self.speed,
+            needle: self.needle,
+            on_change: Some(Box::new(on_change)),
+            step: self.step,
+            scale: self.scale,
+            ticks: self.ticks,
+            zones: self.zones,
+            decimals: self.decimals,
+            formatter: self.formatter,
+            active: self.active,
+            width: self.width,
+            height: self.height,
+            class: self.class,
+        }
+    }
+}
+
+impl<'a, Message, Theme: Catalog> Speedometer<'a, Message, Theme> {
+    /// Identifies the gauge for focus operations, e.g. to focus it from a `Task`.
+    pub fn id(mut self, id: impl Into<widget::Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Where the needle is drawn while it eases towards the speed, e.g.
+    /// [`Animation::value`], by default at the speed. The wheel, the keys and the
+    /// readout always go by the speed.
+    pub fn needle<T>(mut self, needle: T) -> Self
+    where
+        f64: From<T>,
+    {
+        self.needle = Some(f64::from(needle) as f32);
+        self
+    }
+
+    /// Change of the value per arrow key press or wheel notch, by default the minor
+    /// tick step. Dragging the needle is not stepped, a step that is not finite is
+    /// ignored.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.is_finite().then_some(step).or(self.step);
+        self
+    }
+
     pub fn width(mut self, width: impl Into<Length>) -> Self {
         self.width = width.into();
         self
@@ -39,176 +135,790 @@ impl Speedometer {
         self
     }
 
-    pub fn view<'a>(self) -> Element<'a, ()> {
-        let width = self.width;
-        let height = self.height;
+    /// Value the needle rests at, marked with a detent. Use `0` for bipolar scales.
+    pub fn origin<T>(mut self, origin: T) -> Self
+    where
+        f64: From<T>,
+    {
+        self.scale = self.scale.with_origin(f64::from(origin) as f32);
+        self
+    }
+
+    /// Tick layout, by default about ten labeled major ticks over the range.
+    pub fn ticks(mut self, ticks: TickSpec) -> Self {
+        self.ticks = ticks;
+        self
+    }
 
-        canvas(self).width(width).height(height).into()
+    /// Adds a coloured band, later zones are drawn on top of earlier ones.
+    pub fn zone(mut self, zone: Zone<Color>) -> Self {
+        self.zones.push(zone);
+        self
     }
-}
 
-pub fn speedometer(min_speed: u32, max_speed: u32, speed: u32) -> Speedometer {
-    Speedometer::new(min_speed, max_speed, speed)
-}
+    /// Number of decimals shown in the tick labels.
+    pub fn fixed_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
 
-impl canvas::Program<()> for Speedometer {
-    type State = ();
+    /// Formats the tick labels, e.g. to add a unit. Overrides [`Self::fixed_decimals`].
+    pub fn custom_formatter(mut self, formatter: impl Fn(f64) -> String + 'a) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
 
-    fn draw(
+    /// An inactive gauge is faded into the background, e.g. while its source is silent.
+    pub fn active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+
+    /// Sets the style of the gauge.
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the gauge, for themes with classes of their own.
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    fn format_label(&self, value: f32) -> String {
+        match &self.formatter {
+            Some(formatter) => formatter(value as f64),
+            None => format_value(value as f64, self.decimals),
+        }
+    }
+
+    /// Change per arrow key press or wheel notch: the step if it moves the value,
+    /// else the minor tick step, else a hundredth of the range.
+    fn key_step(&self) -> f32 {
+        let minor = self.ticks.minor_step();
+        let range = (self.scale.max() - self.scale.min()).abs();
+
+        self.step
+            .filter(|&step| step != 0.0)
+            .unwrap_or(if minor.is_finite() && minor > 0.0 {
+                minor
+            } else {
+                range / 100.0
+            })
+    }
+
+    fn needle_value(&self) -> f32 {
+        self.needle.unwrap_or(self.speed)
+    }
+
+    fn status(&self, state: &State) -> Status {
+        if !self.active {
+            Status::Inactive
+        } else if state.dragging {
+            Status::Dragged
+        } else if state.hovered {
+            Status::Hovered
+        } else {
+            Status::Active
+        }
+    }
+
+    /// Hash of what the dial looks like. The labels are hashed as text since a
+    /// custom formatter can't be compared.
+    fn dial_key(&self, style: &Style) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        [style.face, style.rim, style.ticks, style.needle, style.cap]
+            .map(Color::into_rgba8)
+            .hash(&mut hasher);
+        style.cap_rim.map(Color::into_rgba8).hash(&mut hasher);
+
+        for tick in self.scale.ticks(&self.ticks) {
+            [tick.value, tick.angle].map(f32::to_bits).hash(&mut hasher);
+            (tick.major, tick.labeled).hash(&mut hasher);
+            if tick.labeled {
+                self.format_label(tick.value).hash(&mut hasher);
+            }
+        }
+        self.scale
+            .origin()
+            .map(|origin| self.scale.angle(origin).to_bits())
+            .hash(&mut hasher);
+        for zone in &self.zones {
+            [zone.from, zone.to, zone.thickness]
+                .map(f32::to_bits)
+                .hash(&mut hasher);
+            zone.color.into_rgba8().hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Hash of what the needle layer looks like.
+    fn needle_key(&self, style: &Style, status: Status, readout: bool) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.scale
+            .angle(self.needle_value())
+            .to_bits()
+            .hash(&mut hasher);
+        (status == Status::Inactive, readout).hash(&mut hasher);
+        readout.then_some(self.speed.to_bits()).hash(&mut hasher);
+        [style.needle, style.readout, style.veil]
+            .map(Color::into_rgba8)
+            .hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// The dial and the needle, drawn from their caches when they have not changed.
+    fn layers(
         &self,
-        _state: &Self::State,
+        state: &State,
         renderer: &Renderer,
         theme: &Theme,
-        bounds: Rectangle,
-        _cursor: mouse::Cursor,
-    ) -> Vec<canvas::Geometry> {
-        let speedometer = self.cache.draw(renderer, bounds.size(), |frame| {
-            let palette = theme.extended_palette();
+        size: Size,
+    ) -> [canvas::Geometry; 2] {
+        let status = self.status(state);
+        let style = theme.style(&self.class, status);
+        let readout = self.on_change.is_some() && (state.hovered || state.dragging);
+        state.invalidate(
+            self.dial_key(&style),
+            self.needle_key(&style, status, readout),
+        );
 
+        let dial = state.dial.draw(renderer, size, |frame| {
             let center = frame.center();
-            let radius = frame.width().min(frame.height()) / 2.0 - 9.0;
+            let radius = dial_radius(frame);
             let scale = frame.width().min(frame.height()) / 694.0;
             let width = radius / 100.0;
 
-            draw_background(frame, center, radius, palette);
-            draw_needle_cap(frame, center, radius);
+            draw_background(frame, center, radius, &style);
+            draw_needle_cap(frame, center, radius, &style);
 
             frame.translate(Vector::new(center.x, center.y));
-            draw_ticks(frame, self.min_speed, self.max_speed, width, scale, radius, &palette);
-            draw_needle(frame, self.speed, self.max_speed, width, radius, &palette);
+            draw_zones(frame, &self.scale, &self.zones, radius);
+            draw_ticks(frame, self, width, scale, radius, &style);
         });
 
-        vec![speedometer]
+        let needle = state.needle.draw(renderer, size, |frame| {
+            let center = frame.center();
+            let radius = dial_radius(frame);
+            let scale = frame.width().min(frame.height()) / 694.0;
+
+            frame.translate(Vector::new(center.x, center.y));
+            draw_needle(
+                frame,
+                &self.scale,
+                self.needle_value(),
+                radius / 100.0,
+                radius,
+                &style,
+            );
+
+            if readout {
+                let decimals = step_decimals(self.key_step() as f64);
+                let speed = format_value(self.speed as f64, decimals);
+                draw_readout(frame, speed, radius, scale, &style);
+            }
+            if status == Status::Inactive {
+                draw_inactive_veil(frame, radius, &style);
+            }
+        });
+
+        [dial, needle]
+    }
+}
+
+/// Widget state of the [`Speedometer`], kept by iced across `view` calls.
+///
+/// The static dial and the needle are cached apart, so a moving needle leaves the
+/// dial alone. Each layer is cleared when the hash of what it shows changes, or
+/// by the cache itself when the bounds change size.
+///
+/// It also follows the pointer and the focus of a gauge with
+/// [`Speedometer::on_change`].
+#[derive(Default)]
+pub struct State {
+    dial: Cache,
+    needle: Cache,
+    dial_key: Cell<Option<u64>>,
+    needle_key: Cell<Option<u64>>,
+    hovered: bool,
+    /// The needle follows the pointer or the finger until it is released.
+    dragging: bool,
+    /// Set by a press on the dial or a focus operation, cleared by a press
+    /// elsewhere. The arrow keys only move the value of a focused gauge.
+    focused: bool,
+}
+
+impl State {
+    fn invalidate(&self, dial_key: u64, needle_key: u64) {
+        if self.dial_key.replace(Some(dial_key)) != Some(dial_key) {
+            self.dial.clear();
+        }
+        if self.needle_key.replace(Some(needle_key)) != Some(needle_key) {
+            self.needle.clear();
+        }
+    }
+}
+
+impl operation::Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.focused = false;
+    }
+}
+
+pub fn speedometer<'a, T, Theme>(speed: T, min_speed: T, max_speed: T) -> Speedometer<'a, (), Theme>
+where
+    T: Copy,
+    f64: From<T>,
+    Theme: Catalog,
+{
+    Speedometer::new(speed, min_speed, max_speed)
+}
+
+impl<Message, Theme: Catalog> Widget<Message, Theme, Renderer> for Speedometer<'_, Message, Theme> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(self.width, self.height)
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        _layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        if self.on_change.is_some() {
+            let state = tree.state.downcast_mut::<State>();
+            operation.focusable(state, self.id.as_ref());
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> event::Status {
+        // A gauge whose bounds are not finite can't be edited.
+        let Some(on_change) = self
+            .on_change
+            .as_ref()
+            .filter(|_| self.scale.min().is_finite() && self.scale.max().is_finite())
+        else {
+            return event::Status::Ignored;
+        };
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+        let step = self.key_step();
+        let center = bounds.center();
+        let pointed = |position: Point| {
+            self.scale
+                .value_at(position.x - center.x, position.y - center.y)
+        };
+        let before = self.status(state);
+
+        let value = match event {
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                state.hovered = cursor.is_over(bounds);
+                state.dragging.then(|| pointed(position))
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                state.focused = cursor.is_over(bounds);
+                state.dragging = state.focused;
+                cursor.position_over(bounds).map(pointed)
+            }
+            Event::Touch(touch::Event::FingerMoved { position, .. }) => {
+                state.dragging.then(|| pointed(position))
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. }) => {
+                state.dragging = false;
+                None
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if state.hovered => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                };
+                Some(self.speed + step * lines)
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key),
+                ..
+            }) if state.focused => match key {
+                Named::ArrowUp | Named::ArrowRight => Some(self.speed + step),
+                Named::ArrowDown | Named::ArrowLeft => Some(self.speed - step),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        // The style and the readout follow the hover and the drag.
+        if self.status(state) != before {
+            shell.request_redraw(window::RedrawRequest::NextFrame);
+        }
+
+        match value.map(|value| self.scale.clamp(value)) {
+            Some(value) => {
+                shell.publish(on_change(value));
+                event::Status::Captured
+            }
+            None => event::Status::Ignored,
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let layers = self.layers(state, renderer, theme, bounds.size());
+
+        renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
+            for layer in layers {
+                renderer.draw_geometry(layer);
+            }
+        });
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+
+        if self.on_change.is_none() {
+            mouse::Interaction::default()
+        } else if state.dragging {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a, Message: 'a, Theme: Catalog + 'a> From<Speedometer<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
+{
+    fn from(speedometer: Speedometer<'a, Message, Theme>) -> Self {
+        Element::new(speedometer)
+    }
+}
+
+/// The state of a [`Speedometer`] its style depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Active,
+    /// The pointer is over an interactive gauge.
+    Hovered,
+    /// The needle is being dragged.
+    Dragged,
+    /// The gauge is faded into the background, see [`Speedometer::active`].
+    Inactive,
+}
+
+/// The colours of a [`Speedometer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// Fill of the dial face.
+    pub face: Color,
+    /// The ring around the face.
+    pub rim: Color,
+    /// Ticks and labels outside the zones.
+    pub ticks: Color,
+    /// The needle and the origin detent.
+    pub needle: Color,
+    /// Disc under the pivot of the needle.
+    pub cap: Color,
+    /// Shading of the rim of the cap, from its top through its middle to its bottom.
+    pub cap_rim: [Color; 3],
+    /// The value shown while an interactive gauge is hovered.
+    pub readout: Color,
+    /// Laid over an inactive gauge, usually translucent.
+    pub veil: Color,
+}
+
+/// The theme catalog of a [`Speedometer`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`Speedometer`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`Speedometer`], from the extended palette of the theme.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let palette = theme.extended_palette();
+
+    let rim = match status {
+        Status::Active | Status::Inactive => palette.background.strong.color,
+        Status::Hovered => palette.primary.weak.color,
+        Status::Dragged => palette.primary.base.color,
+    };
+
+    Style {
+        face: palette.background.weak.color,
+        rim,
+        ticks: palette.secondary.base.text,
+        needle: palette.danger.base.color,
+        cap: CAP,
+        cap_rim: CAP_RIM,
+        readout: palette.background.base.text,
+        veil: Color {
+            a: 0.65,
+            ..palette.background.base.color
+        },
+    }
+}
+
+/// Fill of the needle cap of the default style.
+const CAP: Color = Color::from_rgb(
+    0x14 as f32 / 255.0,
+    0x18 as f32 / 255.0,
+    0x1B as f32 / 255.0,
+);
+
+/// Shading of the rim of the needle cap of the default style, dark for every theme.
+const CAP_RIM: [Color; 3] = [
+    Color::from_rgb(
+        0x14 as f32 / 255.0,
+        0x18 as f32 / 255.0,
+        0x1C as f32 / 255.0,
+    ),
+    Color::from_rgb(
+        0x24 as f32 / 255.0,
+        0x24 as f32 / 255.0,
+        0x26 as f32 / 255.0,
+    ),
+    Color::BLACK,
+];
+
+/// Needle that eases towards the speed, kept in the application state.
+///
+/// Feed it [`Animation::subscription`] frames through [`Animation::tick`] and draw
+/// the needle of the speedometer at [`Animation::value`] with [`Speedometer::needle`]. Speeds off the scale hold the needle
+/// at its end, and speeds that are not finite are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    needle: Needle,
+    scale: Scale,
+    /// The last finite speed on the scale.
+    target: f32,
+    last_frame: Option<Instant>,
+}
+
+impl Animation {
+    /// A needle resting at `speed`, which crosses the scale in half a second.
+    pub fn new<T>(speed: T, min_speed: T, max_speed: T) -> Self
+    where
+        f64: From<T>,
+    {
+        let (min_speed, max_speed) = (f64::from(min_speed) as f32, f64::from(max_speed) as f32);
+        let scale = Scale::new(min_speed, max_speed);
+        let speed = f64::from(speed) as f32;
+        let target = if speed.is_finite() {
+            scale.clamp(speed)
+        } else {
+            scale.clamp(min_speed)
+        };
+
+        Self {
+            needle: Needle::new(target, NeedleDynamics::for_range(min_speed, max_speed)),
+            scale,
+            target,
+            last_frame: None,
+        }
+    }
+
+    pub fn with_dynamics(mut self, dynamics: NeedleDynamics) -> Self {
+        self.needle = self.needle.with_dynamics(dynamics);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.needle.value()
+    }
+
+    pub fn is_moving<T>(&self, speed: T) -> bool
+    where
+        f64: From<T>,
+    {
+        !self.needle.is_settled(self.target(speed))
+    }
+
+    /// Steps the needle towards `speed` up to the frame at `now`.
+    pub fn tick<T>(&mut self, speed: T, now: Instant)
+    where
+        f64: From<T>,
+    {
+        self.target = self.target(speed);
+        let dt = self
+            .last_frame
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        let moving = self.needle.step(self.target, dt);
+
+        self.last_frame = moving.then_some(now);
+    }
+
+    /// Window frames while the needle is moving, nothing once it has settled.
+    pub fn subscription<T>(&self, speed: T) -> Subscription<Instant>
+    where
+        f64: From<T>,
+    {
+        if self.is_moving(speed) {
+            window::frames()
+        } else {
+            Subscription::none()
+        }
     }
+
+    fn target<T>(&self, speed: T) -> f32
+    where
+        f64: From<T>,
+    {
+        let speed = f64::from(speed) as f32;
+        if speed.is_finite() {
+            self.scale.clamp(speed)
+        } else {
+            self.target
+        }
+    }
+}
+
+/// Radius of the dial face, inside its border.
+fn dial_radius(frame: &Frame<Renderer>) -> f32 {
+    frame.width().min(frame.height()) / 2.0 - 9.0
 }
 
-fn draw_background(frame: &mut Frame<Renderer>, center: Point, radius: f32, palette: &Extended) {
-    let background = Path::circle(center, radius);
-    frame.fill(&background, palette.background.weak.color);
+fn draw_background(frame: &mut Frame<Renderer>, center: Point, radius: f32, style: &Style) {
+    // The border lies between the face and the edge of the frame.
+    let background = Path::circle(center, radius + 4.5);
+    frame.fill(&background, style.face);
     frame.stroke(
         &background,
-        Stroke::default()
-            .with_color(palette.background.strong.color)
-            .with_width(9.0),
+        Stroke::default().with_color(style.rim).with_width(9.0),
     );
 }
 
+/// The value, to the decimals of the step, in the gap at the bottom of the dial,
+/// while the pointer is over an interactive gauge.
+fn draw_readout(
+    frame: &mut Frame<Renderer>,
+    speed: String,
+    radius: f32,
+    scale: f32,
+    style: &Style,
+) {
+    let text = Text {
+        content: speed,
+        color: style.readout,
+        size: (28.0 * scale).into(),
+        position: Point::new(0.0, 0.7 * radius),
+        horizontal_alignment: alignment::Horizontal::Center,
+        vertical_alignment: alignment::Vertical::Center,
+        ..Text::default()
+    };
+
+    text.draw_with(|path, color| {
+        frame.fill(&path, color);
+    });
+}
+
+fn draw_inactive_veil(frame: &mut Frame<Renderer>, radius: f32, style: &Style) {
+    let veil = Path::circle(Point::ORIGIN, radius + 9.0);
+    frame.fill(&veil, style.veil);
+}
+
 fn draw_needle(
     frame: &mut Frame<Renderer>,
-    speed: u32,
-    max_speed: u32,
+    scale: &Scale,
+    speed: f32,
     width: f32,
     radius: f32,
-    palette: &Extended,
+    style: &Style,
 ) {
-    let needle = Path::line(
-        Point::new(0.0, 0.12 * radius),
-        Point::new(0.0, -0.88 * radius),
-    );
-
-    let needle_stroke = || -> Stroke {
-        Stroke {
-            width: width * 1.6,
-            style: Style::Solid(palette.secondary.base.color),
-            line_cap: LineCap::Round,
-            ..Stroke::default()
-        }
-    };
+    let needle = radial_bar(0.12 * radius, -0.88 * radius, width * 1.6);
 
     frame.with_save(|frame| {
-        frame.rotate(hand_rotation(speed, max_speed));
-        frame.stroke(&needle, needle_stroke());
+        frame.rotate(Degrees(scale.angle(speed)));
+        frame.fill(&needle, style.needle);
     });
 }
 
-fn draw_ticks(
+/// Bar along the y axis from `from` to `to` with round ends, like a line stroked
+/// `width` wide with round caps, to be rotated into place.
+///
+/// It is filled rather than stroked since a line along an axis has empty bounds,
+/// which renderers may cull before the stroke widens it.
+fn radial_bar(from: f32, to: f32, width: f32) -> Path {
+    let (top, bottom) = (from.min(to), from.max(to));
+
+    Path::rounded_rectangle(
+        Point::new(-width / 2.0, top - width / 2.0),
+        Size::new(width, bottom - top + width),
+        (width / 2.0).into(),
+    )
+}
+
+fn draw_zones(
     frame: &mut Frame<Renderer>,
-    min_speed: u32,
-    max_speed: u32,
+    gauge_scale: &Scale,
+    zones: &[Zone<Color>],
+    radius: f32,
+) {
+    for zone in zones {
+        let thickness = zone.thickness * radius;
+        let band = Path::new(|builder| {
+            builder.arc(Arc {
+                center: Point::ORIGIN,
+                radius: radius - 9.0 - 16.0 - thickness / 2.0,
+                start_angle: Degrees(gauge_scale.angle(zone.from) - 90.0).into(),
+                end_angle: Degrees(gauge_scale.angle(zone.to) - 90.0).into(),
+            });
+        });
+
+        frame.stroke(
+            &band,
+            Stroke {
+                width: thickness,
+                style: stroke::Style::Solid(zone.color),
+                ..Stroke::default()
+            },
+        );
+    }
+}
+
+fn draw_ticks<Message, Theme: Catalog>(
+    frame: &mut Frame<Renderer>,
+    speedometer: &Speedometer<'_, Message, Theme>,
     width: f32,
     scale: f32,
     radius: f32,
-    palette: &Extended,
+    style: &Style,
 ) {
-    let short_ticks = Path::line(
-        Point::new(0.0, -radius + 9.0 + 16.0),
-        Point::new(0.0, -0.86 * radius),
-    );
-    let long_ticks = Path::line(
-        Point::new(0.0, -radius + 9.0 + 16.0),
-        Point::new(0.0, -0.85 * radius),
-    );
+    let short_tick = radial_bar(-radius + 9.0 + 16.0, -0.86 * radius, width);
+    let long_tick = radial_bar(-radius + 9.0 + 16.0, -0.85 * radius, width * 2.0);
 
-    let thin_stroke = || -> Stroke {
-        Stroke {
-            width,
-            style: stroke::Style::Solid(palette.secondary.base.text),
-            line_cap: LineCap::Round,
-            ..Stroke::default()
-        }
-    };
+    let gauge_scale = &speedometer.scale;
 
-    let wide_stroke = || -> Stroke {
-        Stroke {
-            width: width * 2.0,
-            style: stroke::Style::Solid(palette.secondary.base.text),
-            line_cap: LineCap::Round,
-            ..Stroke::default()
-        }
-    };
+    for tick in gauge_scale.ticks(&speedometer.ticks) {
+        let color = tint(&speedometer.zones, tick.value)
+            .copied()
+            .unwrap_or(style.ticks);
 
-    for tick in (min_speed..=max_speed).step_by(10) {
-        if tick % 20 == 0 {
-            frame.with_save(|frame| {
-                frame.rotate(hand_rotation(tick, max_speed));
-                frame.stroke(&long_ticks, wide_stroke());
-            });
-            draw_tick_label(frame, tick, radius, scale, max_speed);
-        } else {
-            frame.with_save(|frame| {
-                frame.rotate(hand_rotation(tick, max_speed));
-                frame.stroke(&short_ticks, thin_stroke());
-            });
+        frame.with_save(|frame| {
+            frame.rotate(Degrees(tick.angle));
+            frame.fill(if tick.major { &long_tick } else { &short_tick }, color);
+        });
+
+        if tick.labeled {
+            draw_tick_label(
+                frame,
+                gauge_scale,
+                &tick,
+                speedometer.format_label(tick.value),
+                color,
+                radius,
+                scale,
+            );
         }
     }
+
+    if let Some(origin) = gauge_scale.origin() {
+        let detent = radial_bar(-radius + 9.0 + 16.0, -0.8 * radius, width * 2.5);
+
+        frame.with_save(|frame| {
+            frame.rotate(Degrees(gauge_scale.angle(origin)));
+            frame.fill(&detent, style.needle);
+        });
+    }
 }
 
-fn draw_needle_cap(frame: &mut Frame<Renderer>, center: Point, radius: f32) {
+fn draw_needle_cap(frame: &mut Frame<Renderer>, center: Point, radius: f32, style: &Style) {
     let needle_cap = Path::circle(center, radius * 0.1);
-    frame.fill(
-        &needle_cap,
-        Color::from_rgb(
-            0x14 as f32 / 255.0,
-            0x18 as f32 / 255.0,
-            0x1B as f32 / 255.0,
-        ),
-    );
+    frame.fill(&needle_cap, style.cap);
 
     frame.stroke(
         &needle_cap,
         Stroke {
-            style: Style::Gradient(
+            style: stroke::Style::Gradient(
                 gradient::Linear::new(
                     Point::new(center.x, center.y - radius * 0.1),
                     Point::new(center.x, center.y + radius * 0.1),
                 )
-                .add_stop(
-                    0.0,
-                    Color::from_rgb(
-                        0x14 as f32 / 255.0,
-                        0x18 as f32 / 255.0,
-                        0x1C as f32 / 255.0,
-                    ),
-                )
-                .add_stop(
-                    0.46,
-                    Color::from_rgb(
-                        0x24 as f32 / 255.0,
-                        0x24 as f32 / 255.0,
-                        0x26 as f32 / 255.0,
-                    ),
-                )
-                .add_stop(0.98, Color::BLACK)
+                .add_stop(0.0, style.cap_rim[0])
+                .add_stop(0.46, style.cap_rim[1])
+                .add_stop(0.98, style.cap_rim[2])
                 .into(),
             ),
             ..Default::default()
@@ -219,21 +929,19 @@ fn draw_needle_cap(frame: &mut Frame<Renderer>, center: Point, radius: f32) {
 
 fn draw_tick_label(
     frame: &mut Frame<Renderer>,
-    tick: u32,
+    gauge_scale: &Scale,
+    tick: &Tick,
+    label: String,
+    color: Color,
     radius: f32,
     scale: f32,
-    max_speed: u32,
 ) {
-    let radians: Radians = tick_label_rotation(tick, max_speed).into();
+    let (x, y) = gauge_scale.point_at(tick.value, 0.76 * radius);
     let text = Text {
-        content: format!("{tick:<3}"),
-        color: Color::WHITE,
-        // size: (0.07 * radius).into(),
+        content: format!("{label:<3}"),
+        color,
         size: (24.0 * scale).into(),
-        position: Point::new(
-            0.76 * radius * radians.0.cos(),
-            0.76 * radius * radians.0.sin(),
-        ),
+        position: Point::new(x, y),
         horizontal_alignment: alignment::Horizontal::Center,
         vertical_alignment: alignment::Vertical::Center,
         font: Font {
@@ -250,16 +958,269 @@ fn draw_tick_label(
     });
 }
 
-const SECTOR_DEGREES: f32 = 100.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::advanced::clipboard;
+    use iced::Pixels;
 
-fn hand_rotation(n: u32, total: u32) -> Degrees {
-    let turns = n as f32 / total as f32;
+    const BOUNDS: Rectangle = Rectangle {
+        x: 0.0,
+        y: 0.0,
+        width: 200.0,
+        height: 200.0,
+    };
 
-    Degrees((360.0 - SECTOR_DEGREES) * turns - (180.0 - SECTOR_DEGREES / 2.0))
-}
+    /// A gauge laid out in [`BOUNDS`], with its widget state.
+    struct Mounted<'a, Message> {
+        gauge: Speedometer<'a, Message>,
+        tree: Tree,
+        node: layout::Node,
+        renderer: Renderer,
+    }
+
+    impl<'a, Message: std::fmt::Debug + 'a> Mounted<'a, Message> {
+        fn new(gauge: Speedometer<'a, Message>) -> Self {
+            let tree = Tree {
+                tag: Widget::<Message, Theme, Renderer>::tag(&gauge),
+                state: Widget::<Message, Theme, Renderer>::state(&gauge),
+                children: Vec::new(),
+            };
+            let tiny_skia = iced_tiny_skia::Renderer::new(Font::DEFAULT, Pixels(16.0));
+
+            Self {
+                gauge,
+                tree,
+                node: layout::Node::new(BOUNDS.size()),
+                renderer: Renderer::Secondary(tiny_skia),
+            }
+        }
+
+        fn send(&mut self, event: Event, cursor: Point) -> Option<Message> {
+            let mut messages = Vec::new();
+            self.gauge.on_event(
+                &mut self.tree,
+                event,
+                Layout::new(&self.node),
+                mouse::Cursor::Available(cursor),
+                &self.renderer,
+                &mut clipboard::Null,
+                &mut Shell::new(&mut messages),
+                &BOUNDS,
+            );
+            assert!(messages.len() <= 1, "{messages:?}");
+            messages.pop()
+        }
+
+        fn state(&self) -> &State {
+            self.tree.state.downcast_ref::<State>()
+        }
+
+        fn interaction(&self, cursor: Point) -> mouse::Interaction {
+            self.gauge.mouse_interaction(
+                &self.tree,
+                Layout::new(&self.node),
+                mouse::Cursor::Available(cursor),
+                &BOUNDS,
+                &self.renderer,
+            )
+        }
+    }
+
+    fn key(key: Named) -> Event {
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(key),
+            modified_key: keyboard::Key::Named(key),
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Standard,
+            modifiers: keyboard::Modifiers::empty(),
+            text: None,
+        })
+    }
+
+    #[test]
+    fn drags_the_needle() {
+        let mut gauge = Mounted::new(speedometer(20.0, 0.0, 200.0).on_change(|value| value));
+        let top = Point::new(100.0, 40.0);
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+
+        assert_eq!(gauge.send(press.clone(), top), Some(100.0));
+        assert_eq!(gauge.interaction(top), mouse::Interaction::Grabbing);
+        assert_eq!(gauge.gauge.status(gauge.state()), Status::Dragged);
+
+        // Straight down is in the empty sector, nearer the end on its side.
+        let below = Point::new(101.0, 160.0);
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position: below });
+        assert_eq!(gauge.send(moved, below), Some(200.0));
+
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+        assert_eq!(gauge.send(release, below), None);
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position: top });
+        assert_eq!(gauge.send(moved, top), None);
+        assert_eq!(gauge.gauge.status(gauge.state()), Status::Hovered);
+
+        // Without `on_change` the gauge is only drawn.
+        let mut display = Mounted::new(speedometer(20.0, 0.0, 200.0));
+        assert_eq!(display.send(press, top), None);
+        assert!(!display.state().dragging);
+        assert_eq!(display.interaction(top), mouse::Interaction::default());
+    }
+
+    #[test]
+    fn steps_with_the_wheel_and_the_keys() {
+        let id = widget::Id::new("gauge");
+        let mut gauge = Mounted::new(
+            speedometer(100.0, 0.0, 200.0)
+                .on_change(|value| value)
+                .step(5.0)
+                .id(id.clone()),
+        );
+        let center = Point::new(100.0, 100.0);
+        let outside = Point::new(300.0, 100.0);
+
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position: center });
+        assert_eq!(gauge.send(moved, center), None);
+        let scrolled = Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: 0.0, y: 2.0 },
+        });
+        assert_eq!(gauge.send(scrolled, center), Some(110.0));
+
+        // The keys wait for the gauge to be focused, by a press on the dial...
+        assert_eq!(gauge.send(key(Named::ArrowUp), center), None);
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+        gauge.send(press.clone(), center);
+        gauge.send(release, center);
+        assert_eq!(gauge.send(key(Named::ArrowLeft), center), Some(95.0));
 
-fn tick_label_rotation(n: u32, total: u32) -> Degrees {
-    let turns = n as f32 / total as f32;
+        gauge.send(press, outside);
+        assert_eq!(gauge.send(key(Named::ArrowUp), center), None);
 
-    Degrees((360.0 - SECTOR_DEGREES) * turns - 220.0)
+        // ...or by a focus operation.
+        let mut focus = operation::focusable::focus::<()>(id);
+        gauge.gauge.operate(
+            &mut gauge.tree,
+            Layout::new(&gauge.node),
+            &gauge.renderer,
+            &mut focus,
+        );
+        assert_eq!(gauge.send(key(Named::ArrowUp), outside), Some(105.0));
+    }
+
+    #[test]
+    fn falls_back_to_a_hundredth_of_the_range() {
+        let center = Point::new(100.0, 100.0);
+        let moved = Event::Mouse(mouse::Event::CursorMoved { position: center });
+        let scrolled = Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: 0.0, y: 1.0 },
+        });
+        let scroll = |gauge: Speedometer<'static>| {
+            let mut gauge = Mounted::new(gauge.on_change(|value| value));
+            gauge.send(moved.clone(), center);
+            gauge.send(scrolled.clone(), center)
+        };
+
+        let empty = TickSpec::new(0.0);
+        assert_eq!(
+            scroll(speedometer(100.0, 0.0, 200.0).ticks(empty)),
+            Some(102.0)
+        );
+        assert_eq!(
+            scroll(speedometer(100.0, 0.0, 200.0).ticks(empty).step(0.0)),
+            Some(102.0)
+        );
+
+        // The minor tick step, 10 on this scale, rather than a step that is not finite
+        // or zero.
+        assert_eq!(
+            scroll(speedometer(100.0, 0.0, 200.0).step(f32::NAN)),
+            Some(110.0)
+        );
+        assert_eq!(
+            scroll(speedometer(100.0, 0.0, 200.0).step(0.0)),
+            Some(110.0)
+        );
+
+        // Nor is a gauge whose bounds are not finite edited.
+        assert_eq!(scroll(speedometer(100.0, f32::NAN, f32::NAN)), None);
+    }
+
+    #[test]
+    fn steps_from_the_speed_while_the_needle_eases() {
+        fn gauge<'a>(speed: f32, needle: &Animation, id: &widget::Id) -> Speedometer<'a, f32> {
+            speedometer(speed, 0.0, 200.0)
+                .needle(needle.value())
+                .on_change(|value| value)
+                .step(5.0)
+                .id(id.clone())
+        }
+
+        let id = widget::Id::new("gauge");
+        let center = Point::new(100.0, 100.0);
+        let start = Instant::now();
+        let mut speed = 100.0;
+        let mut needle = Animation::new(speed, 0.0, 200.0);
+        let mut mounted = Mounted::new(gauge(speed, &needle, &id));
+        let mut focus = operation::focusable::focus::<()>(id.clone());
+        mounted.gauge.operate(
+            &mut mounted.tree,
+            Layout::new(&mounted.node),
+            &mounted.renderer,
+            &mut focus,
+        );
+
+        for frame in 1..=2 {
+            speed = mounted.send(key(Named::ArrowUp), center).unwrap();
+            needle.tick(speed, start + std::time::Duration::from_millis(16 * frame));
+            mounted.gauge = gauge(speed, &needle, &id);
+        }
+
+        assert_eq!(speed, 110.0);
+        assert!(needle.is_moving(speed) && needle.value() < 105.0);
+    }
+
+    #[test]
+    fn animation_stays_on_the_scale() {
+        let start = Instant::now();
+        let mut needle = Animation::new(0.0, 0.0, 200.0);
+
+        needle.tick(f32::NAN, start);
+        assert!(!needle.is_moving(f32::NAN));
+        assert_eq!(needle.value(), 0.0);
+
+        let mut now = start;
+        while needle.is_moving(250.0) && now < start + std::time::Duration::from_secs(5) {
+            now += std::time::Duration::from_millis(16);
+            needle.tick(250.0, now);
+        }
+        assert_eq!(needle.value(), 200.0);
+
+        // A lost sample leaves the needle where it was.
+        needle.tick(f32::INFINITY, now);
+        assert_eq!(needle.value(), 200.0);
+        assert!(!needle.is_moving(f32::NAN));
+    }
+
+    #[test]
+    fn styles_by_status() {
+        let gauge = speedometer(20.0, 0.0, 200.0)
+            .active(false)
+            .style(|theme: &Theme, status| Style {
+                needle: Color::WHITE,
+                ..default(theme, status)
+            });
+        let state = State::default();
+        let status = gauge.status(&state);
+        let style = Theme::Dark.style(&gauge.class, status);
+
+        assert_eq!(status, Status::Inactive);
+        assert_eq!(style.needle, Color::WHITE);
+        assert_eq!(style.face, default(&Theme::Dark, status).face);
+        assert_ne!(
+            gauge.needle_key(&style, status, false),
+            gauge.needle_key(&style, Status::Active, false)
+        );
+    }
 }
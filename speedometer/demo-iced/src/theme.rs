@@ -1,96 +1,350 @@
-use iced::theme::{palette, Palette};
+use crate::speedometer::{self, Status};
+use iced::theme::palette::{Background, Danger, Extended, Pair, Primary, Secondary, Success};
+use iced::theme::Palette;
 use iced::Color;
-use once_cell::sync::Lazy;
+use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
+/// The looks of the dashboard, with the full extended palette of each, so that
+/// the built-in widgets and the speedometer match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Theme {
+    #[default]
     Dark,
+    Light,
+    /// Black and white with saturated accents, for direct sunlight or poor sight.
+    HighContrast,
+    /// Dim reds only, which keep the eyes of the driver adapted to the dark.
+    Night,
 }
 
 impl Theme {
+    pub const ALL: [Self; 4] = [Self::Dark, Self::Light, Self::HighContrast, Self::Night];
+
+    /// The theme after this one in [`Theme::ALL`], for a shortcut cycling through them.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|theme| *theme == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
     pub fn palette(&self) -> Palette {
         match self {
             Self::Dark => DARK,
+            Self::Light => LIGHT,
+            Self::HighContrast => HIGH_CONTRAST,
+            Self::Night => NIGHT,
         }
     }
 
-    pub fn extended_palette(&self) -> palette::Extended {
+    pub fn extended_palette(&self) -> Extended {
         match self {
-            Theme::Dark => *EXTENDED_DARK,
+            Self::Dark => EXTENDED_DARK,
+            Self::Light => EXTENDED_LIGHT,
+            Self::HighContrast => EXTENDED_HIGH_CONTRAST,
+            Self::Night => EXTENDED_NIGHT,
+        }
+    }
+
+    /// The iced theme of the dashboard, which the built-in widgets follow.
+    pub fn iced(&self) -> iced::Theme {
+        let extended = self.extended_palette();
+        iced::Theme::custom_with_fn(self.to_string(), self.palette(), move |_| extended)
+    }
+
+    /// The dial of the dashboard, for [`Speedometer::style`](crate::Speedometer::style).
+    ///
+    /// The face is the weak background with its text for the ticks, the needle is
+    /// the danger colour, the cap is the background with a rim shaded through the
+    /// strong background, and the rim of the dial lights up in the primary colour
+    /// under the pointer.
+    pub fn speedometer(&self, status: Status) -> speedometer::Style {
+        let palette = self.extended_palette();
+
+        speedometer::Style {
+            face: palette.background.weak.color,
+            rim: match status {
+                Status::Active | Status::Inactive => palette.background.strong.color,
+                Status::Hovered => palette.primary.base.color,
+                Status::Dragged => palette.primary.strong.color,
+            },
+            ticks: palette.background.weak.text,
+            needle: palette.danger.base.color,
+            cap: palette.background.base.color,
+            cap_rim: [
+                palette.background.weak.color,
+                palette.background.strong.color,
+                palette.background.base.color,
+            ],
+            readout: palette.background.weak.text,
+            veil: Color {
+                a: 0.65,
+                ..palette.background.base.color
+            },
         }
     }
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Self::Dark
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+            Self::HighContrast => "High contrast",
+            Self::Night => "Night",
+        })
     }
 }
 
 pub const DARK: Palette = Palette {
-    background: Color::from_rgb(
-        0x20 as f32 / 255.0,
-        0x22 as f32 / 255.0,
-        0x25 as f32 / 255.0,
-    ),
-    text: Color::from_rgb(0.90, 0.90, 0.90),
-    primary: Color::from_rgb(
-        0x5E as f32 / 255.0,
-        0x7C as f32 / 255.0,
-        0xE2 as f32 / 255.0,
-    ),
-    success: Color::from_rgb(
-        0x12 as f32 / 255.0,
-        0x66 as f32 / 255.0,
-        0x4F as f32 / 255.0,
-    ),
-    danger: Color::from_rgb(
-        0xC3 as f32 / 255.0,
-        0x42 as f32 / 255.0,
-        0x3F as f32 / 255.0,
-    ),
+    background: Color::BLACK,
+    text: rgb(0xE6, 0xE6, 0xE6),
+    primary: rgb(0x5E, 0x7C, 0xE2),
+    success: rgb(0x12, 0x66, 0x4F),
+    danger: rgb(0xFF, 0x45, 0x3A),
+};
+
+pub const EXTENDED_DARK: Extended = Extended {
+    background: Background {
+        base: pair(DARK.background, DARK.text),
+        weak: pair(rgb(0x1C, 0x1C, 0x1E), Color::WHITE),
+        strong: pair(rgb(0x44, 0x44, 0x46), Color::WHITE),
+    },
+    primary: Primary {
+        base: pair(DARK.primary, Color::BLACK),
+        weak: pair(rgb(0x2C, 0x3A, 0x6B), DARK.text),
+        strong: pair(rgb(0x8F, 0xA4, 0xEE), Color::BLACK),
+    },
+    secondary: Secondary {
+        base: pair(rgb(0x63, 0x63, 0x66), Color::WHITE),
+        weak: pair(rgb(0x2C, 0x2C, 0x2E), DARK.text),
+        strong: pair(rgb(0x8E, 0x8E, 0x93), Color::BLACK),
+    },
+    success: Success {
+        base: pair(DARK.success, Color::WHITE),
+        weak: pair(rgb(0x0B, 0x3B, 0x2E), DARK.text),
+        strong: pair(rgb(0x2F, 0xA3, 0x7F), Color::BLACK),
+    },
+    danger: Danger {
+        base: pair(DARK.danger, Color::BLACK),
+        weak: pair(rgb(0x5C, 0x1A, 0x16), DARK.text),
+        strong: pair(rgb(0xFF, 0x69, 0x61), Color::BLACK),
+    },
+    is_dark: true,
+};
+
+pub const LIGHT: Palette = Palette {
+    background: rgb(0xF2, 0xF2, 0xF7),
+    text: rgb(0x1C, 0x1C, 0x1E),
+    primary: rgb(0x3A, 0x5C, 0xCC),
+    success: rgb(0x1E, 0x7B, 0x5B),
+    danger: rgb(0xD7, 0x00, 0x15),
+};
+
+pub const EXTENDED_LIGHT: Extended = Extended {
+    background: Background {
+        base: pair(LIGHT.background, LIGHT.text),
+        weak: pair(Color::WHITE, LIGHT.text),
+        strong: pair(rgb(0xC7, 0xC7, 0xCC), LIGHT.text),
+    },
+    primary: Primary {
+        base: pair(LIGHT.primary, Color::WHITE),
+        weak: pair(rgb(0xC9, 0xD3, 0xF2), LIGHT.text),
+        strong: pair(rgb(0x2A, 0x45, 0xA0), Color::WHITE),
+    },
+    secondary: Secondary {
+        base: pair(rgb(0x6C, 0x6C, 0x70), Color::WHITE),
+        weak: pair(rgb(0xE5, 0xE5, 0xEA), LIGHT.text),
+        strong: pair(rgb(0x48, 0x48, 0x4A), Color::WHITE),
+    },
+    success: Success {
+        base: pair(LIGHT.success, Color::WHITE),
+        weak: pair(rgb(0xCF, 0xEB, 0xDD), LIGHT.text),
+        strong: pair(rgb(0x14, 0x5C, 0x43), Color::WHITE),
+    },
+    danger: Danger {
+        base: pair(LIGHT.danger, Color::WHITE),
+        weak: pair(rgb(0xF9, 0xD0, 0xD3), LIGHT.text),
+        strong: pair(rgb(0xA3, 0x00, 0x0F), Color::WHITE),
+    },
+    is_dark: false,
+};
+
+pub const HIGH_CONTRAST: Palette = Palette {
+    background: Color::BLACK,
+    text: Color::WHITE,
+    primary: rgb(0xFF, 0xD6, 0x0A),
+    success: rgb(0x00, 0xE6, 0x76),
+    danger: rgb(0xFF, 0x6B, 0x6B),
+};
+
+pub const EXTENDED_HIGH_CONTRAST: Extended = Extended {
+    background: Background {
+        base: pair(Color::BLACK, Color::WHITE),
+        weak: pair(Color::BLACK, Color::WHITE),
+        strong: pair(Color::WHITE, Color::BLACK),
+    },
+    primary: Primary {
+        base: pair(HIGH_CONTRAST.primary, Color::BLACK),
+        weak: pair(rgb(0x3D, 0x33, 0x00), Color::WHITE),
+        strong: pair(rgb(0xFF, 0xE6, 0x66), Color::BLACK),
+    },
+    secondary: Secondary {
+        base: pair(Color::WHITE, Color::BLACK),
+        weak: pair(rgb(0x33, 0x33, 0x33), Color::WHITE),
+        strong: pair(rgb(0xCC, 0xCC, 0xCC), Color::BLACK),
+    },
+    success: Success {
+        base: pair(HIGH_CONTRAST.success, Color::BLACK),
+        weak: pair(rgb(0x00, 0x3D, 0x1F), Color::WHITE),
+        strong: pair(rgb(0x69, 0xF0, 0xAE), Color::BLACK),
+    },
+    danger: Danger {
+        base: pair(HIGH_CONTRAST.danger, Color::BLACK),
+        weak: pair(rgb(0x4D, 0x00, 0x00), Color::WHITE),
+        strong: pair(rgb(0xFF, 0x8A, 0x80), Color::BLACK),
+    },
+    is_dark: true,
+};
+
+pub const NIGHT: Palette = Palette {
+    background: Color::BLACK,
+    text: rgb(0xD0, 0x50, 0x3A),
+    primary: rgb(0x8C, 0x2A, 0x1F),
+    success: rgb(0x6B, 0x3D, 0x0A),
+    danger: rgb(0xFF, 0x2A, 0x1A),
+};
+
+pub const EXTENDED_NIGHT: Extended = Extended {
+    background: Background {
+        base: pair(NIGHT.background, NIGHT.text),
+        weak: pair(rgb(0x12, 0x05, 0x04), rgb(0xD4, 0x56, 0x3F)),
+        strong: pair(rgb(0x2A, 0x0B, 0x08), rgb(0xE0, 0x6A, 0x52)),
+    },
+    primary: Primary {
+        base: pair(NIGHT.primary, rgb(0xFF, 0xB3, 0xA3)),
+        weak: pair(rgb(0x2E, 0x0D, 0x09), rgb(0xE0, 0x6A, 0x52)),
+        strong: pair(rgb(0xE0, 0x4A, 0x38), Color::BLACK),
+    },
+    secondary: Secondary {
+        base: pair(rgb(0x4D, 0x1A, 0x14), rgb(0xE8, 0x91, 0x7F)),
+        weak: pair(rgb(0x1F, 0x08, 0x06), rgb(0xD4, 0x56, 0x3F)),
+        strong: pair(rgb(0x6B, 0x26, 0x1D), rgb(0xE8, 0x91, 0x7F)),
+    },
+    success: Success {
+        base: pair(NIGHT.success, rgb(0xF2, 0xB8, 0x80)),
+        weak: pair(rgb(0x24, 0x14, 0x03), rgb(0xD9, 0x98, 0x5C)),
+        strong: pair(rgb(0xA8, 0x66, 0x1A), Color::BLACK),
+    },
+    danger: Danger {
+        base: pair(NIGHT.danger, Color::BLACK),
+        weak: pair(rgb(0x4D, 0x0A, 0x05), rgb(0xFF, 0x6B, 0x5E)),
+        strong: pair(rgb(0xFF, 0x5A, 0x4D), Color::BLACK),
+    },
+    is_dark: true,
 };
 
-pub static EXTENDED_DARK: Lazy<palette::Extended> = Lazy::new(|| palette::Extended {
-    background: palette::Background {
-        base: palette::Pair {
-            color: Color::BLACK,
-            text: Default::default(),
-        },
-        weak: palette::Pair {
-            color: Color::from_rgb(
-                0x1C as f32 / 255.0,
-                0x1C as f32 / 255.0,
-                0x1E as f32 / 255.0,
-            ),
-            text: Default::default(),
-        },
-        strong: palette::Pair {
-            color: Color::from_rgb(
-                0x44 as f32 / 255.0,
-                0x44 as f32 / 255.0,
-                0x46 as f32 / 255.0,
-            ),
-            text: Default::default(),
-        },
-    },
-    secondary: palette::Secondary {
-        base: palette::Pair {
-            color: Color::from_rgb(
-                0xFF as f32 / 255.0,
-                0x45 as f32 / 255.0,
-                0x3A as f32 / 255.0,
-            ),
-            text: Color::WHITE,
-        },
-        weak: palette::Pair {
-            color: Default::default(),
-            text: Default::default(),
-        },
-        strong: palette::Pair {
-            color: Default::default(),
-            text: Default::default(),
-        },
-    },
-    ..palette::Extended::generate(DARK)
-});
\ No newline at end of file
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::from_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+const fn pair(color: Color, text: Color) -> Pair {
+    Pair { color, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// WCAG contrast ratio of two opaque colours, from 1 to 21.
+    fn contrast(a: Color, b: Color) -> f32 {
+        let luminance = |color: Color| {
+            let [r, g, b, _] = color.into_linear();
+            0.2126 * r + 0.7152 * g + 0.0722 * b
+        };
+        let (a, b) = (luminance(a), luminance(b));
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    #[test]
+    fn every_pair_is_readable() {
+        for theme in Theme::ALL {
+            let palette = theme.extended_palette();
+            let minimum = match theme {
+                Theme::HighContrast => 7.0,
+                _ => 4.5,
+            };
+            let groups = [
+                (
+                    "background",
+                    palette.background.base,
+                    palette.background.weak,
+                    palette.background.strong,
+                ),
+                (
+                    "primary",
+                    palette.primary.base,
+                    palette.primary.weak,
+                    palette.primary.strong,
+                ),
+                (
+                    "secondary",
+                    palette.secondary.base,
+                    palette.secondary.weak,
+                    palette.secondary.strong,
+                ),
+                (
+                    "success",
+                    palette.success.base,
+                    palette.success.weak,
+                    palette.success.strong,
+                ),
+                (
+                    "danger",
+                    palette.danger.base,
+                    palette.danger.weak,
+                    palette.danger.strong,
+                ),
+            ];
+
+            for (group, base, weak, strong) in groups {
+                for (name, pair) in [("base", base), ("weak", weak), ("strong", strong)] {
+                    let ratio = contrast(pair.color, pair.text);
+                    assert!(
+                        ratio >= minimum,
+                        "{theme} {group}.{name}: contrast {ratio:.1} under {minimum}"
+                    );
+                }
+            }
+            assert_eq!(theme.palette().background, palette.background.base.color);
+            assert_eq!(theme.palette().danger, palette.danger.base.color);
+        }
+    }
+
+    #[test]
+    fn night_has_no_blue_nor_green_light() {
+        let palette = Theme::Night.extended_palette();
+        let style = Theme::Night.speedometer(Status::Active);
+
+        for color in [
+            palette.background.weak.text,
+            palette.primary.base.color,
+            style.ticks,
+            style.needle,
+            style.cap_rim[1],
+        ] {
+            assert!(
+                color.b < color.r / 2.0 && color.g < color.r / 2.0,
+                "{color:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cycles_through_the_themes() {
+        let mut theme = Theme::default();
+        for expected in [Theme::Light, Theme::HighContrast, Theme::Night, Theme::Dark] {
+            theme = theme.next();
+            assert_eq!(theme, expected);
+        }
+    }
+}
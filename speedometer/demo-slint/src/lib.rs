@@ -0,0 +1,44 @@
+//! The Slint windows, shared by the dashboard and the `gauge-render` tool.
+
+use gauge_core::{format_value, Scale, TickSpec};
+use slint::{ModelRc, VecModel};
+
+slint::include_modules!();
+
+/// The ticks `spec` lays out on `scale`, with labels of `decimals` decimals, for the
+/// `ticks` property of the speedometer.
+pub fn tick_model(scale: &Scale, spec: &TickSpec, decimals: usize) -> ModelRc<GaugeTick> {
+    let ticks: Vec<GaugeTick> = scale
+        .ticks(spec)
+        .into_iter()
+        .map(|tick| GaugeTick {
+            value: tick.value,
+            major: tick.major,
+            labeled: tick.labeled,
+            label: format_value(tick.value as f64, decimals).into(),
+        })
+        .collect();
+
+    ModelRc::new(VecModel::from(ticks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slint::Model;
+
+    #[test]
+    fn ticks_are_laid_out_by_tick_spec() {
+        let scale = Scale::new(0.0, 180.0);
+
+        let ticks = tick_model(&scale, &TickSpec::for_range(0.0, 180.0), 0);
+        assert_eq!(ticks.row_count(), 19);
+        let first = ticks.row_data(0).unwrap();
+        assert!(first.major && first.labeled);
+        assert_eq!(first.label, "0");
+
+        for step in [0.0, f32::NAN, 1e-6] {
+            assert_eq!(tick_model(&scale, &TickSpec::new(step), 0).row_count(), 0);
+        }
+    }
+}
@@ -1,5 +1,164 @@
-slint::include_modules!();
+use demo_slint::{tick_model, MainWindow};
+use frame_metrics::{FrameMetrics, MetricsArgs};
+use gauge_core::{Scale, TickSpec};
+use slint::{ComponentHandle, ModelRc, RenderingState, SharedString, Timer, TimerMode, VecModel};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Instant;
+use vehicle_data::{
+    source_from_args, Playback, SourceStatus, VehicleDataSource, PLAYBACK_RATES, SAMPLE_PERIOD,
+};
+
+const MAX_SPEED: f32 = 180.0;
+const MIN_SPEED: f32 = 0.0;
+const LABEL_DECIMALS: usize = 0;
 
 pub fn main() {
-    MainWindow::new().unwrap().run().unwrap();
-}
\ No newline at end of file
+    let (options, args) = MetricsArgs::split(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let metrics = Rc::new(RefCell::new(options.metrics("slint")));
+    let source = source_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
+    let window = MainWindow::new().unwrap();
+
+    let scale = Scale::new(MIN_SPEED, MAX_SPEED);
+    window.set_min_speed(MIN_SPEED);
+    window.set_max_speed(MAX_SPEED);
+    window.set_start_angle(scale.start_angle());
+    window.set_sweep_angle(scale.sweep_angle());
+
+    let ticks = TickSpec::for_range(MIN_SPEED, MAX_SPEED);
+    window.set_ticks(tick_model(&scale, &ticks, LABEL_DECIMALS));
+
+    let source = Rc::new(RefCell::new(source));
+    let rates: Vec<SharedString> = PLAYBACK_RATES
+        .iter()
+        .map(|rate| format!("{rate}×").into())
+        .collect();
+    window.set_rates(ModelRc::new(VecModel::from(rates)));
+    bind_playback(&window, &source);
+
+    record_frames(&window, &metrics);
+    if let Some(exit_after) = options.exit_after {
+        Timer::single_shot(exit_after, || {
+            let _ = slint::quit_event_loop();
+        });
+    }
+
+    let started = Instant::now();
+    let timer = Timer::default();
+    let frames = metrics.clone();
+    let weak = window.as_weak();
+    timer.start(TimerMode::Repeated, SAMPLE_PERIOD, move || {
+        let Some(window) = weak.upgrade() else {
+            return;
+        };
+        let mut source = source.borrow_mut();
+
+        let elapsed = started.elapsed();
+        if let Some(sample) = source.poll(elapsed) {
+            window.set_speed(sample.speed);
+            window.set_telemetry(sample.to_string().into());
+        }
+        let status = source.status(elapsed);
+        window.set_active(status == SourceStatus::Live);
+        window.set_status(status.to_string().into());
+        if let Some(playback) = source.playback() {
+            show_playback(&window, playback);
+        }
+        window.set_frame_metrics(frames.borrow().stats().to_string().into());
+    });
+
+    window.run().unwrap();
+    let exported = metrics.borrow().export();
+    if let Err(err) = exported {
+        eprintln!("cannot write frame metrics: {err}");
+    }
+}
+
+/// Times the rendering of every frame, the CPU time is the rendering alone. A frame
+/// follows the previous one right away when that one left animations running.
+fn record_frames(window: &MainWindow, metrics: &Rc<RefCell<FrameMetrics>>) {
+    let metrics = metrics.clone();
+    let weak = window.as_weak();
+    let rendering = Cell::new(None);
+    let animating = Cell::new(false);
+
+    let notifier = window
+        .window()
+        .set_rendering_notifier(move |state, _| match state {
+            RenderingState::BeforeRendering => rendering.set(Some(Instant::now())),
+            RenderingState::AfterRendering => {
+                let Some(started) = rendering.take() else {
+                    return;
+                };
+                metrics
+                    .borrow_mut()
+                    .record(started, started.elapsed(), animating.get());
+                animating.set(
+                    weak.upgrade()
+                        .is_some_and(|window| window.window().has_active_animations()),
+                );
+            }
+            _ => {}
+        });
+    if let Err(err) = notifier {
+        eprintln!("no frame metrics with this renderer: {err}");
+    }
+}
+
+fn bind_playback(window: &MainWindow, source: &Rc<RefCell<Box<dyn VehicleDataSource>>>) {
+    if let Some(playback) = source.borrow().playback() {
+        show_playback(window, playback);
+    }
+
+    let toggle = source.clone();
+    window.on_toggle_pause(move || {
+        if let Some(playback) = toggle.borrow_mut().playback_mut() {
+            playback.set_paused(!playback.is_paused());
+        }
+    });
+
+    let seek = source.clone();
+    window.on_seek(move |position| {
+        if let Some(playback) = seek.borrow_mut().playback_mut() {
+            playback.seek(position);
+        }
+    });
+
+    let rate = source.clone();
+    window.on_rate_selected(move |index| {
+        if let (Some(playback), Some(&rate)) = (
+            rate.borrow_mut().playback_mut(),
+            PLAYBACK_RATES.get(index as usize),
+        ) {
+            playback.set_rate(rate);
+        }
+    });
+
+    let looping = source.clone();
+    window.on_looping_toggled(move |enabled| {
+        if let Some(playback) = looping.borrow_mut().playback_mut() {
+            playback.set_looping(enabled);
+        }
+    });
+}
+
+fn show_playback(window: &MainWindow, playback: &dyn Playback) {
+    window.set_playback_visible(true);
+    window.set_paused(playback.is_paused());
+    window.set_position(playback.position());
+    window.set_duration(playback.duration());
+    window.set_looping(playback.is_looping());
+    if let Some(index) = PLAYBACK_RATES
+        .iter()
+        .position(|&rate| rate == playback.rate())
+    {
+        window.set_rate_index(index as i32);
+    }
+}
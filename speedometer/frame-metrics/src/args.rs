@@ -0,0 +1,115 @@
+use crate::{FrameMetrics, DEFAULT_TARGET_FPS, MAX_TARGET_FPS, MIN_TARGET_FPS};
+use cli_args::{ArgError, Args};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Frame metrics options of a dashboard, see [`MetricsArgs::split`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsArgs {
+    pub export: Option<PathBuf>,
+    pub target_fps: f32,
+    /// How long the dashboard runs before it closes itself, for benchmarks.
+    pub exit_after: Option<Duration>,
+}
+
+impl Default for MetricsArgs {
+    fn default() -> Self {
+        Self {
+            export: None,
+            target_fps: DEFAULT_TARGET_FPS,
+            exit_after: None,
+        }
+    }
+}
+
+impl MetricsArgs {
+    /// Takes the frame metrics options out of the arguments and returns the others,
+    /// which select the data source:
+    ///
+    /// - `--metrics <path>` to export the metrics as JSON;
+    /// - `--target-fps <1-1000>`, the refresh rate frames are dropped against, `60`
+    ///   by default;
+    /// - `--exit-after <seconds>` to close the dashboard on its own.
+    pub fn split<I>(args: I) -> Result<(Self, Vec<String>), ArgError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut rest = Vec::new();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            match arg.flag() {
+                "--metrics" => parsed.export = Some(args.value(&arg)?.into()),
+                "--target-fps" => {
+                    parsed.target_fps =
+                        args.parse(&arg, "a refresh rate from 1 to 1000", |fps| {
+                            (MIN_TARGET_FPS..=MAX_TARGET_FPS).contains(fps)
+                        })?;
+                }
+                "--exit-after" => {
+                    let seconds = args.parse(&arg, "a positive number", |seconds: &f32| {
+                        *seconds > 0.0 && Duration::try_from_secs_f32(*seconds).is_ok()
+                    })?;
+                    parsed.exit_after = Some(Duration::from_secs_f32(seconds));
+                }
+                _ => rest.push(arg.into_string()),
+            }
+        }
+
+        Ok((parsed, rest))
+    }
+
+    pub fn metrics(&self, name: impl Into<String>) -> FrameMetrics {
+        let metrics = FrameMetrics::new(name).with_target_fps(self.target_fps);
+        match &self.export {
+            Some(path) => metrics.with_export(path),
+            None => metrics,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_the_source_arguments() {
+        let (args, rest) = MetricsArgs::split([
+            "--source",
+            "sweep",
+            "--metrics",
+            "egui.json",
+            "--target-fps=144",
+            "--loop",
+            "--exit-after",
+            "2.5",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args,
+            MetricsArgs {
+                export: Some("egui.json".into()),
+                target_fps: 144.0,
+                exit_after: Some(Duration::from_millis(2500)),
+            }
+        );
+        assert_eq!(rest, ["--source", "sweep", "--loop"]);
+        for fps in ["0", "1e10", "NaN"] {
+            assert!(matches!(
+                MetricsArgs::split(["--target-fps", fps]),
+                Err(ArgError::InvalidValue { .. })
+            ));
+        }
+        assert!(matches!(
+            MetricsArgs::split(["--exit-after", "1e30"]),
+            Err(ArgError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            MetricsArgs::split(["--metrics"]),
+            Err(ArgError::MissingValue(_))
+        ));
+    }
+}
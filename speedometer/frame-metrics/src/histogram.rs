@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+/// Durations counted in buckets of equal width, the ones longer than the last
+/// bucket are counted in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bucket_width: Duration,
+    counts: Vec<u64>,
+    total: u64,
+}
+
+impl Histogram {
+    /// `buckets` buckets of `bucket_width`, at least one.
+    pub fn new(bucket_width: Duration, buckets: usize) -> Self {
+        Self {
+            bucket_width,
+            counts: vec![0; buckets.max(1)],
+            total: 0,
+        }
+    }
+
+    pub fn record(&mut self, duration: Duration) {
+        let index = (duration.as_nanos() / self.bucket_width.as_nanos().max(1)) as usize;
+        let last = self.counts.len() - 1;
+        self.counts[index.min(last)] += 1;
+        self.total += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    /// Upper edge of the bucket holding the `quantile` (`0.0` to `1.0`) of the
+    /// durations, `None` while empty.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        let rank = ((quantile.clamp(0.0, 1.0) * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+
+        self.counts.iter().enumerate().find_map(|(index, &count)| {
+            seen += count;
+            (seen >= rank).then(|| self.upper_edge(index))
+        })
+    }
+
+    /// Upper edge and count of every bucket that is not empty.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(index, &count)| (self.upper_edge(index), count))
+    }
+
+    fn upper_edge(&self, index: usize) -> Duration {
+        self.bucket_width * (index as u32 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn finds_percentiles_by_bucket() {
+        let mut histogram = Histogram::new(ms(1), 50);
+        assert_eq!(histogram.percentile(0.5), None);
+
+        for _ in 0..98 {
+            histogram.record(Duration::from_micros(16_400));
+        }
+        histogram.record(ms(30));
+        histogram.record(ms(400));
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.percentile(0.5), Some(ms(17)));
+        assert_eq!(histogram.percentile(0.99), Some(ms(31)));
+        // Longer than the last bucket.
+        assert_eq!(histogram.percentile(1.0), Some(ms(50)));
+        assert_eq!(
+            histogram.buckets().collect::<Vec<_>>(),
+            [(ms(17), 98), (ms(31), 1), (ms(50), 1)]
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Frame timing shared by the egui, iced and Slint dashboards, so that the toolkits
+//! are compared on the same numbers.
+//!
+//! Each presented frame is recorded with the CPU time the toolkit spent on it and
+//! whether it followed the previous frame right away, i.e. while something was
+//! animating. Only then is the interval between the two a frame time: the
+//! dashboards otherwise repaint when new data arrives. A frame is dropped when it
+//! came one or more frame budgets late during an animation, or when it took longer
+//! than the budget on its own.
+//!
+//! The startup time is counted from [`FrameMetrics::new`], which the dashboards
+//! call first thing in `main`, to the first recorded frame.
+
+mod args;
+mod histogram;
+
+pub use args::MetricsArgs;
+pub use cli_args::ArgError;
+pub use histogram::Histogram;
+
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_TARGET_FPS: f32 = 60.0;
+/// Bounds of the target refresh rate, see [`FrameMetrics::with_target_fps`].
+pub const MIN_TARGET_FPS: f32 = 1.0;
+pub const MAX_TARGET_FPS: f32 = 1000.0;
+
+/// How far back [`FrameMetrics::stats`] looks.
+const WINDOW: Duration = Duration::from_secs(5);
+const BUCKET_WIDTH: Duration = Duration::from_micros(250);
+/// Up to 100 ms, longer frames share the last bucket.
+const BUCKETS: usize = 400;
+/// How often the export file is written again.
+const EXPORT_PERIOD: Duration = Duration::from_secs(1);
+/// Frame budgets after which an animation frame is late.
+const LATE: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    at: Instant,
+    interval: Option<Duration>,
+    cpu: Duration,
+    dropped: u64,
+}
+
+#[derive(Debug)]
+struct Export {
+    path: PathBuf,
+    written: Option<Instant>,
+}
+
+/// Frame times, CPU times and dropped frames of one dashboard.
+#[derive(Debug)]
+pub struct FrameMetrics {
+    name: String,
+    budget: Duration,
+    created: Instant,
+    startup: Option<Duration>,
+    last: Option<Instant>,
+    recent: VecDeque<Frame>,
+    frame_times: Histogram,
+    cpu_times: Histogram,
+    cpu_total: Duration,
+    frames: u64,
+    dropped: u64,
+    export: Option<Export>,
+}
+
+impl FrameMetrics {
+    /// `name` tells the dashboards apart in the exported files.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            budget: Duration::from_secs_f32(1.0 / DEFAULT_TARGET_FPS),
+            created: Instant::now(),
+            startup: None,
+            last: None,
+            recent: VecDeque::new(),
+            frame_times: Histogram::new(BUCKET_WIDTH, BUCKETS),
+            cpu_times: Histogram::new(BUCKET_WIDTH, BUCKETS),
+            cpu_total: Duration::ZERO,
+            frames: 0,
+            dropped: 0,
+            export: None,
+        }
+    }
+
+    /// Sets the refresh rate frames are dropped against, clamped to
+    /// [`MIN_TARGET_FPS`]..=[`MAX_TARGET_FPS`]. A rate that is not a number is ignored.
+    pub fn with_target_fps(mut self, fps: f32) -> Self {
+        if !fps.is_nan() {
+            self.budget = Duration::from_secs_f32(1.0 / fps.clamp(MIN_TARGET_FPS, MAX_TARGET_FPS));
+        }
+        self
+    }
+
+    /// Writes [`FrameMetrics::to_json`] to `path` every second while frames come in.
+    pub fn with_export(mut self, path: impl Into<PathBuf>) -> Self {
+        self.export = Some(Export {
+            path: path.into(),
+            written: None,
+        });
+        self
+    }
+
+    /// Records a frame presented at `now` that took `cpu` of CPU time.
+    /// `continuous` tells whether the previous frame asked for this one right away.
+    pub fn record(&mut self, now: Instant, cpu: Duration, continuous: bool) {
+        let interval = self
+            .last
+            .filter(|_| continuous)
+            .map(|last| now.saturating_duration_since(last));
+        let dropped = match interval {
+            Some(interval) => missed_frames(interval, self.budget),
+            None => u64::from(cpu > self.budget),
+        };
+
+        if let Some(interval) = interval {
+            self.frame_times.record(interval);
+        }
+        self.cpu_times.record(cpu);
+        self.cpu_total += cpu;
+        self.frames += 1;
+        self.dropped = self.dropped.saturating_add(dropped);
+        self.last = Some(now);
+        self.startup
+            .get_or_insert_with(|| now.saturating_duration_since(self.created));
+
+        self.recent.push_back(Frame {
+            at: now,
+            interval,
+            cpu,
+            dropped,
+        });
+        while self
+            .recent
+            .front()
+            .is_some_and(|frame| now.saturating_duration_since(frame.at) > WINDOW)
+        {
+            self.recent.pop_front();
+        }
+
+        self.export_if_due(now);
+    }
+
+    /// Timings of the last five seconds.
+    pub fn stats(&self) -> FrameStats {
+        let span = match (self.recent.front(), self.recent.back()) {
+            (Some(first), Some(last)) => last.at.saturating_duration_since(first.at),
+            _ => Duration::ZERO,
+        };
+        let frames = self.recent.len() as u64;
+        let fps = if span.is_zero() {
+            0.0
+        } else {
+            (frames - 1) as f32 / span.as_secs_f32()
+        };
+        let cpu: Vec<_> = self.recent.iter().map(|frame| frame.cpu).collect();
+
+        FrameStats {
+            fps,
+            frame_time: Percentiles::of(
+                self.recent
+                    .iter()
+                    .filter_map(|frame| frame.interval)
+                    .collect(),
+            ),
+            mean_cpu_time: cpu.iter().sum::<Duration>() / (frames.max(1) as u32),
+            cpu_time: Percentiles::of(cpu),
+            dropped: self
+                .recent
+                .iter()
+                .fold(0, |dropped, frame| dropped.saturating_add(frame.dropped)),
+            frames,
+        }
+    }
+
+    /// Time from [`FrameMetrics::new`] to the first frame, `None` before it.
+    pub fn startup(&self) -> Option<Duration> {
+        self.startup
+    }
+
+    /// Intervals between continuous frames since the start.
+    pub fn frame_times(&self) -> &Histogram {
+        &self.frame_times
+    }
+
+    /// CPU time of every frame since the start.
+    pub fn cpu_times(&self) -> &Histogram {
+        &self.cpu_times
+    }
+
+    /// Totals since the start, with the histograms as `[upper edge, count]` pairs
+    /// in milliseconds.
+    pub fn to_json(&self) -> String {
+        let summary = |histogram: &Histogram| {
+            let percentile = |quantile| histogram.percentile(quantile).map(millis);
+            let buckets: Vec<_> = histogram
+                .buckets()
+                .map(|(edge, count)| json!([millis(edge), count]))
+                .collect();
+            json!({
+                "p50": percentile(0.5),
+                "p95": percentile(0.95),
+                "p99": percentile(0.99),
+                "histogram": buckets,
+            })
+        };
+
+        let mut cpu_time = summary(&self.cpu_times);
+        cpu_time["mean"] = json!(millis(self.cpu_total / (self.frames.max(1) as u32)));
+
+        json!({
+            "name": self.name,
+            "target_fps": 1.0 / self.budget.as_secs_f64(),
+            "startup_ms": self.startup.map(millis),
+            "frames": self.frames,
+            "dropped": self.dropped,
+            "frame_time_ms": summary(&self.frame_times),
+            "cpu_time_ms": cpu_time,
+        })
+        .to_string()
+    }
+
+    /// Writes the export file now, e.g. when the dashboard closes. Does nothing
+    /// without [`FrameMetrics::with_export`].
+    pub fn export(&self) -> io::Result<()> {
+        match &self.export {
+            Some(export) => std::fs::write(&export.path, self.to_json()),
+            None => Ok(()),
+        }
+    }
+
+    /// A failing export is reported once and then given up, the dashboard goes on.
+    fn export_if_due(&mut self, now: Instant) {
+        let Some(export) = &mut self.export else {
+            return;
+        };
+        if export
+            .written
+            .is_some_and(|written| now.saturating_duration_since(written) < EXPORT_PERIOD)
+        {
+            return;
+        }
+        export.written = Some(now);
+
+        if let Err(err) = self.export() {
+            let export = self.export.take().expect("checked above");
+            eprintln!(
+                "cannot write frame metrics to {}: {err}",
+                export.path.display()
+            );
+        }
+    }
+}
+
+/// Frames that should have been presented between two animation frames.
+fn missed_frames(interval: Duration, budget: Duration) -> u64 {
+    let budgets = interval.as_secs_f64() / budget.as_secs_f64();
+    if budgets > LATE {
+        (budgets.round() as u64).saturating_sub(1)
+    } else {
+        0
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e3
+}
+
+/// Timings of the last seconds, see [`FrameMetrics::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameStats {
+    pub fps: f32,
+    /// `None` when nothing animated.
+    pub frame_time: Option<Percentiles>,
+    pub cpu_time: Option<Percentiles>,
+    pub mean_cpu_time: Duration,
+    pub dropped: u64,
+    pub frames: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl Percentiles {
+    /// Nearest-rank percentiles, `None` for no durations.
+    fn of(mut durations: Vec<Duration>) -> Option<Self> {
+        durations.sort_unstable();
+        let at = |quantile: f64| {
+            let rank = (quantile * durations.len() as f64).ceil() as usize;
+            durations.get(rank.max(1) - 1).copied()
+        };
+
+        Some(Self {
+            p50: at(0.5)?,
+            p95: at(0.95)?,
+            p99: at(0.99)?,
+        })
+    }
+}
+
+impl fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "p50 {:.2} p95 {:.2} p99 {:.2} ms",
+            millis(self.p50),
+            millis(self.p95),
+            millis(self.p99)
+        )
+    }
+}
+
+/// Three lines for the overlay of the dashboards.
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:.0} FPS, {} of {} frames dropped",
+            self.fps, self.dropped, self.frames
+        )?;
+        match &self.frame_time {
+            Some(frame_time) => writeln!(f, "Frame time: {frame_time}")?,
+            None => writeln!(f, "Frame time: not animating")?,
+        }
+        write!(f, "CPU: {:.2} ms / frame", millis(self.mean_cpu_time))?;
+        if let Some(cpu_time) = &self.cpu_time {
+            write!(f, ", {cpu_time}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn counts_late_animation_frames() {
+        let start = Instant::now();
+        let mut metrics = FrameMetrics::new("test").with_target_fps(50.0);
+
+        // 20 ms frames, then one 70 ms late, i.e. three frames dropped.
+        let mut at = start;
+        for _ in 0..10 {
+            at += ms(20);
+            metrics.record(at, ms(2), true);
+        }
+        at += ms(70);
+        metrics.record(at, ms(2), true);
+
+        let stats = metrics.stats();
+        assert_eq!(stats.frames, 11);
+        assert_eq!(stats.dropped, 3);
+        assert_eq!(stats.frame_time.unwrap().p50, ms(20));
+        assert_eq!(stats.frame_time.unwrap().p99, ms(70));
+        assert_eq!(stats.mean_cpu_time, ms(2));
+        assert!((stats.fps - 10.0 / 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn idle_frames_only_drop_when_slow() {
+        let mut metrics = FrameMetrics::new("test");
+        let start = Instant::now() + ms(300);
+        assert_eq!(metrics.startup(), None);
+
+        metrics.record(start, ms(1), false);
+        metrics.record(start + ms(100), ms(1), false);
+        metrics.record(start + ms(200), ms(30), false);
+
+        let stats = metrics.stats();
+        assert_eq!(stats.frame_time, None);
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(metrics.frame_times().count(), 0);
+        assert_eq!(metrics.cpu_times().count(), 3);
+        assert!(metrics.startup().unwrap() >= ms(300));
+    }
+
+    #[test]
+    fn clamps_the_target_fps() {
+        let start = Instant::now();
+        let mut metrics = FrameMetrics::new("test").with_target_fps(0.0);
+        assert_eq!(metrics.budget, Duration::from_secs(1));
+
+        metrics = metrics.with_target_fps(f32::NAN);
+        assert_eq!(metrics.budget, Duration::from_secs(1));
+
+        metrics = metrics.with_target_fps(1e10);
+        assert_eq!(metrics.budget, ms(1));
+        metrics.record(start, ms(1), false);
+        metrics.record(start + ms(11), ms(1), true);
+        assert_eq!(metrics.stats().dropped, 10);
+    }
+
+    #[test]
+    fn forgets_old_frames_but_keeps_totals() {
+        let start = Instant::now();
+        let mut metrics = FrameMetrics::new("egui");
+
+        metrics.record(start, ms(40), false);
+        metrics.record(start + ms(6_000), ms(1), false);
+
+        assert_eq!(metrics.stats().frames, 1);
+        let json: serde_json::Value = serde_json::from_str(&metrics.to_json()).unwrap();
+        assert_eq!(json["name"], "egui");
+        assert_eq!(json["frames"], 2);
+        assert_eq!(json["dropped"], 1);
+        assert_eq!(json["frame_time_ms"]["p50"], serde_json::Value::Null);
+        let slowest = &json["cpu_time_ms"]["histogram"][1];
+        assert!((slowest[0].as_f64().unwrap() - 40.25).abs() < 1e-9);
+        assert_eq!(slowest[1], 1);
+    }
+}
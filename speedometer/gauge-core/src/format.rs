@@ -0,0 +1,49 @@
+/// Formats `value` with a fixed number of decimals without ever printing `-0`.
+pub fn format_value(value: f64, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+
+    match text.strip_prefix('-') {
+        Some(unsigned) if unsigned.chars().all(|c| c == '0' || c == '.') => unsigned.to_owned(),
+        _ => text,
+    }
+}
+
+/// Decimals that show every multiple of `step`, e.g. `1` for `0.5` and `2` for
+/// `0.25`, up to `6`.
+pub fn step_decimals(step: f64) -> usize {
+    (0..6)
+        .find(|&decimals| {
+            let scaled = step.abs() * 10f64.powi(decimals as i32);
+            (scaled - scaled.round()).abs() < 1e-6 * scaled.max(1.0)
+        })
+        .unwrap_or(6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_to_decimals() {
+        assert_eq!(format_value(120.0, 0), "120");
+        assert_eq!(format_value(12.345, 1), "12.3");
+        assert_eq!(format_value(0.5, 2), "0.50");
+    }
+
+    #[test]
+    fn no_negative_zero() {
+        assert_eq!(format_value(-0.0, 0), "0");
+        assert_eq!(format_value(-0.04, 1), "0.0");
+        assert_eq!(format_value(-1.5, 1), "-1.5");
+    }
+
+    #[test]
+    fn decimals_of_steps() {
+        assert_eq!(step_decimals(5.0), 0);
+        assert_eq!(step_decimals(0.5), 1);
+        assert_eq!(step_decimals(0.25), 2);
+        assert_eq!(step_decimals(0.1), 1);
+        assert_eq!(step_decimals(1.0 / 3.0), 6);
+        assert_eq!(step_decimals(f64::NAN), 6);
+    }
+}
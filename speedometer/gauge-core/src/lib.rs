@@ -0,0 +1,16 @@
+//! Scale model shared by the egui, iced and Slint speedometers.
+//!
+//! Angles are in degrees and are measured clockwise from twelve o'clock, so a
+//! needle drawn pointing up only has to be rotated by [`Scale::angle`].
+
+mod format;
+mod needle;
+mod scale;
+mod tick;
+mod zone;
+
+pub use format::{format_value, step_decimals};
+pub use needle::{Needle, NeedleDynamics, MIN_DAMPING, MIN_STIFFNESS};
+pub use scale::{direction, Scale, DEFAULT_SECTOR_DEGREES};
+pub use tick::{Tick, TickSpec};
+pub use zone::{tint, Zone, DEFAULT_ZONE_THICKNESS};
@@ -0,0 +1,255 @@
+/// Longest simulated interval, so a needle woken up after a pause doesn't jump.
+const MAX_FRAME_SECONDS: f32 = 0.1;
+/// Integration step, small enough to keep stiff springs stable.
+const SUBSTEP_SECONDS: f32 = 1.0 / 240.0;
+/// Lowest stiffness, a needle without any pull would never reach its target.
+pub const MIN_STIFFNESS: f32 = 1.0;
+/// Lowest damping, an undamped needle would swing around its target forever.
+pub const MIN_DAMPING: f32 = 1.0;
+
+/// Spring-damper parameters of the needle, with unit mass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NeedleDynamics {
+    stiffness: f32,
+    damping: f32,
+    max_slew: f32,
+    tolerance: f32,
+}
+
+impl NeedleDynamics {
+    /// Slightly underdamped needle without a slew limit.
+    pub fn new() -> Self {
+        Self {
+            stiffness: 120.0,
+            damping: 18.0,
+            max_slew: f32::INFINITY,
+            tolerance: 1e-3,
+        }
+    }
+
+    /// Crosses the whole range in half a second at most and settles within 0.01% of it.
+    /// An empty or infinite range has the limits of [`NeedleDynamics::new`], with
+    /// which the needle still reaches its target and settles.
+    pub fn for_range(min: f32, max: f32) -> Self {
+        let span = (max - min).abs();
+        if !(span > 0.0 && span.is_finite()) {
+            return Self::new();
+        }
+
+        Self::new()
+            .with_max_slew(2.0 * span)
+            .with_tolerance(1e-4 * span)
+    }
+
+    /// Pull towards the target per unit of distance, at least [`MIN_STIFFNESS`].
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness.max(MIN_STIFFNESS);
+        self
+    }
+
+    /// Drag per unit of speed, `2 * sqrt(stiffness)` is critically damped. At least
+    /// [`MIN_DAMPING`].
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.max(MIN_DAMPING);
+        self
+    }
+
+    /// Highest needle speed in scale units per second.
+    pub fn with_max_slew(mut self, max_slew: f32) -> Self {
+        self.max_slew = max_slew.abs();
+        self
+    }
+
+    /// Distance from the target, in scale units, at which the needle snaps and stops.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+
+    pub fn stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn max_slew(&self) -> f32 {
+        self.max_slew
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+}
+
+impl Default for NeedleDynamics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Needle position eased towards a target value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Needle {
+    value: f32,
+    velocity: f32,
+    dynamics: NeedleDynamics,
+}
+
+impl Needle {
+    /// Needle resting at `value`.
+    pub fn new(value: f32, dynamics: NeedleDynamics) -> Self {
+        Self {
+            value,
+            velocity: 0.0,
+            dynamics,
+        }
+    }
+
+    pub fn with_dynamics(mut self, dynamics: NeedleDynamics) -> Self {
+        self.dynamics = dynamics;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    pub fn dynamics(&self) -> NeedleDynamics {
+        self.dynamics
+    }
+
+    /// Moves the needle to `value` at once.
+    pub fn jump_to(&mut self, value: f32) {
+        self.value = value;
+        self.velocity = 0.0;
+    }
+
+    pub fn is_settled(&self, target: f32) -> bool {
+        let tolerance = self.dynamics.tolerance;
+
+        (target - self.value).abs() <= tolerance && self.velocity.abs() <= tolerance
+    }
+
+    /// Advances the needle by `dt` seconds and returns whether it is still moving.
+    pub fn step(&mut self, target: f32, dt: f32) -> bool {
+        let NeedleDynamics {
+            stiffness,
+            damping,
+            max_slew,
+            ..
+        } = self.dynamics;
+
+        let mut remaining = dt.clamp(0.0, MAX_FRAME_SECONDS);
+        while remaining > 0.0 && !self.is_settled(target) {
+            let dt = remaining.min(SUBSTEP_SECONDS);
+            let acceleration = stiffness * (target - self.value) - damping * self.velocity;
+
+            self.velocity = (self.velocity + acceleration * dt).clamp(-max_slew, max_slew);
+            self.value += self.velocity * dt;
+            remaining -= dt;
+        }
+
+        if self.is_settled(target) {
+            self.jump_to(target);
+            return false;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(needle: &mut Needle, target: f32, seconds: f32) -> f32 {
+        let mut peak = needle.value();
+        let mut elapsed = 0.0;
+        while elapsed < seconds && needle.step(target, 1.0 / 60.0) {
+            peak = peak.max(needle.value());
+            elapsed += 1.0 / 60.0;
+        }
+        peak
+    }
+
+    #[test]
+    fn settles_on_target() {
+        let mut needle = Needle::new(0.0, NeedleDynamics::for_range(0.0, 200.0));
+
+        run(&mut needle, 120.0, 5.0);
+
+        assert_eq!(needle.value(), 120.0);
+        assert!(!needle.step(120.0, 1.0 / 60.0));
+    }
+
+    #[test]
+    fn resting_needle_does_not_move() {
+        let mut needle = Needle::new(40.0, NeedleDynamics::new());
+
+        assert!(needle.is_settled(40.0));
+        assert!(!needle.step(40.0, 1.0 / 60.0));
+        assert!(needle.step(41.0, 1.0 / 60.0));
+    }
+
+    #[test]
+    fn critical_damping_does_not_overshoot() {
+        let dynamics = NeedleDynamics::new()
+            .with_stiffness(100.0)
+            .with_damping(20.0);
+        let mut needle = Needle::new(0.0, dynamics);
+
+        assert!(run(&mut needle, 100.0, 5.0) <= 100.0);
+    }
+
+    #[test]
+    fn slew_rate_is_limited() {
+        let dynamics = NeedleDynamics::new()
+            .with_stiffness(10_000.0)
+            .with_max_slew(50.0);
+        let mut needle = Needle::new(0.0, dynamics);
+
+        needle.step(200.0, 0.1);
+
+        assert!(needle.value() <= 5.0 + 1e-3);
+        assert!(needle.velocity() <= 50.0);
+    }
+
+    #[test]
+    fn empty_range_settles() {
+        let dynamics = NeedleDynamics::for_range(50.0, 50.0);
+        assert!(dynamics.max_slew() > 0.0 && dynamics.tolerance() > 0.0);
+
+        let mut needle = Needle::new(0.0, dynamics);
+        run(&mut needle, 50.0, 5.0);
+        assert!(!needle.step(50.0, 1.0 / 60.0));
+        assert_eq!(needle.value(), 50.0);
+    }
+
+    #[test]
+    fn undamped_springs_are_clamped_and_settle() {
+        let dynamics = NeedleDynamics::new()
+            .with_stiffness(0.0)
+            .with_damping(f32::NAN);
+        assert_eq!(dynamics.stiffness(), MIN_STIFFNESS);
+        assert_eq!(dynamics.damping(), MIN_DAMPING);
+
+        let mut needle = Needle::new(0.0, dynamics);
+        run(&mut needle, 1.0, 60.0);
+        assert!(!needle.step(1.0, 1.0 / 60.0));
+    }
+
+    #[test]
+    fn long_pauses_are_capped() {
+        let mut needle = Needle::new(0.0, NeedleDynamics::new().with_max_slew(100.0));
+
+        needle.step(200.0, 10.0);
+
+        assert!(needle.value() <= 100.0 * MAX_FRAME_SECONDS + 1e-3);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::tick::{Tick, TickSpec};
+
+/// Size of the empty sector at the bottom of the dial.
+pub const DEFAULT_SECTOR_DEGREES: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    min: f32,
+    max: f32,
+    origin: Option<f32>,
+    start_angle: f32,
+    sweep_angle: f32,
+}
+
+impl Scale {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            origin: None,
+            start_angle: 0.0,
+            sweep_angle: 0.0,
+        }
+        .with_sector(DEFAULT_SECTOR_DEGREES)
+    }
+
+    /// Scale with a detent at zero, e.g. a power gauge where negative values mean regen.
+    pub fn bipolar(min: f32, max: f32) -> Self {
+        Self::new(min, max).with_origin(0.0)
+    }
+
+    /// Marks `origin` as the value the needle rests at.
+    pub fn with_origin(mut self, origin: f32) -> Self {
+        self.origin = Some(self.clamp(origin));
+        self
+    }
+
+    /// Leaves a gap of `sector_degrees` centred at six o'clock.
+    pub fn with_sector(mut self, sector_degrees: f32) -> Self {
+        self.sweep_angle = 360.0 - sector_degrees;
+        self.start_angle = -self.sweep_angle / 2.0;
+        self
+    }
+
+    pub fn with_angles(mut self, start_angle: f32, sweep_angle: f32) -> Self {
+        self.start_angle = start_angle;
+        self.sweep_angle = sweep_angle;
+        self
+    }
+
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    pub fn origin(&self) -> Option<f32> {
+        self.origin
+    }
+
+    pub fn start_angle(&self) -> f32 {
+        self.start_angle
+    }
+
+    pub fn sweep_angle(&self) -> f32 {
+        self.sweep_angle
+    }
+
+    pub fn end_angle(&self) -> f32 {
+        self.start_angle + self.sweep_angle
+    }
+
+    pub fn clamp(&self, value: f32) -> f32 {
+        value
+            .max(self.min.min(self.max))
+            .min(self.max.max(self.min))
+    }
+
+    /// Position of `value` along the scale, `0.0` at `min` and `1.0` at `max`.
+    /// Values outside the range are pinned to the nearest end, an empty range is
+    /// all at its start.
+    pub fn fraction(&self, value: f32) -> f32 {
+        let span = self.max - self.min;
+        if span == 0.0 {
+            return 0.0;
+        }
+
+        (self.clamp(value) - self.min) / span
+    }
+
+    pub fn angle(&self, value: f32) -> f32 {
+        self.start_angle + self.sweep_angle * self.fraction(value)
+    }
+
+    /// Offset from the dial centre of the point `radius` away in the direction of `value`.
+    pub fn point_at(&self, value: f32, radius: f32) -> (f32, f32) {
+        let (x, y) = direction(self.angle(value));
+        (x * radius, y * radius)
+    }
+
+    /// Value pointed at from the dial centre towards the offset `(x, y)`, the inverse
+    /// of [`Scale::point_at`]. Directions in the empty sector give the nearest end.
+    pub fn value_at(&self, x: f32, y: f32) -> f32 {
+        let sweep = self.sweep_angle.abs();
+        if sweep == 0.0 {
+            return self.min;
+        }
+
+        let angle = x.atan2(-y).to_degrees();
+        let mut along = ((angle - self.start_angle) * self.sweep_angle.signum()).rem_euclid(360.0);
+        // Past the middle of the gap the end before the start is the nearest.
+        if along > sweep + (360.0 - sweep).max(0.0) / 2.0 {
+            along -= 360.0;
+        }
+
+        self.min + (self.max - self.min) * (along / sweep).clamp(0.0, 1.0)
+    }
+
+    pub fn ticks(&self, spec: &TickSpec) -> Vec<Tick> {
+        spec.ticks(self)
+    }
+}
+
+/// Unit vector in screen coordinates (y grows down) for a dial angle.
+pub fn direction(angle: f32) -> (f32, f32) {
+    let radians = angle.to_radians();
+    (radians.sin(), -radians.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn needle_spans_default_sector() {
+        let scale = Scale::new(0.0, 200.0);
+
+        assert_close(scale.angle(0.0), -130.0);
+        assert_close(scale.angle(100.0), 0.0);
+        assert_close(scale.angle(200.0), 130.0);
+        assert_close(scale.end_angle(), 130.0);
+    }
+
+    #[test]
+    fn needle_points_up_at_mid_scale() {
+        let (x, y) = Scale::new(0.0, 180.0).point_at(90.0, 1.0);
+
+        assert_close(x, 0.0);
+        assert_close(y, -1.0);
+    }
+
+    #[test]
+    fn zero_points_down_left() {
+        let (x, y) = Scale::new(0.0, 200.0).point_at(0.0, 1.0);
+
+        assert!(x < 0.0 && y > 0.0);
+        assert_close(x, -(50.0f32.to_radians().sin()));
+        assert_close(y, 50.0f32.to_radians().cos());
+    }
+
+    #[test]
+    fn custom_sector() {
+        let scale = Scale::new(0.0, 100.0).with_sector(90.0);
+
+        assert_close(scale.start_angle(), -135.0);
+        assert_close(scale.angle(100.0), 135.0);
+    }
+
+    #[test]
+    fn offset_range_starts_at_min() {
+        let scale = Scale::new(20.0, 220.0);
+
+        assert_close(scale.angle(20.0), -130.0);
+        assert_close(scale.angle(120.0), 0.0);
+        assert_close(scale.angle(220.0), 130.0);
+    }
+
+    #[test]
+    fn negative_range() {
+        let scale = Scale::new(-50.0, 150.0);
+
+        assert_close(scale.angle(-50.0), -130.0);
+        assert_close(scale.angle(0.0), -65.0);
+        assert_close(scale.angle(150.0), 130.0);
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let scale = Scale::new(-50.0, 150.0);
+
+        assert_close(scale.angle(-80.0), -130.0);
+        assert_close(scale.angle(400.0), 130.0);
+        assert_close(scale.fraction(400.0), 1.0);
+    }
+
+    #[test]
+    fn empty_range_points_at_start() {
+        let scale = Scale::new(50.0, 50.0);
+
+        assert_close(scale.fraction(50.0), 0.0);
+        assert_close(scale.fraction(80.0), 0.0);
+        assert_close(scale.angle(20.0), -130.0);
+        let (x, y) = scale.point_at(50.0, 1.0);
+        assert!(x.is_finite() && y.is_finite());
+    }
+
+    #[test]
+    fn value_at_inverts_point_at() {
+        let scale = Scale::new(-50.0, 150.0);
+
+        for value in [-50.0, 0.0, 42.0, 150.0] {
+            let (x, y) = scale.point_at(value, 3.0);
+            assert_close(scale.value_at(x, y), value);
+        }
+        // Straight down is in the middle of the gap, either side is the nearest end.
+        assert_close(scale.value_at(-0.1, 1.0), -50.0);
+        assert_close(scale.value_at(0.1, 1.0), 150.0);
+        assert_close(
+            Scale::new(0.0, 100.0)
+                .with_angles(90.0, -180.0)
+                .value_at(-0.1, 1.0),
+            100.0,
+        );
+    }
+
+    #[test]
+    fn bipolar_detent_at_zero() {
+        let scale = Scale::bipolar(-100.0, 300.0);
+
+        assert_eq!(scale.origin(), Some(0.0));
+        assert_close(scale.angle(0.0), -65.0);
+        assert_eq!(Scale::new(0.0, 200.0).origin(), None);
+        assert_eq!(Scale::new(10.0, 20.0).with_origin(0.0).origin(), Some(10.0));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::scale::Scale;
+
+const EPSILON: f32 = 1e-4;
+/// Most ticks a dial is given, far more than can be told apart on a screen.
+const MAX_TICKS: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    pub value: f32,
+    pub angle: f32,
+    pub major: bool,
+    pub labeled: bool,
+}
+
+/// Layout of the dial ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickSpec {
+    major_step: f32,
+    minor_subdivisions: usize,
+    label_every: usize,
+    end_caps: bool,
+}
+
+impl TickSpec {
+    /// Major ticks on every multiple of `major_step`, each of them labeled.
+    ///
+    /// The sign of the step is ignored. A step that is zero or not finite, or
+    /// one that would make more than a thousand ticks, gives no ticks at all.
+    pub fn new(major_step: f32) -> Self {
+        Self {
+            major_step: major_step.abs(),
+            minor_subdivisions: 1,
+            label_every: 1,
+            end_caps: false,
+        }
+    }
+
+    /// Roughly ten major ticks on a 1-2-5 step, e.g. every 20 on a 0-200 scale.
+    pub fn for_range(min: f32, max: f32) -> Self {
+        let rough = ((max - min).abs() / 10.0).max(f32::MIN_POSITIVE);
+        let magnitude = 10f32.powf(rough.log10().floor());
+
+        let (mantissa, minor_subdivisions) = match rough / magnitude {
+            f if f < 1.5 => (1.0, 5),
+            f if f < 3.5 => (2.0, 2),
+            f if f < 7.5 => (5.0, 5),
+            _ => (10.0, 5),
+        };
+
+        Self::new(mantissa * magnitude).with_minor_subdivisions(minor_subdivisions)
+    }
+
+    /// Splits every major interval into `subdivisions` minor ones, `1` disables minor ticks.
+    pub fn with_minor_subdivisions(mut self, subdivisions: usize) -> Self {
+        self.minor_subdivisions = subdivisions.max(1);
+        self
+    }
+
+    /// Labels every `n`-th major tick, counting from zero.
+    pub fn with_label_every(mut self, n: usize) -> Self {
+        self.label_every = n.max(1);
+        self
+    }
+
+    /// Adds labeled major ticks at `min` and `max` when they are not on a step.
+    pub fn with_end_caps(mut self, end_caps: bool) -> Self {
+        self.end_caps = end_caps;
+        self
+    }
+
+    pub fn major_step(&self) -> f32 {
+        self.major_step
+    }
+
+    pub fn minor_step(&self) -> f32 {
+        self.major_step / self.minor_subdivisions as f32
+    }
+
+    pub fn minor_subdivisions(&self) -> usize {
+        self.minor_subdivisions
+    }
+
+    pub fn label_every(&self) -> usize {
+        self.label_every
+    }
+
+    pub fn end_caps(&self) -> bool {
+        self.end_caps
+    }
+
+    pub fn ticks(&self, scale: &Scale) -> Vec<Tick> {
+        let (min, max) = (scale.min().min(scale.max()), scale.max().max(scale.min()));
+        let step = self.minor_step();
+
+        let (first, last) = (
+            (min / step - EPSILON).ceil(),
+            (max / step + EPSILON).floor(),
+        );
+        let valid = step > 0.0 && step.is_finite() && first.is_finite() && last.is_finite();
+        let (first, last) = if valid && last - first < MAX_TICKS as f32 {
+            (first as i64, last as i64)
+        } else {
+            (0, -1)
+        };
+
+        let mut ticks: Vec<Tick> = (first..=last)
+            .map(|index| {
+                let value = index as f32 * step;
+                let major = index.rem_euclid(self.minor_subdivisions as i64) == 0;
+                let major_index = index.div_euclid(self.minor_subdivisions as i64);
+
+                Tick {
+                    value,
+                    angle: scale.angle(value),
+                    major,
+                    labeled: major && major_index.rem_euclid(self.label_every as i64) == 0,
+                }
+            })
+            .collect();
+
+        if self.end_caps {
+            let cap = |value: f32| Tick {
+                value,
+                angle: scale.angle(value),
+                major: true,
+                labeled: true,
+            };
+
+            if ticks.first().is_none_or(|tick| tick.value - min > EPSILON) {
+                ticks.insert(0, cap(min));
+            }
+            if ticks.last().is_none_or(|tick| max - tick.value > EPSILON) {
+                ticks.push(cap(max));
+            }
+        }
+
+        ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(ticks: &[Tick]) -> Vec<f32> {
+        ticks.iter().map(|tick| tick.value).collect()
+    }
+
+    #[test]
+    fn majors_and_minors() {
+        let ticks = TickSpec::new(20.0)
+            .with_minor_subdivisions(2)
+            .ticks(&Scale::new(0.0, 200.0));
+
+        assert_eq!(ticks.len(), 21);
+        assert_eq!(ticks.iter().filter(|tick| tick.major).count(), 11);
+        assert!(ticks[0].major && !ticks[1].major);
+        assert_eq!(ticks.last().unwrap().value, 200.0);
+    }
+
+    #[test]
+    fn ticks_align_to_step_multiples() {
+        let spec = TickSpec::new(20.0).with_minor_subdivisions(2);
+
+        let ticks = spec.ticks(&Scale::new(25.0, 225.0));
+        assert_eq!(ticks.len(), 20);
+        assert_eq!(ticks[0].value, 30.0);
+        assert!(!ticks[0].major && ticks[1].major);
+
+        let ticks = spec.ticks(&Scale::new(-50.0, 150.0));
+        assert_eq!(ticks.len(), 21);
+        assert_eq!(ticks[0].angle, -130.0);
+        assert!(ticks.iter().any(|tick| tick.value == 0.0 && tick.major));
+    }
+
+    #[test]
+    fn label_interval_counts_from_zero() {
+        let ticks = TickSpec::new(1000.0)
+            .with_label_every(2)
+            .ticks(&Scale::new(-1000.0, 4000.0));
+        let labeled: Vec<f32> = ticks
+            .iter()
+            .filter(|tick| tick.labeled)
+            .map(|tick| tick.value)
+            .collect();
+
+        assert_eq!(labeled, vec![0.0, 2000.0, 4000.0]);
+    }
+
+    #[test]
+    fn end_caps_only_when_off_step() {
+        let spec = TickSpec::new(20.0).with_end_caps(true);
+
+        assert_eq!(
+            values(&spec.ticks(&Scale::new(5.0, 75.0))),
+            vec![5.0, 20.0, 40.0, 60.0, 75.0]
+        );
+        assert_eq!(
+            values(&spec.ticks(&Scale::new(0.0, 60.0))),
+            vec![0.0, 20.0, 40.0, 60.0]
+        );
+    }
+
+    #[test]
+    fn degenerate_steps_give_no_ticks() {
+        let scale = Scale::new(0.0, 200.0);
+
+        for step in [0.0, f32::NAN, f32::INFINITY, 1e-6] {
+            assert_eq!(TickSpec::new(step).ticks(&scale), vec![], "{step}");
+        }
+        assert_eq!(
+            TickSpec::new(-50.0).ticks(&scale),
+            TickSpec::new(50.0).ticks(&scale)
+        );
+        assert_eq!(
+            TickSpec::new(20.0).ticks(&Scale::new(0.0, f32::INFINITY)),
+            vec![]
+        );
+        assert_eq!(TickSpec::new(20.0).ticks(&scale).len(), 11);
+    }
+
+    #[test]
+    fn range_defaults() {
+        let spec = TickSpec::for_range(0.0, 200.0);
+        assert_eq!((spec.major_step(), spec.minor_step()), (20.0, 10.0));
+
+        let spec = TickSpec::for_range(0.0, 180.0);
+        assert_eq!(spec.major_step(), 20.0);
+
+        let spec = TickSpec::for_range(0.0, 8000.0);
+        assert_eq!((spec.major_step(), spec.minor_step()), (1000.0, 200.0));
+
+        let spec = TickSpec::for_range(0.0, 5.0);
+        assert_eq!(spec.major_step(), 0.5);
+    }
+}
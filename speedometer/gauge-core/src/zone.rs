@@ -0,0 +1,114 @@
+use crate::scale::Scale;
+
+/// Default band thickness as a fraction of the dial radius.
+pub const DEFAULT_ZONE_THICKNESS: f32 = 0.04;
+
+/// Coloured band between two values, e.g. a redline from 6500 rpm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zone<C> {
+    pub from: f32,
+    pub to: f32,
+    pub color: C,
+    /// Band thickness as a fraction of the dial radius.
+    pub thickness: f32,
+    /// Draws the ticks and labels inside the band in its colour.
+    pub tint_ticks: bool,
+}
+
+impl<C> Zone<C> {
+    pub fn new(from: f32, to: f32, color: C) -> Self {
+        Self {
+            from: from.min(to),
+            to: to.max(from),
+            color,
+            thickness: DEFAULT_ZONE_THICKNESS,
+            tint_ticks: false,
+        }
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn with_tinted_ticks(mut self, tint_ticks: bool) -> Self {
+        self.tint_ticks = tint_ticks;
+        self
+    }
+
+    pub fn contains(&self, value: f32) -> bool {
+        (self.from..=self.to).contains(&value)
+    }
+}
+
+/// Colour of the last tinting zone that contains `value`.
+pub fn tint<C>(zones: &[Zone<C>], value: f32) -> Option<&C> {
+    zones
+        .iter()
+        .rev()
+        .find(|zone| zone.tint_ticks && zone.contains(value))
+        .map(|zone| &zone.color)
+}
+
+impl Scale {
+    /// Points along the dial between `from` and `to`, `radius` away from the centre.
+    pub fn arc_points(&self, from: f32, to: f32, radius: f32) -> Vec<(f32, f32)> {
+        let (start, end) = (self.angle(from), self.angle(to));
+        let segments = ((end - start).abs() / 2.0).ceil().max(1.0) as usize;
+
+        (0..=segments)
+            .map(|i| {
+                let (x, y) = crate::direction(start + (end - start) * i as f32 / segments as f32);
+                (x * radius, y * radius)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_bounds_are_ordered() {
+        let zone = Zone::new(6500.0, 5500.0, ());
+
+        assert_eq!((zone.from, zone.to), (5500.0, 6500.0));
+        assert!(zone.contains(5500.0) && zone.contains(6500.0));
+        assert!(!zone.contains(7000.0));
+    }
+
+    #[test]
+    fn last_tinting_zone_wins() {
+        let zones = [
+            Zone::new(5500.0, 8000.0, "amber").with_tinted_ticks(true),
+            Zone::new(6500.0, 8000.0, "red").with_tinted_ticks(true),
+            Zone::new(7000.0, 8000.0, "plain"),
+        ];
+
+        assert_eq!(tint(&zones, 5000.0), None);
+        assert_eq!(tint(&zones, 6000.0), Some(&"amber"));
+        assert_eq!(tint(&zones, 7500.0), Some(&"red"));
+    }
+
+    #[test]
+    fn arc_follows_scale() {
+        let scale = Scale::new(0.0, 200.0);
+        let points = scale.arc_points(100.0, 200.0, 1.0);
+
+        assert_eq!(points.len(), 66);
+        let (x, y) = points[0];
+        assert!(x.abs() < 1e-4 && (y + 1.0).abs() < 1e-4);
+        assert_eq!(*points.last().unwrap(), scale.point_at(200.0, 1.0));
+    }
+
+    #[test]
+    fn arc_is_clamped_to_scale() {
+        let scale = Scale::new(0.0, 200.0);
+
+        assert_eq!(
+            scale.arc_points(150.0, 500.0, 1.0).last(),
+            Some(&scale.point_at(200.0, 1.0))
+        );
+    }
+}
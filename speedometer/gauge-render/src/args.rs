@@ -0,0 +1,299 @@
+use crate::gauge::{Framework, Gauge, Theme};
+use crate::parity::Limits;
+use cli_args::{ArgError, Args};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Largest image side, enough for print and small enough to fit in memory.
+const MAX_SIDE: u32 = 8_192;
+
+/// What to render and where, see [`RenderArgs::parse`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RenderArgs {
+    pub framework: Framework,
+    pub gauge: Gauge,
+    pub output: Option<PathBuf>,
+}
+
+impl RenderArgs {
+    /// Reads the arguments, without the program name:
+    ///
+    /// - `--framework <egui|iced|slint>`, `egui` by default;
+    /// - `--value <number>`, `--min <number>` and `--max <number>`, `0` on a `0` to
+    ///   `200` dial by default;
+    /// - `--size <pixels>` for a square image or `<width>x<height>`, `512` by
+    ///   default;
+    /// - `--theme <dark|light>`, `dark` by default;
+    /// - `--output <path>`, `<framework>.png` by default.
+    ///
+    /// Values can also follow the flag after `=`, e.g. `--value=120`.
+    pub fn parse<I>(args: I) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            match arg.flag() {
+                "--framework" => parsed.framework = args.value(&arg)?.parse()?,
+                "--value" => parsed.gauge.value = args.parse(&arg, "a number", finite)?,
+                "--min" => parsed.gauge.min = args.parse(&arg, "a number", finite)?,
+                "--max" => parsed.gauge.max = args.parse(&arg, "a number", finite)?,
+                "--size" => {
+                    let size = args.value(&arg)?;
+                    (parsed.gauge.width, parsed.gauge.height) =
+                        parse_size(&size).ok_or_else(|| {
+                            arg.invalid(size, "a size like `512` or `800x600`, up to 8192 a side")
+                        })?;
+                }
+                "--theme" => parsed.gauge.theme = args.value(&arg)?.parse()?,
+                "--output" => parsed.output = Some(args.value(&arg)?.into()),
+                _ => return Err(arg.unknown().into()),
+            }
+        }
+
+        if parsed.gauge.min == parsed.gauge.max {
+            return Err(ArgsError::EmptyRange);
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn output(&self) -> PathBuf {
+        self.output
+            .clone()
+            .unwrap_or_else(|| format!("{}.png", self.framework).into())
+    }
+}
+
+/// What to compare and where to write the overlay, see [`ParityArgs::parse`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParityArgs {
+    pub gauge: Gauge,
+    pub limits: Limits,
+    pub output: Option<PathBuf>,
+}
+
+impl ParityArgs {
+    /// Reads the options of [`RenderArgs::parse`] but `--framework`, and the
+    /// limits that make the comparison fail:
+    ///
+    /// - `--max-needle-error <degrees>`;
+    /// - `--max-tick-error <degrees>`;
+    /// - `--max-pixel-difference <percent>`.
+    ///
+    /// `--output` is where the overlay goes, `parity.png` by default.
+    pub fn parse<I>(args: I) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut limits = Limits::default();
+        let mut rest = Vec::new();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            let limit = match arg.flag() {
+                "--max-needle-error" => &mut limits.needle,
+                "--max-tick-error" => &mut limits.ticks,
+                "--max-pixel-difference" => &mut limits.pixels,
+                "--framework" => return Err(arg.unknown().into()),
+                _ => {
+                    rest.push(arg.into_string());
+                    continue;
+                }
+            };
+
+            let number = args.parse(&arg, "a number of at least 0", |number: &f32| {
+                number.is_finite() && *number >= 0.0
+            })?;
+            *limit = Some(match arg.flag() {
+                "--max-pixel-difference" => number / 100.0,
+                _ => number,
+            });
+        }
+
+        let RenderArgs { gauge, output, .. } = RenderArgs::parse(rest)?;
+        Ok(Self {
+            gauge,
+            limits,
+            output,
+        })
+    }
+
+    pub fn output(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| "parity.png".into())
+    }
+}
+
+fn finite(number: &f32) -> bool {
+    number.is_finite()
+}
+
+/// `512` or `800x600`, up to [`MAX_SIDE`] pixels a side.
+fn parse_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.split_once('x').unwrap_or((size, size));
+    let side = |side: &str| {
+        side.trim()
+            .parse()
+            .ok()
+            .filter(|side| (1..=MAX_SIDE).contains(side))
+    };
+
+    Some((side(width)?, side(height)?))
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    Args(ArgError),
+    /// `--min` and `--max` are equal.
+    EmptyRange,
+    UnknownFramework(String),
+    UnknownTheme(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Args(err) => err.fmt(f),
+            ArgsError::EmptyRange => f.write_str("`--min` and `--max` must differ"),
+            ArgsError::UnknownFramework(name) => {
+                let names: Vec<_> = Framework::ALL.iter().map(|f| f.name()).collect();
+                write!(
+                    f,
+                    "unknown framework `{name}`, expected one of: {}",
+                    names.join(" ")
+                )
+            }
+            ArgsError::UnknownTheme(name) => {
+                let names: Vec<_> = Theme::ALL.iter().map(|theme| theme.name()).collect();
+                write!(
+                    f,
+                    "unknown theme `{name}`, expected one of: {}",
+                    names.join(" ")
+                )
+            }
+        }
+    }
+}
+
+impl Error for ArgsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArgsError::Args(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ArgError> for ArgsError {
+    fn from(err: ArgError) -> Self {
+        ArgsError::Args(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_every_option() {
+        let args = RenderArgs::parse([
+            "--framework",
+            "slint",
+            "--value=120.5",
+            "--min",
+            "-20",
+            "--max",
+            "240",
+            "--size",
+            "800x600",
+            "--theme",
+            "light",
+            "--output",
+            "dial.png",
+        ])
+        .unwrap();
+
+        assert_eq!(args.framework, Framework::Slint);
+        assert_eq!(
+            args.gauge,
+            Gauge {
+                value: 120.5,
+                min: -20.0,
+                max: 240.0,
+                width: 800,
+                height: 600,
+                theme: Theme::Light,
+            }
+        );
+        assert_eq!(args.output(), PathBuf::from("dial.png"));
+    }
+
+    #[test]
+    fn defaults_to_a_square_egui_dial() {
+        let args = RenderArgs::parse(["--size", "256"]).unwrap();
+
+        assert_eq!(args.framework, Framework::Egui);
+        assert_eq!((args.gauge.width, args.gauge.height), (256, 256));
+        assert_eq!(args.output(), PathBuf::from("egui.png"));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(matches!(
+            RenderArgs::parse(["--framework", "gtk"]),
+            Err(ArgsError::UnknownFramework(_))
+        ));
+        assert!(matches!(
+            RenderArgs::parse(["--value", "NaN"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+        ));
+        assert!(matches!(
+            RenderArgs::parse(["--size", "0x10"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+        ));
+        assert!(matches!(
+            RenderArgs::parse(["--min", "10", "--max=10"]),
+            Err(ArgsError::EmptyRange)
+        ));
+        assert!(matches!(
+            RenderArgs::parse(["--theme"]),
+            Err(ArgsError::Args(ArgError::MissingValue(_)))
+        ));
+    }
+
+    #[test]
+    fn reads_parity_limits() {
+        let args = ParityArgs::parse([
+            "--value",
+            "80",
+            "--max-needle-error=0.5",
+            "--max-pixel-difference",
+            "2",
+        ])
+        .unwrap();
+
+        assert_eq!(args.gauge.value, 80.0);
+        assert_eq!(
+            args.limits,
+            Limits {
+                needle: Some(0.5),
+                ticks: None,
+                pixels: Some(0.02),
+            }
+        );
+        assert_eq!(args.output(), PathBuf::from("parity.png"));
+        assert!(matches!(
+            ParityArgs::parse(["--framework", "egui"]),
+            Err(ArgsError::Args(ArgError::UnknownArgument(_)))
+        ));
+        assert!(matches!(
+            ParityArgs::parse(["--max-tick-error", "-1"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+        ));
+    }
+}
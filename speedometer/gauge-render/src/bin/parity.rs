@@ -0,0 +1,42 @@
+use gauge_render::{parity, save_png, ParityArgs};
+
+const USAGE: &str = "\
+usage: parity [--value <number>] [--min <number>] [--max <number>]
+              [--size <pixels>|<width>x<height>] [--theme dark|light]
+              [--output <path>] [--max-needle-error <degrees>]
+              [--max-tick-error <degrees>] [--max-pixel-difference <percent>]
+
+Renders the egui, iced and Slint speedometers at the same gauge, prints how far
+apart they are and writes an overlay, egui in red, iced in green and Slint in
+blue. Fails when a difference exceeds a given limit.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let args = ParityArgs::parse(args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        std::process::exit(2);
+    });
+
+    let output = args.output();
+    let parity = parity::check(&args.gauge).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    });
+    if let Err(err) = save_png(&parity.overlay, &output) {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+
+    println!("{parity}\noverlay: {}", output.display());
+
+    let violations = args.limits.violations(&parity);
+    if !violations.is_empty() {
+        eprintln!("\n{}", violations.join("\n"));
+        std::process::exit(1);
+    }
+}
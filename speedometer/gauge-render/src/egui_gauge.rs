@@ -0,0 +1,36 @@
+use crate::gauge::{Gauge, Theme};
+use crate::raster::{self, Textures};
+use demo_egui::Speedometer;
+use eframe::egui::{self, CentralPanel, Context, Frame, Pos2, RawInput, Rect, Vec2};
+use tiny_skia::Pixmap;
+
+/// Runs one egui pass with the speedometer filling the screen and rasterises the
+/// tessellated output.
+pub fn render(gauge: &Gauge, pixmap: &mut Pixmap) {
+    let ctx = Context::default();
+    ctx.set_theme(match gauge.theme {
+        Theme::Dark => egui::Theme::Dark,
+        Theme::Light => egui::Theme::Light,
+    });
+
+    let screen = Vec2::new(pixmap.width() as f32, pixmap.height() as f32);
+    let input = RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, screen)),
+        ..RawInput::default()
+    };
+    let output = ctx.run(input, |ctx| {
+        let background = Frame::none().fill(ctx.style().visuals.panel_fill);
+        CentralPanel::default().frame(background).show(ctx, |ui| {
+            ui.add(
+                Speedometer::new(gauge.value, gauge.min, gauge.max)
+                    .animate(false)
+                    .paint_callback(false),
+            )
+        });
+    });
+
+    let mut textures = Textures::default();
+    textures.update(&output.textures_delta);
+    let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+    raster::draw(pixmap, &primitives, &textures, output.pixels_per_point);
+}
@@ -0,0 +1,103 @@
+use crate::args::ArgsError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Toolkit whose speedometer is rendered, picked with `--framework <name>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Framework {
+    #[default]
+    Egui,
+    Iced,
+    Slint,
+}
+
+impl Framework {
+    pub const ALL: [Framework; 3] = [Framework::Egui, Framework::Iced, Framework::Slint];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Framework::Egui => "egui",
+            Framework::Iced => "iced",
+            Framework::Slint => "slint",
+        }
+    }
+}
+
+impl FromStr for Framework {
+    type Err = ArgsError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|framework| framework.name() == name)
+            .ok_or_else(|| ArgsError::UnknownFramework(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Framework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `Dark` is the look of the dashboards, `Light` the stock light theme of each
+/// toolkit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ArgsError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name() == name)
+            .ok_or_else(|| ArgsError::UnknownTheme(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What to draw: the needle at `value` on a `min` to `max` dial, in an image of
+/// `width` by `height` pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gauge {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub width: u32,
+    pub height: u32,
+    pub theme: Theme,
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 200.0,
+            width: 512,
+            height: 512,
+            theme: Theme::Dark,
+        }
+    }
+}
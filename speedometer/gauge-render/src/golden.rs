@@ -0,0 +1,194 @@
+//! Comparison of a rendered gauge with a reference image, for the golden-image
+//! tests.
+//!
+//! Colours are compared by their perceived difference in the YIQ space, the way
+//! `pixelmatch` does, and a pixel that differs is forgiven when a neighbour in the
+//! other image matches it, which absorbs anti-aliasing and glyph rasterisation that
+//! moved by a pixel between library versions.
+
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+/// Largest squared YIQ difference, between black and white.
+const MAX_DELTA: f32 = 35_215.0;
+
+/// How different two images may be and still match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Perceived difference of two pixels, `0.0` to `1.0`, below which they match.
+    pub color: f32,
+    /// Share of the pixels that may differ.
+    pub pixels: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            color: 0.1,
+            pixels: 0.002,
+        }
+    }
+}
+
+/// Outcome of [`compare`].
+#[derive(Debug)]
+pub enum Comparison {
+    Match,
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// More pixels differ than the tolerance allows. `diff` is the reference in
+    /// faded grey, with the pixels that differ in red and the ones forgiven as
+    /// shifted in yellow.
+    Different {
+        pixels: usize,
+        share: f32,
+        diff: Pixmap,
+    },
+}
+
+/// Compares `actual` with the reference image `expected`.
+pub fn compare(expected: &Pixmap, actual: &Pixmap, tolerance: Tolerance) -> Comparison {
+    let size = |pixmap: &Pixmap| (pixmap.width(), pixmap.height());
+    if size(expected) != size(actual) {
+        return Comparison::SizeMismatch {
+            expected: size(expected),
+            actual: size(actual),
+        };
+    }
+
+    let (width, height) = size(expected);
+    let mut diff = Pixmap::new(width, height).expect("the images are not empty");
+    let mut different = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let (want, got) = (pixel(expected, x, y), pixel(actual, x, y));
+            let marker = if delta(want, got) <= tolerance.color {
+                faded(want)
+            } else if matches_around(actual, x, y, want, tolerance.color)
+                && matches_around(expected, x, y, got, tolerance.color)
+            {
+                PremultipliedColorU8::from_rgba(255, 200, 0, 255).unwrap()
+            } else {
+                different += 1;
+                PremultipliedColorU8::from_rgba(230, 0, 40, 255).unwrap()
+            };
+            diff.pixels_mut()[(y * width + x) as usize] = marker;
+        }
+    }
+
+    let share = different as f32 / (width * height) as f32;
+    if share <= tolerance.pixels {
+        Comparison::Match
+    } else {
+        Comparison::Different {
+            pixels: different,
+            share,
+            diff,
+        }
+    }
+}
+
+fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> PremultipliedColorU8 {
+    pixmap.pixels()[(y * pixmap.width() + x) as usize]
+}
+
+/// Whether a pixel next to `(x, y)` in `pixmap` looks like `color`.
+fn matches_around(
+    pixmap: &Pixmap,
+    x: u32,
+    y: u32,
+    color: PremultipliedColorU8,
+    limit: f32,
+) -> bool {
+    let xs = x.saturating_sub(1)..=(x + 1).min(pixmap.width() - 1);
+    let ys = y.saturating_sub(1)..=(y + 1).min(pixmap.height() - 1);
+
+    ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+        .any(|(x, y)| delta(pixel(pixmap, x, y), color) <= limit)
+}
+
+/// Perceived difference of two colours blended over white, `0.0` to `1.0`.
+pub(crate) fn delta(a: PremultipliedColorU8, b: PremultipliedColorU8) -> f32 {
+    let [ar, ag, ab] = over_white(a);
+    let [br, bg, bb] = over_white(b);
+    let (r, g, b) = (ar - br, ag - bg, ab - bb);
+
+    let y = r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_23;
+    let i = r * 0.595_977_99 - g * 0.274_176_1 - b * 0.321_801_9;
+    let q = r * 0.211_470_17 - g * 0.522_617_1 + b * 0.311_146_94;
+
+    ((0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_DELTA).sqrt()
+}
+
+fn over_white(color: PremultipliedColorU8) -> [f32; 3] {
+    let background = 255.0 - color.alpha() as f32;
+    [color.red(), color.green(), color.blue()].map(|channel| channel as f32 + background)
+}
+
+/// The reference pixel in light grey, so the markers stand out.
+fn faded(color: PremultipliedColorU8) -> PremultipliedColorU8 {
+    let [r, g, b] = over_white(color);
+    let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+    let grey = (255.0 - (255.0 - luma) * 0.2) as u8;
+    PremultipliedColorU8::from_rgba(grey, grey, grey, 255).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_skia::{Color, Paint, Rect, Transform};
+
+    fn square(size: u32, at: f32, color: Color) -> Pixmap {
+        let mut pixmap = Pixmap::new(size, size).unwrap();
+        pixmap.fill(Color::WHITE);
+        let mut paint = Paint::default();
+        paint.set_color(color);
+        pixmap.fill_rect(
+            Rect::from_xywh(at, at, 4.0, 4.0).unwrap(),
+            &paint,
+            Transform::identity(),
+            None,
+        );
+        pixmap
+    }
+
+    #[test]
+    fn forgives_faint_and_shifted_pixels() {
+        let expected = square(32, 8.0, Color::BLACK);
+        let faint = square(32, 8.0, Color::from_rgba8(20, 20, 20, 255));
+        let shifted = square(32, 9.0, Color::BLACK);
+
+        assert!(matches!(
+            compare(&expected, &faint, Tolerance::default()),
+            Comparison::Match
+        ));
+        assert!(matches!(
+            compare(&expected, &shifted, Tolerance::default()),
+            Comparison::Match
+        ));
+    }
+
+    #[test]
+    fn reports_changed_pixels() {
+        let expected = square(32, 8.0, Color::BLACK);
+        let moved = square(32, 20.0, Color::BLACK);
+
+        let Comparison::Different { pixels, diff, .. } =
+            compare(&expected, &moved, Tolerance::default())
+        else {
+            panic!("the squares do not overlap");
+        };
+        assert_eq!(pixels, 32);
+        assert_eq!(diff.pixel(9, 9).unwrap().red(), 230);
+        assert!(matches!(
+            compare(
+                &expected,
+                &Pixmap::new(16, 32).unwrap(),
+                Tolerance::default()
+            ),
+            Comparison::SizeMismatch { .. }
+        ));
+    }
+}
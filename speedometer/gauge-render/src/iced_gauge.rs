@@ -0,0 +1,60 @@
+use crate::gauge::{Gauge, Theme};
+use demo_iced::speedometer;
+use demo_iced::theme::Theme as DashboardTheme;
+use iced::advanced::graphics::Viewport;
+use iced::advanced::widget::Tree;
+use iced::advanced::{layout, mouse, renderer, Layout, Widget};
+use iced::{Font, Pixels, Rectangle, Size};
+use tiny_skia::{Mask, Pixmap};
+
+/// Draws the speedometer widget with the tiny-skia half of iced's renderer, the one
+/// iced falls back to without a GPU.
+///
+/// The dark gauge is the one of the dashboard, the light one has the default style
+/// of iced's light theme.
+pub fn render(gauge: &Gauge, pixmap: &mut Pixmap) {
+    let dashboard = DashboardTheme::Dark;
+    let speedometer = speedometer(gauge.value, gauge.min, gauge.max);
+    let (theme, speedometer) = match gauge.theme {
+        Theme::Dark => (
+            dashboard.iced(),
+            speedometer.style(move |_, status| dashboard.speedometer(status)),
+        ),
+        Theme::Light => (iced::Theme::Light, speedometer),
+    };
+
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let bounds = Rectangle::with_size(Size::new(width as f32, height as f32));
+    let mut renderer =
+        iced::Renderer::Secondary(iced_tiny_skia::Renderer::new(Font::DEFAULT, Pixels(16.0)));
+
+    let widget: &dyn Widget<(), _, _> = &speedometer;
+    let node = layout::Node::new(bounds.size());
+    widget.draw(
+        &Tree::new(widget),
+        &mut renderer,
+        &theme,
+        &renderer::Style::default(),
+        Layout::new(&node),
+        mouse::Cursor::Unavailable,
+        &bounds,
+    );
+
+    let iced::Renderer::Secondary(mut renderer) = renderer else {
+        unreachable!("the renderer was built as tiny-skia");
+    };
+    let mut clip_mask = Mask::new(width, height).expect("the pixmap has the same size");
+    renderer.draw(
+        &mut pixmap.as_mut(),
+        &mut clip_mask,
+        &Viewport::with_physical_size(Size::new(width, height), 1.0),
+        &[bounds],
+        theme.extended_palette().background.base.color,
+        &[] as &[&str],
+    );
+
+    // The renderer writes BGRA for the window surface.
+    for pixel in pixmap.data_mut().chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
@@ -0,0 +1,84 @@
+//! Renders the egui, iced and Slint speedometers to PNG images without a window
+//! or a GPU, for design reviews and documentation.
+//!
+//! egui goes through its tessellated meshes and a small CPU rasteriser, iced
+//! through the tiny-skia renderer and Slint through the software renderer.
+//!
+//! The `parity` tool renders the three at the same gauge and measures how far
+//! apart they are, see [`parity`].
+
+mod args;
+mod egui_gauge;
+mod gauge;
+pub mod golden;
+mod iced_gauge;
+pub mod parity;
+mod raster;
+mod slint_gauge;
+
+pub use args::{ArgsError, ParityArgs, RenderArgs};
+pub use gauge::{Framework, Gauge, Theme};
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use tiny_skia::Pixmap;
+
+/// Draws `gauge` with `framework` into a new image.
+pub fn render(framework: Framework, gauge: &Gauge) -> Result<Pixmap, RenderError> {
+    let mut pixmap = Pixmap::new(gauge.width, gauge.height)
+        .ok_or(RenderError::InvalidSize(gauge.width, gauge.height))?;
+
+    match framework {
+        Framework::Egui => egui_gauge::render(gauge, &mut pixmap),
+        Framework::Iced => iced_gauge::render(gauge, &mut pixmap),
+        Framework::Slint => slint_gauge::render(gauge, &mut pixmap)?,
+    }
+
+    Ok(pixmap)
+}
+
+pub fn save_png(pixmap: &Pixmap, path: impl AsRef<Path>) -> Result<(), RenderError> {
+    let png = pixmap
+        .encode_png()
+        .map_err(|err| RenderError::Encode(Box::new(err)))?;
+    std::fs::write(path, png).map_err(RenderError::Io)
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    InvalidSize(u32, u32),
+    Slint(slint::PlatformError),
+    Encode(Box<dyn Error + Send + Sync>),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::InvalidSize(width, height) => {
+                write!(f, "cannot render an image of {width}x{height} pixels")
+            }
+            RenderError::Slint(err) => write!(f, "Slint: {err}"),
+            RenderError::Encode(err) => write!(f, "cannot encode the PNG: {err}"),
+            RenderError::Io(err) => write!(f, "cannot write the PNG: {err}"),
+        }
+    }
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RenderError::InvalidSize(..) => None,
+            RenderError::Slint(err) => Some(err),
+            RenderError::Encode(err) => Some(err.as_ref()),
+            RenderError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<slint::PlatformError> for RenderError {
+    fn from(err: slint::PlatformError) -> Self {
+        RenderError::Slint(err)
+    }
+}
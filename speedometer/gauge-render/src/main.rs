@@ -0,0 +1,29 @@
+use gauge_render::{render, save_png, RenderArgs};
+
+const USAGE: &str = "\
+usage: render [--framework egui|iced|slint] [--value <number>] [--min <number>]
+              [--max <number>] [--size <pixels>|<width>x<height>]
+              [--theme dark|light] [--output <path>]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let args = RenderArgs::parse(args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        std::process::exit(2);
+    });
+
+    let output = args.output();
+    let rendered =
+        render(args.framework, &args.gauge).and_then(|pixmap| save_png(&pixmap, &output));
+    if let Err(err) = rendered {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+
+    println!("{}", output.display());
+}
@@ -0,0 +1,485 @@
+//! Measures how far the egui, iced and Slint speedometers are from looking the
+//! same.
+//!
+//! Every dial is rendered at the value and at two values far from it: what the
+//! three images share is the static dial, what only the first one has is the
+//! needle. The needle angle is the mean direction of the needle pixels in
+//! [`NEEDLE_BAND`], the angle of a tick the centroid of the dial pixels in
+//! [`TICK_BAND`] around the angle `gauge_core` gives it, and both are compared with
+//! [`Scale`]. The dials are then compared with each other pixel by pixel.
+
+use crate::gauge::{Framework, Gauge};
+use crate::golden::delta;
+use crate::{render, RenderError};
+use gauge_core::{direction, Scale, TickSpec};
+use std::fmt;
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+/// Distances from the centre, in dial radii, where only the needle is drawn.
+pub const NEEDLE_BAND: (f32, f32) = (0.3, 0.6);
+/// Distances from the centre, in dial radii, where every dial draws its ticks and
+/// no labels. The dials keep margins of a fixed number of pixels, so in images much
+/// smaller than the default 512 pixels the iced ticks stop short of the band.
+pub const TICK_BAND: (f32, f32) = (0.855, 0.92);
+
+/// Perceived difference from the dial background above which a pixel is drawn.
+const INK: f32 = 0.2;
+/// Resolution of the tick search, in degrees.
+const ANGLE_STEP: f32 = 0.1;
+/// Widest search for a tick on each side of its expected angle, in degrees.
+const MAX_TICK_WINDOW: f32 = 5.0;
+
+/// Measurements of the three dials drawing the same gauge.
+#[derive(Debug)]
+pub struct Parity {
+    pub gauge: Gauge,
+    pub dials: Vec<Dial>,
+    pub pairs: Vec<Pair>,
+    /// How much each dial draws at every pixel, egui in red, iced in green and Slint
+    /// in blue: what all three draw alike is grey to white.
+    pub overlay: Pixmap,
+}
+
+/// Geometry measured on the render of one framework, angles in degrees as in
+/// `gauge_core`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dial {
+    pub framework: Framework,
+    pub expected_needle: f32,
+    /// `None` when no needle was found in [`NEEDLE_BAND`].
+    pub needle: Option<f32>,
+    pub ticks: Vec<TickAngle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickAngle {
+    pub value: f32,
+    pub expected: f32,
+    /// `None` when nothing is drawn near the expected angle.
+    pub measured: Option<f32>,
+}
+
+/// Pixel differences between two dials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pair {
+    pub frameworks: (Framework, Framework),
+    /// Share of the dial drawn by one framework and not the other, colours aside.
+    /// A pixel is forgiven when a neighbour matches it.
+    pub pixels: f32,
+    /// Perceived difference of the two dial backgrounds, `0.0` to `1.0`.
+    pub background: f32,
+}
+
+impl Dial {
+    /// Signed error of the needle angle, `None` when it was not found.
+    pub fn needle_error(&self) -> Option<f32> {
+        self.needle.map(|needle| needle - self.expected_needle)
+    }
+
+    /// Largest tick angle error, `None` when a tick is missing.
+    pub fn tick_error(&self) -> Option<f32> {
+        self.ticks.iter().try_fold(0.0_f32, |worst, tick| {
+            Some(worst.max((tick.measured? - tick.expected).abs()))
+        })
+    }
+}
+
+/// Largest differences allowed by [`Limits::violations`], the ones left `None`
+/// are not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Limits {
+    /// Degrees.
+    pub needle: Option<f32>,
+    /// Degrees.
+    pub ticks: Option<f32>,
+    /// Share of the dial, see [`Pair::pixels`].
+    pub pixels: Option<f32>,
+}
+
+impl Limits {
+    /// What exceeds the limits, one line each.
+    pub fn violations(&self, parity: &Parity) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        for dial in &parity.dials {
+            if let Some(limit) = self.needle {
+                match dial.needle_error() {
+                    None => violations.push(format!("{}: no needle found", dial.framework)),
+                    Some(error) if error.abs() > limit => violations.push(format!(
+                        "{}: needle off by {error:+.2}°, more than {limit}°",
+                        dial.framework
+                    )),
+                    Some(_) => {}
+                }
+            }
+            if let Some(limit) = self.ticks {
+                match dial.tick_error() {
+                    None => violations.push(format!("{}: ticks missing", dial.framework)),
+                    Some(error) if error > limit => violations.push(format!(
+                        "{}: a tick is off by {error:.2}°, more than {limit}°",
+                        dial.framework
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if let Some(limit) = self.pixels {
+            for pair in parity.pairs.iter().filter(|pair| pair.pixels > limit) {
+                violations.push(format!(
+                    "{} and {}: {:.2}% of the dial differs, more than {:.2}%",
+                    pair.frameworks.0,
+                    pair.frameworks.1,
+                    pair.pixels * 100.0,
+                    limit * 100.0
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+/// The renders of one framework and where its dial is.
+struct Renders {
+    at_value: Pixmap,
+    elsewhere: [Pixmap; 2],
+    background: PremultipliedColorU8,
+    center: (f32, f32),
+    radius: f32,
+}
+
+impl Renders {
+    fn new(framework: Framework, gauge: &Gauge) -> Result<Self, RenderError> {
+        let scale = Scale::new(gauge.min, gauge.max);
+        let angle = scale.angle(gauge.value);
+        // The two of the ends and the middle whose needles are farthest away.
+        let mut others = [gauge.min, (gauge.min + gauge.max) / 2.0, gauge.max];
+        others.sort_by(|a, b| {
+            let distance = |value: &f32| (scale.angle(*value) - angle).abs();
+            distance(b).total_cmp(&distance(a))
+        });
+        let elsewhere = |value| render(framework, &Gauge { value, ..*gauge });
+
+        let at_value = render(framework, gauge)?;
+        let center = (gauge.width as f32 / 2.0, gauge.height as f32 / 2.0);
+        let radius = center.0.min(center.1);
+        // Below the centre, in the gap at the bottom of the dial.
+        let background = sample(&at_value, center.0, center.1 + radius / 2.0);
+
+        Ok(Self {
+            elsewhere: [elsewhere(others[0])?, elsewhere(others[1])?],
+            at_value,
+            background,
+            center,
+            radius,
+        })
+    }
+
+    fn ink(&self, pixmap: &Pixmap, x: f32, y: f32) -> f32 {
+        delta(sample(pixmap, x, y), self.background)
+    }
+
+    /// What at least two renders draw at a point, i.e. the dial without the needle
+    /// since the needles never overlap.
+    fn static_ink(&self, x: f32, y: f32) -> f32 {
+        let mut inks = [&self.at_value, &self.elsewhere[0], &self.elsewhere[1]]
+            .map(|pixmap| self.ink(pixmap, x, y));
+        inks.sort_by(f32::total_cmp);
+        inks[1]
+    }
+
+    fn needle(&self) -> Option<f32> {
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+
+        for y in 0..self.at_value.height() {
+            for x in 0..self.at_value.width() {
+                let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+                let (dx, dy) = (x - self.center.0, y - self.center.1);
+                let distance = dx.hypot(dy);
+                if !(NEEDLE_BAND.0..NEEDLE_BAND.1).contains(&(distance / self.radius)) {
+                    continue;
+                }
+
+                let weight = self.ink(&self.at_value, x, y);
+                let elsewhere = self
+                    .elsewhere
+                    .iter()
+                    .any(|pixmap| self.ink(pixmap, x, y) >= INK);
+                if weight >= INK && !elsewhere {
+                    sum_x += weight * dx / distance;
+                    sum_y += weight * dy / distance;
+                }
+            }
+        }
+
+        (sum_x != 0.0 || sum_y != 0.0).then(|| angle_of(sum_x, sum_y))
+    }
+
+    /// Centroid of the dial pixels within `window` degrees of `expected`.
+    fn tick(&self, expected: f32, window: f32) -> Option<f32> {
+        let (mut sum, mut weights) = (0.0, 0.0);
+        let steps = (2.0 * window / ANGLE_STEP).round() as usize;
+
+        for step in 0..=steps {
+            let angle = expected - window + step as f32 * ANGLE_STEP;
+            let (ux, uy) = direction(angle);
+            let (from, to) = (TICK_BAND.0 * self.radius, TICK_BAND.1 * self.radius);
+
+            let weight = (0..=(to - from) as usize)
+                .map(|offset| {
+                    let distance = from + offset as f32;
+                    self.static_ink(self.center.0 + ux * distance, self.center.1 + uy * distance)
+                })
+                .fold(0.0, f32::max);
+            if weight >= INK {
+                sum += weight * angle;
+                weights += weight;
+            }
+        }
+
+        (weights > 0.0).then(|| sum / weights)
+    }
+
+    fn dial(&self, framework: Framework, gauge: &Gauge) -> Dial {
+        let scale = Scale::new(gauge.min, gauge.max);
+        let expected = scale.ticks(&TickSpec::for_range(gauge.min, gauge.max));
+
+        let ticks = expected
+            .iter()
+            .enumerate()
+            .map(|(i, tick)| {
+                // Half way to the nearest neighbour, so windows never overlap.
+                let gap = [i.checked_sub(1), Some(i + 1)]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|j| expected.get(j))
+                    .map(|other| (other.angle - tick.angle).abs())
+                    .fold(2.0 * MAX_TICK_WINDOW, f32::min);
+
+                TickAngle {
+                    value: tick.value,
+                    expected: tick.angle,
+                    measured: self.tick(tick.angle, gap / 2.0),
+                }
+            })
+            .collect();
+
+        Dial {
+            framework,
+            expected_needle: scale.angle(gauge.value),
+            needle: self.needle(),
+            ticks,
+        }
+    }
+}
+
+/// Renders every framework at `gauge` and measures the dials.
+pub fn check(gauge: &Gauge) -> Result<Parity, RenderError> {
+    let renders = Framework::ALL
+        .into_iter()
+        .map(|framework| Renders::new(framework, gauge))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let dials = Framework::ALL
+        .into_iter()
+        .zip(&renders)
+        .map(|(framework, renders)| renders.dial(framework, gauge))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for a in 0..renders.len() {
+        for b in a + 1..renders.len() {
+            pairs.push(Pair {
+                frameworks: (Framework::ALL[a], Framework::ALL[b]),
+                pixels: pixel_difference(&renders[a], &renders[b]),
+                background: delta(renders[a].background, renders[b].background),
+            });
+        }
+    }
+
+    Ok(Parity {
+        gauge: *gauge,
+        dials,
+        pairs,
+        overlay: overlay(&renders),
+    })
+}
+
+/// Share of the dial where one render draws and the other does not, forgiving
+/// pixels that a neighbour matches.
+fn pixel_difference(a: &Renders, b: &Renders) -> f32 {
+    let (width, height) = (a.at_value.width(), a.at_value.height());
+    let ink_a = |x: u32, y: u32| a.ink(&a.at_value, x as f32 + 0.5, y as f32 + 0.5);
+    let ink_b = |x: u32, y: u32| b.ink(&b.at_value, x as f32 + 0.5, y as f32 + 0.5);
+    let near = |x: u32, y: u32| {
+        let xs = x.saturating_sub(1)..=(x + 1).min(width - 1);
+        let ys = y.saturating_sub(1)..=(y + 1).min(height - 1);
+        ys.flat_map(move |y| xs.clone().map(move |x| (x, y)))
+    };
+
+    let (mut dial, mut different) = (0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - a.center.0, y as f32 + 0.5 - a.center.1);
+            if dx.hypot(dy) > a.radius {
+                continue;
+            }
+            dial += 1;
+
+            let (here_a, here_b) = (ink_a(x, y), ink_b(x, y));
+            let matches = (here_a - here_b).abs() <= INK
+                || (near(x, y).any(|(x, y)| (ink_b(x, y) - here_a).abs() <= INK)
+                    && near(x, y).any(|(x, y)| (ink_a(x, y) - here_b).abs() <= INK));
+            if !matches {
+                different += 1;
+            }
+        }
+    }
+
+    different as f32 / dial.max(1) as f32
+}
+
+fn overlay(renders: &[Renders]) -> Pixmap {
+    let (width, height) = (renders[0].at_value.width(), renders[0].at_value.height());
+    let mut overlay = Pixmap::new(width, height).expect("the renders are not empty");
+
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b] = std::array::from_fn(|i| {
+                let ink = renders[i].ink(&renders[i].at_value, x as f32 + 0.5, y as f32 + 0.5);
+                (ink * 2.0).min(1.0) * 255.0
+            });
+            overlay.pixels_mut()[(y * width + x) as usize] =
+                PremultipliedColorU8::from_rgba(r as u8, g as u8, b as u8, 255).unwrap();
+        }
+    }
+
+    overlay
+}
+
+/// Nearest pixel, clamped to the image.
+fn sample(pixmap: &Pixmap, x: f32, y: f32) -> PremultipliedColorU8 {
+    let x = (x.max(0.0) as u32).min(pixmap.width() - 1);
+    let y = (y.max(0.0) as u32).min(pixmap.height() - 1);
+    pixmap.pixels()[(y * pixmap.width() + x) as usize]
+}
+
+/// Dial angle of a direction in screen coordinates, the inverse of [`direction`].
+fn angle_of(x: f32, y: f32) -> f32 {
+    x.atan2(-y).to_degrees()
+}
+
+impl fmt::Display for Parity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gauge = &self.gauge;
+        writeln!(
+            f,
+            "{} on {} to {}, {}x{}, {}",
+            gauge.value, gauge.min, gauge.max, gauge.width, gauge.height, gauge.theme
+        )?;
+
+        writeln!(
+            f,
+            "\n{:<8}{:>10}{:>10}{:>10}",
+            "needle", "expected", "measured", "error"
+        )?;
+        for dial in &self.dials {
+            let measured = dial
+                .needle
+                .map_or("-".into(), |angle| format!("{angle:.2}°"));
+            let error = dial
+                .needle_error()
+                .map_or("-".into(), |error| format!("{error:+.2}°"));
+            writeln!(
+                f,
+                "{:<8}{:>10}{measured:>10}{error:>10}",
+                dial.framework.name(),
+                format!("{:.2}°", dial.expected_needle),
+            )?;
+        }
+
+        writeln!(
+            f,
+            "\n{:<8}{:>10}{:>10}{:>10}",
+            "ticks", "expected", "found", "worst"
+        )?;
+        for dial in &self.dials {
+            let found = dial.ticks.iter().filter(|tick| tick.measured.is_some());
+            let worst = dial
+                .ticks
+                .iter()
+                .filter_map(|tick| Some((tick.measured? - tick.expected).abs()))
+                .fold(0.0, f32::max);
+            writeln!(
+                f,
+                "{:<8}{:>10}{:>10}{:>10}",
+                dial.framework.name(),
+                dial.ticks.len(),
+                found.count(),
+                format!("{worst:.2}°")
+            )?;
+        }
+
+        writeln!(f, "\n{:<14}{:>10}{:>12}", "pixels", "differ", "background")?;
+        for pair in &self.pairs {
+            writeln!(
+                f,
+                "{:<14}{:>10}{:>12.2}",
+                format!("{} / {}", pair.frameworks.0, pair.frameworks.1),
+                format!("{:.2}%", pair.pixels * 100.0),
+                pair.background
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn angles_match_gauge_core() {
+        for angle in [-130.0, -45.0, 0.0, 90.0, 130.0] {
+            let (x, y) = direction(angle);
+            assert!((angle_of(x, y) - angle).abs() < 1e-3, "{angle}");
+        }
+    }
+
+    #[test]
+    fn limits_report_what_they_check() {
+        let dial = Dial {
+            framework: Framework::Iced,
+            expected_needle: 10.0,
+            needle: Some(12.5),
+            ticks: vec![TickAngle {
+                value: 0.0,
+                expected: -130.0,
+                measured: None,
+            }],
+        };
+        let parity = Parity {
+            gauge: Gauge::default(),
+            dials: vec![dial],
+            pairs: Vec::new(),
+            overlay: Pixmap::new(1, 1).unwrap(),
+        };
+
+        assert!(Limits::default().violations(&parity).is_empty());
+        let violations = Limits {
+            needle: Some(1.0),
+            ticks: Some(1.0),
+            pixels: Some(0.0),
+        }
+        .violations(&parity);
+        assert_eq!(
+            violations,
+            [
+                "iced: needle off by +2.50°, more than 1°",
+                "iced: ticks missing"
+            ]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Just enough of a GPU, on the CPU, to draw egui's tessellated meshes.
+//!
+//! Triangles are filled with the top-left rule so that shared edges are blended
+//! once, vertex colours are interpolated and multiplied with a bilinear texture
+//! sample, and the result is blended over the target with premultiplied alpha in
+//! gamma space, the way the egui backends do.
+
+use eframe::egui::{Color32, ImageData, Pos2, TextureId, TexturesDelta};
+use eframe::epaint::{ClippedPrimitive, Mesh, Primitive, Vertex};
+use std::collections::HashMap;
+use tiny_skia::Pixmap;
+
+/// Textures uploaded by egui, kept as premultiplied sRGBA.
+#[derive(Debug, Default)]
+pub struct Textures {
+    images: HashMap<TextureId, Texture>,
+}
+
+#[derive(Debug)]
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+}
+
+impl Textures {
+    /// Applies the uploads and frees of a frame.
+    pub fn update(&mut self, delta: &TexturesDelta) {
+        for (id, image_delta) in &delta.set {
+            let (size, pixels) = match &image_delta.image {
+                ImageData::Color(image) => (image.size, image.pixels.clone()),
+                ImageData::Font(image) => (image.size, image.srgba_pixels(None).collect()),
+            };
+
+            match (image_delta.pos, self.images.get_mut(id)) {
+                (Some([x, y]), Some(texture)) => {
+                    for (row, line) in pixels.chunks_exact(size[0]).enumerate() {
+                        let start = (y + row) * texture.size[0] + x;
+                        texture.pixels[start..start + size[0]].copy_from_slice(line);
+                    }
+                }
+                _ => {
+                    self.images.insert(*id, Texture { size, pixels });
+                }
+            }
+        }
+
+        for id in &delta.free {
+            self.images.remove(id);
+        }
+    }
+}
+
+impl Texture {
+    /// Bilinear sample at normalised coordinates, clamped to the edges.
+    fn sample(&self, uv: Pos2) -> [f32; 4] {
+        let [width, height] = self.size;
+        let x = (uv.x * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+        let y = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (fx, fy) = (x.fract(), y.fract());
+
+        let texel = |x: usize, y: usize| self.pixels[y * width + x].to_array();
+        let (a, b, c, d) = (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1));
+
+        std::array::from_fn(|i| {
+            let top = a[i] as f32 * (1.0 - fx) + b[i] as f32 * fx;
+            let bottom = c[i] as f32 * (1.0 - fx) + d[i] as f32 * fx;
+            (top * (1.0 - fy) + bottom * fy) / 255.0
+        })
+    }
+}
+
+/// Draws the primitives over `pixmap`, paint callbacks are skipped.
+pub fn draw(
+    pixmap: &mut Pixmap,
+    primitives: &[ClippedPrimitive],
+    textures: &Textures,
+    pixels_per_point: f32,
+) {
+    let size = [pixmap.width() as usize, pixmap.height() as usize];
+    let pixels = pixmap.data_mut();
+
+    for ClippedPrimitive {
+        clip_rect,
+        primitive,
+    } in primitives
+    {
+        let Primitive::Mesh(mesh) = primitive else {
+            continue;
+        };
+        let Some(texture) = textures.images.get(&mesh.texture_id) else {
+            continue;
+        };
+
+        let clip = [
+            (clip_rect.min.x * pixels_per_point).round().max(0.0) as usize,
+            (clip_rect.min.y * pixels_per_point).round().max(0.0) as usize,
+            ((clip_rect.max.x * pixels_per_point).round().max(0.0) as usize).min(size[0]),
+            ((clip_rect.max.y * pixels_per_point).round().max(0.0) as usize).min(size[1]),
+        ];
+        draw_mesh(pixels, size, clip, mesh, texture, pixels_per_point);
+    }
+}
+
+fn draw_mesh(
+    pixels: &mut [u8],
+    size: [usize; 2],
+    clip: [usize; 4],
+    mesh: &Mesh,
+    texture: &Texture,
+    pixels_per_point: f32,
+) {
+    for triangle in mesh.indices.chunks_exact(3) {
+        let vertices = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+        fill_triangle(pixels, size, clip, vertices, texture, pixels_per_point);
+    }
+}
+
+fn fill_triangle(
+    pixels: &mut [u8],
+    size: [usize; 2],
+    clip: [usize; 4],
+    mut vertices: [&Vertex; 3],
+    texture: &Texture,
+    pixels_per_point: f32,
+) {
+    let mut points = vertices.map(|vertex| vertex.pos * pixels_per_point);
+    let mut area = edge(points[0], points[1], points[2]);
+    if area == 0.0 {
+        return;
+    }
+    // Wind every triangle the same way, so inside is where all edges are positive.
+    if area < 0.0 {
+        points.swap(1, 2);
+        vertices.swap(1, 2);
+        area = -area;
+    }
+
+    let min_x = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+    let max_x = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+    let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+    let x_range =
+        (min_x.floor().max(clip[0] as f32) as usize)..(max_x.ceil() as usize).min(clip[2]);
+    let y_range =
+        (min_y.floor().max(clip[1] as f32) as usize)..(max_y.ceil() as usize).min(clip[3]);
+
+    // Each weight belongs to the edge opposite its vertex.
+    let edges = [(1, 2), (2, 0), (0, 1)];
+    let owns_ties = edges.map(|(a, b)| is_top_left(points[a], points[b]));
+
+    for y in y_range {
+        for x in x_range.clone() {
+            let center = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let weights = edges.map(|(a, b)| edge(points[a], points[b], center));
+            let inside = weights
+                .iter()
+                .zip(owns_ties)
+                .all(|(&weight, owns_tie)| weight > 0.0 || (weight == 0.0 && owns_tie));
+            if !inside {
+                continue;
+            }
+
+            let weights = weights.map(|weight| weight / area);
+            let uv = Pos2::new(
+                interpolate(weights, vertices.map(|v| v.uv.x)),
+                interpolate(weights, vertices.map(|v| v.uv.y)),
+            );
+            let texel = texture.sample(uv);
+            let source: [f32; 4] = std::array::from_fn(|i| {
+                interpolate(weights, vertices.map(|v| v.color.to_array()[i] as f32)) * texel[i]
+            });
+
+            let offset = (y * size[0] + x) * 4;
+            blend(&mut pixels[offset..offset + 4], source);
+        }
+    }
+}
+
+/// Twice the signed area of `a`, `b`, `p`, positive when `p` is clockwise from `a`
+/// to `b` on a screen whose y axis points down.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn is_top_left(a: Pos2, b: Pos2) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
+}
+
+fn interpolate(weights: [f32; 3], values: [f32; 3]) -> f32 {
+    weights[0] * values[0] + weights[1] * values[1] + weights[2] * values[2]
+}
+
+/// Premultiplied "source over", `source` is `0` to `255`.
+fn blend(target: &mut [u8], source: [f32; 4]) {
+    let keep = 1.0 - source[3] / 255.0;
+    for (channel, source) in target.iter_mut().zip(source) {
+        *channel = (source + *channel as f32 * keep).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::{Rect, TextureId};
+    use eframe::epaint::WHITE_UV;
+
+    fn white_texture() -> Textures {
+        let mut textures = Textures::default();
+        textures.images.insert(
+            TextureId::default(),
+            Texture {
+                size: [1, 1],
+                pixels: vec![Color32::WHITE],
+            },
+        );
+        textures
+    }
+
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        let color = pixmap.pixel(x, y).unwrap();
+        [color.red(), color.green(), color.blue(), color.alpha()]
+    }
+
+    #[test]
+    fn fills_rectangles_once() {
+        let mut pixmap = Pixmap::new(8, 8).unwrap();
+        let mut mesh = Mesh::default();
+        let half_red = Color32::from_rgba_premultiplied(128, 0, 0, 128);
+        mesh.add_colored_rect(
+            Rect::from_min_max(Pos2::new(2.0, 2.0), Pos2::new(6.0, 6.0)),
+            half_red,
+        );
+        let primitives = [ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        draw(&mut pixmap, &primitives, &white_texture(), 1.0);
+
+        // The diagonal both triangles share is not blended twice.
+        for i in 2..6 {
+            assert_eq!(pixel(&pixmap, i, i), [128, 0, 0, 128]);
+        }
+        assert_eq!(pixel(&pixmap, 1, 1), [0, 0, 0, 0]);
+        assert_eq!(pixel(&pixmap, 6, 2), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn clips_and_scales() {
+        let mut pixmap = Pixmap::new(8, 8).unwrap();
+        let mut mesh = Mesh::default();
+        mesh.add_rect_with_uv(
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(4.0, 4.0)),
+            Rect::from_min_max(WHITE_UV, WHITE_UV),
+            Color32::WHITE,
+        );
+        let primitives = [ClippedPrimitive {
+            clip_rect: Rect::from_min_max(Pos2::ZERO, Pos2::new(2.0, 4.0)),
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        draw(&mut pixmap, &primitives, &white_texture(), 2.0);
+
+        assert_eq!(pixel(&pixmap, 3, 7), [255; 4]);
+        assert_eq!(pixel(&pixmap, 4, 0), [0; 4]);
+    }
+}
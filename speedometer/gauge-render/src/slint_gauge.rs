@@ -0,0 +1,70 @@
+use crate::gauge::{Gauge, Theme};
+use demo_slint::{tick_model, GaugeWindow};
+use gauge_core::{Scale, TickSpec};
+use slint::platform::software_renderer::{
+    MinimalSoftwareWindow, PremultipliedRgbaColor, RepaintBufferType,
+};
+use slint::platform::{Platform, WindowAdapter};
+use slint::{ComponentHandle, PhysicalSize, PlatformError};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tiny_skia::Pixmap;
+
+thread_local! {
+    /// Window the platform created last, picked up by [`render`] to draw it.
+    static WINDOW: RefCell<Option<Rc<MinimalSoftwareWindow>>> = const { RefCell::new(None) };
+}
+
+/// Platform whose windows are only ever drawn to memory.
+struct Headless;
+
+impl Platform for Headless {
+    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
+        let window = MinimalSoftwareWindow::new(RepaintBufferType::NewBuffer);
+        WINDOW.with(|slot| *slot.borrow_mut() = Some(window.clone()));
+        Ok(window)
+    }
+}
+
+/// Shows the `GaugeWindow` on a headless platform and draws it with the software
+/// renderer.
+pub fn render(gauge: &Gauge, pixmap: &mut Pixmap) -> Result<(), PlatformError> {
+    // Only fails when this thread already has a platform, e.g. from an earlier call.
+    let _ = slint::platform::set_platform(Box::new(Headless));
+
+    let ui = GaugeWindow::new()?;
+    let (width, height) = (pixmap.width(), pixmap.height());
+    // The window adapter is only created when the window is first used.
+    ui.window().set_size(PhysicalSize::new(width, height));
+    let window = WINDOW
+        .with(|slot| slot.borrow_mut().take())
+        .ok_or_else(|| PlatformError::Other("no headless window was created".into()))?;
+
+    let scale = Scale::new(gauge.min, gauge.max);
+    ui.set_min_speed(gauge.min);
+    ui.set_max_speed(gauge.max);
+    ui.set_start_angle(scale.start_angle());
+    ui.set_sweep_angle(scale.sweep_angle());
+
+    let ticks = TickSpec::for_range(gauge.min, gauge.max);
+    ui.set_ticks(tick_model(&scale, &ticks, 0));
+
+    ui.set_speed(gauge.value);
+    ui.global::<demo_slint::Theme>()
+        .set_dark(gauge.theme == Theme::Dark);
+
+    ui.show()?;
+    slint::platform::update_timers_and_animations();
+
+    let mut buffer = vec![PremultipliedRgbaColor::default(); (width * height) as usize];
+    window.draw_if_needed(|renderer| {
+        renderer.render(&mut buffer, width as usize);
+    });
+    ui.hide()?;
+
+    for (pixel, color) in pixmap.data_mut().chunks_exact_mut(4).zip(buffer) {
+        pixel.copy_from_slice(&[color.red, color.green, color.blue, color.alpha]);
+    }
+
+    Ok(())
+}
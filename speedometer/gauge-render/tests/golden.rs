@@ -0,0 +1,100 @@
+//! Renders every speedometer at a matrix of values, sizes and themes and compares
+//! the images with the references in `tests/golden`.
+//!
+//! After a deliberate change of the dial, write the references again with
+//! `UPDATE_GOLDEN=1 cargo test -p gauge-render --test golden` and review them. A
+//! failure leaves the rendered image and a diff next to each other in
+//! `target/tmp/golden`.
+
+use gauge_render::golden::{compare, Comparison, Tolerance};
+use gauge_render::{render, save_png, Framework, Gauge, Theme};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tiny_skia::Pixmap;
+
+/// The bottom of the dial, a value between ticks and one pinned to the top.
+const VALUES: [f32; 3] = [0.0, 87.5, 230.0];
+/// Square, and wider than high so the dial is centred.
+const SIZES: [(u32, u32); 2] = [(160, 160), (320, 200)];
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn failure_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden")
+}
+
+fn check(framework: Framework) {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for theme in Theme::ALL {
+        for value in VALUES {
+            for (width, height) in SIZES {
+                let gauge = Gauge {
+                    value,
+                    width,
+                    height,
+                    theme,
+                    ..Gauge::default()
+                };
+                let name = format!("{framework}-{theme}-{value}-{width}x{height}");
+                let actual = render(framework, &gauge).unwrap();
+                let reference = golden_dir().join(format!("{name}.png"));
+
+                if update {
+                    save_png(&actual, &reference).unwrap();
+                    continue;
+                }
+                let Ok(expected) = Pixmap::load_png(&reference) else {
+                    failures.push(format!("{name}: no reference, run with UPDATE_GOLDEN=1"));
+                    continue;
+                };
+
+                let failure = match compare(&expected, &actual, Tolerance::default()) {
+                    Comparison::Match => continue,
+                    Comparison::SizeMismatch { expected, actual } => {
+                        format!("{name}: {actual:?} pixels instead of {expected:?}")
+                    }
+                    Comparison::Different {
+                        pixels,
+                        share,
+                        diff,
+                    } => {
+                        fs::create_dir_all(failure_dir()).unwrap();
+                        save_png(&diff, failure_dir().join(format!("{name}.diff.png"))).unwrap();
+                        format!("{name}: {pixels} pixels differ ({:.2}%)", share * 100.0)
+                    }
+                };
+                fs::create_dir_all(failure_dir()).unwrap();
+                save_png(&actual, failure_dir().join(format!("{name}.png"))).unwrap();
+                failures.push(failure);
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} images differ from the references, see {}:\n{}",
+        failures.len(),
+        failure_dir().display(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn egui_matches_the_references() {
+    check(Framework::Egui);
+}
+
+#[test]
+fn iced_matches_the_references() {
+    check(Framework::Iced);
+}
+
+#[test]
+fn slint_matches_the_references() {
+    check(Framework::Slint);
+}
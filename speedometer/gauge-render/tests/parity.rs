@@ -0,0 +1,31 @@
+//! Keeps the egui, iced and Slint dials in agreement on both themes, see the
+//! `parity` tool for the full report.
+
+use gauge_render::parity::{self, Limits};
+use gauge_render::{Gauge, Theme};
+
+/// The needles and ticks of the three land within 0.03° of the scale, the limit is
+/// under half a pixel at the rim of the default 512 px dial. Up to 3.7% of the pixels
+/// differ, from each toolkit's own tick lengths, face shade and label font.
+const LIMITS: Limits = Limits {
+    needle: Some(0.1),
+    ticks: Some(0.1),
+    pixels: Some(0.04),
+};
+
+#[test]
+fn dials_agree() {
+    for theme in Theme::ALL {
+        for value in [0.0, 87.5, 100.0] {
+            let gauge = Gauge {
+                value,
+                theme,
+                ..Gauge::default()
+            };
+            let parity = parity::check(&gauge).unwrap();
+
+            let violations = LIMITS.violations(&parity);
+            assert!(violations.is_empty(), "{parity}\n{}", violations.join("\n"));
+        }
+    }
+}
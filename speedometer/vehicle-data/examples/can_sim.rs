@@ -0,0 +1,70 @@
+//! Puts a simulated drive on a SocketCAN bus, encoded with `vehicle.dbc`, so the
+//! dashboards can be tried with `--can vcan0` and no hardware:
+//!
+//! ```sh
+//! sudo modprobe vcan
+//! sudo ip link add dev vcan0 type vcan
+//! sudo ip link set up vcan0
+//! cargo run -p vehicle-data --example can_sim -- vcan0 highway
+//! cargo run -p demo-egui -- --can vcan0 --dbc vehicle-data/examples/vehicle.dbc
+//! ```
+//!
+//! Stopping the simulator makes the dashboards grey the speedometer out.
+
+#[cfg(target_os = "linux")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use std::thread;
+    use std::time::Instant;
+    use vehicle_data::can::{default_signals, CanFrame, CanSocket, Dbc};
+    use vehicle_data::{Channel, Generator, Sample, SAMPLE_PERIOD};
+
+    let mut args = std::env::args().skip(1);
+    let interface = args.next().unwrap_or_else(|| "vcan0".to_owned());
+    let generator: Generator = args.next().as_deref().unwrap_or("urban").parse()?;
+
+    let dbc = Dbc::parse(include_str!("vehicle.dbc"))?;
+    let signals = default_signals();
+    let socket = CanSocket::open(&interface)?;
+    let mut source = generator.source();
+    let started = Instant::now();
+
+    let value = |sample: &Sample, channel| match channel {
+        Channel::Speed => sample.speed,
+        Channel::Rpm => sample.rpm,
+        Channel::Gear => sample.gear as f32,
+        Channel::Fuel => sample.fuel,
+        Channel::CoolantTemp => sample.coolant_temp,
+        Channel::OilTemp => sample.oil_temp,
+        Channel::Heading => sample.heading.unwrap_or_default(),
+        Channel::Throttle => sample.throttle.unwrap_or_default(),
+        Channel::Brake => sample.brake.unwrap_or_default(),
+        Channel::Time => sample.time.as_secs_f32(),
+    };
+
+    println!("sending {} on {interface}", generator.name());
+    loop {
+        if let Some(sample) = source.poll(started.elapsed()) {
+            for message in &dbc.messages {
+                let mut payload = [0; 8];
+                for (channel, name, _) in signals.iter() {
+                    if let Some(signal) = message.signals.iter().find(|signal| signal.name == name)
+                    {
+                        signal.encode(value(&sample, channel) as f64, &mut payload);
+                    }
+                }
+                let len = message.size.min(8) as usize;
+                socket.write(&CanFrame::new(
+                    message.id,
+                    message.extended,
+                    &payload[..len],
+                ))?;
+            }
+        }
+        thread::sleep(SAMPLE_PERIOD);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("SocketCAN is only available on Linux");
+}
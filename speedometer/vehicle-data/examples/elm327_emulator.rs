@@ -0,0 +1,46 @@
+//! ELM327 adapter on a pseudo-terminal, answering with a simulated drive, so the
+//! dashboards can be tried with `--obd` and no car:
+//!
+//! ```sh
+//! cargo run -p vehicle-data --example elm327_emulator -- highway 10
+//! cargo run -p demo-iced -- --obd /dev/pts/3
+//! ```
+//!
+//! The optional second argument makes every n-th request fail with `NO DATA` or
+//! `STOPPED`. The emulator keeps running when the dashboard disconnects.
+
+#[cfg(unix)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use std::thread;
+    use std::time::Duration;
+    use vehicle_data::obd::Elm327Emulator;
+    use vehicle_data::{open_pty, Generator};
+
+    let mut args = std::env::args().skip(1);
+    let generator: Generator = args.next().as_deref().unwrap_or("urban").parse()?;
+    let faults: u32 = args.next().as_deref().unwrap_or("0").parse()?;
+
+    let (mut master, path) = open_pty()?;
+    let mut emulator = Elm327Emulator::new(generator.source()).with_faults(faults);
+    println!(
+        "ELM327 emulator playing {} on {}",
+        generator.name(),
+        path.display()
+    );
+
+    loop {
+        match emulator.serve(&mut master) {
+            // Nobody has the terminal open, wait for the next client.
+            Err(err) if err.raw_os_error() == Some(libc::EIO) => {
+                thread::sleep(Duration::from_millis(100))
+            }
+            Err(err) => return Err(err.into()),
+            Ok(()) => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("the emulator needs a Unix pseudo-terminal");
+}
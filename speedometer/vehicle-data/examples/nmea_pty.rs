@@ -0,0 +1,37 @@
+//! GPS receiver on a pseudo-terminal, replaying a recording of NMEA sentences at
+//! their original pace, so the dashboards can be tried with `--nmea` and no GPS:
+//!
+//! ```sh
+//! cargo run -p vehicle-data --example nmea_pty -- vehicle-data/examples/drive.nmea
+//! cargo run -p demo-slint -- --nmea /dev/pts/3
+//! ```
+//!
+//! The recording loops. It starts without a fix and loses it again in a tunnel
+//! halfway through, the dashboards show "No signal" meanwhile.
+
+#[cfg(unix)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    use vehicle_data::nmea;
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/examples/drive.nmea").to_owned());
+    let recording = std::fs::read_to_string(&path)?;
+
+    let (mut master, pty) = vehicle_data::open_pty()?;
+    println!("replaying {path} on {}", pty.display());
+
+    nmea::play(&recording, true, |line| {
+        // Keeps going while no dashboard has the terminal open, like a receiver.
+        let _ = write!(master, "{line}\r\n");
+        true
+    });
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("the replay needs a Unix pseudo-terminal");
+}
@@ -0,0 +1,59 @@
+//! Sends a simulated drive as UDP telemetry to a local dashboard, the way a game
+//! would, so `--udp` can be tried without one:
+//!
+//! ```sh
+//! cargo run -p demo-slint -- --udp 20800 --layout outgauge
+//! cargo run -p vehicle-data --example udp_send -- highway outgauge 127.0.0.1:20800
+//! ```
+//!
+//! Throttle and brake follow the acceleration of the drive cycle. Stopping the
+//! sender makes the dashboards grey the speedometer out.
+
+use std::net::UdpSocket;
+use std::thread;
+use std::time::Instant;
+use vehicle_data::udp::{Layout, Telemetry, DEFAULT_PORT};
+use vehicle_data::{Generator, SAMPLE_PERIOD};
+
+/// Acceleration in km/h per second that floors the throttle or the brake.
+const FULL_PEDAL: f32 = 10.0;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let generator: Generator = args.next().as_deref().unwrap_or("urban").parse()?;
+    let layout: Layout = args.next().as_deref().unwrap_or("native").parse()?;
+    let target = args
+        .next()
+        .unwrap_or_else(|| format!("127.0.0.1:{DEFAULT_PORT}"));
+
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    socket.connect(&target)?;
+    let mut source = generator.source();
+    let started = Instant::now();
+    let mut last_speed = 0.0;
+    let mut sequence = 0u16;
+
+    println!("sending {} to {target} as {layout}", generator.name());
+    loop {
+        if let Some(sample) = source.poll(started.elapsed()) {
+            let acceleration = (sample.speed - last_speed) / SAMPLE_PERIOD.as_secs_f32();
+            last_speed = sample.speed;
+
+            let telemetry = Telemetry {
+                sequence: Some(sequence),
+                throttle: (acceleration / FULL_PEDAL).clamp(0.0, 1.0),
+                brake: (-acceleration / FULL_PEDAL).clamp(0.0, 1.0),
+                ..Telemetry::from_sample(&sample)
+            };
+            sequence = sequence.wrapping_add(1);
+
+            // Nobody listening yet is not an error for a game either.
+            if let Err(err) = socket.send(&layout.encode(&telemetry)) {
+                if err.kind() != std::io::ErrorKind::ConnectionRefused {
+                    return Err(err.into());
+                }
+            }
+        }
+        thread::sleep(SAMPLE_PERIOD);
+    }
+}
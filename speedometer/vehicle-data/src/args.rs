@@ -0,0 +1,578 @@
+use crate::can::CanError;
+use crate::generator::{Generator, UnknownGenerator};
+use crate::log::{ColumnMap, Log, ReplayError};
+use crate::nmea::{NmeaError, NmeaSource};
+use crate::obd::{ObdError, PidSchedule};
+use crate::replay::{Replay, MAX_PLAYBACK_RATE, MIN_PLAYBACK_RATE};
+use crate::source::VehicleDataSource;
+use crate::udp::{Layout, UdpError, UdpSource};
+use cli_args::{ArgError, Args};
+use std::error::Error;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Flags that each pick a source, only one of them can be given.
+const SOURCES: [&str; 6] = ["--source", "--replay", "--can", "--obd", "--nmea", "--udp"];
+
+/// Flags that only apply to some sources, and those sources.
+const SOURCE_OPTIONS: [(&str, &[&str]); 9] = [
+    ("--columns", &["--replay"]),
+    ("--rate", &["--replay"]),
+    ("--loop", &["--replay", "--nmea"]),
+    ("--dbc", &["--can"]),
+    ("--signals", &["--can"]),
+    ("--timeout", &["--can", "--obd", "--nmea", "--udp"]),
+    ("--baud", &["--obd", "--nmea"]),
+    ("--pids", &["--obd"]),
+    ("--layout", &["--udp"]),
+];
+
+/// Rates the serial ports of `--obd` and `--nmea` can be set to.
+const BAUD_RATES: [u32; 7] = [4_800, 9_600, 19_200, 38_400, 57_600, 115_200, 230_400];
+
+/// Source picked on the command line, see [`SourceArgs::parse`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceArgs {
+    pub generator: Generator,
+    pub replay: Option<PathBuf>,
+    pub columns: ColumnMap,
+    pub rate: Option<f32>,
+    pub looping: bool,
+    pub can: Option<String>,
+    pub dbc: Option<PathBuf>,
+    /// Overrides of [`crate::can::default_signals`].
+    pub signals: Option<String>,
+    pub timeout: Option<Duration>,
+    pub obd: Option<PathBuf>,
+    pub baud_rate: Option<u32>,
+    pub pids: PidSchedule,
+    pub nmea: Option<PathBuf>,
+    pub udp: Option<SocketAddr>,
+    pub layout: Option<Layout>,
+}
+
+impl SourceArgs {
+    /// Reads the arguments, without the program name:
+    ///
+    /// - `--source <name>` picks a [`Generator`], `urban` by default;
+    /// - `--replay <path>` plays a CSV or JSON log instead, with `--columns <map>`
+    ///   (see [`ColumnMap`]), `--rate <0.5-8>` and `--loop`;
+    /// - `--can <interface>` reads a SocketCAN bus instead, with `--dbc <path>`,
+    ///   `--signals <map>` naming the DBC signal of each channel in the
+    ///   [`ColumnMap`] syntax, and `--timeout <seconds>` of silence after which the
+    ///   gauges grey out;
+    /// - `--obd <serial port>` polls an ELM327 adapter instead, with `--baud <rate>`,
+    ///   `--pids <schedule>` (see [`PidSchedule`]) and `--timeout <seconds>`;
+    /// - `--nmea <path>` reads a GPS receiver instead, from a serial port with
+    ///   `--baud <rate>` or from a recorded file with `--loop`, and `--timeout`;
+    /// - `--udp <port|address:port>` listens for telemetry packets instead, with
+    ///   `--layout <native|outgauge|forza>` (see [`Layout`]) and `--timeout`.
+    ///
+    /// Values can also follow the flag after `=`, e.g. `--source=sweep`. An option
+    /// given without a source it applies to is an error.
+    pub fn parse<I>(args: I) -> Result<Self, ArgsError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut given = Vec::new();
+        let mut args = Args::new(args);
+
+        while let Some(arg) = args.next() {
+            match arg.flag() {
+                "--source" => parsed.generator = args.value(&arg)?.parse()?,
+                "--replay" => parsed.replay = Some(args.value(&arg)?.into()),
+                "--columns" => parsed.columns = args.value(&arg)?.parse()?,
+                "--rate" => {
+                    parsed.rate =
+                        Some(args.parse(&arg, "a playback rate from 0.5 to 8", |rate| {
+                            (MIN_PLAYBACK_RATE..=MAX_PLAYBACK_RATE).contains(rate)
+                        })?);
+                }
+                "--loop" => parsed.looping = true,
+                "--can" => parsed.can = Some(args.value(&arg)?),
+                "--dbc" => parsed.dbc = Some(args.value(&arg)?.into()),
+                "--signals" => parsed.signals = Some(args.value(&arg)?),
+                "--timeout" => {
+                    let seconds =
+                        args.parse(&arg, "a positive timeout in seconds", |seconds: &f32| {
+                            *seconds > 0.0 && Duration::try_from_secs_f32(*seconds).is_ok()
+                        })?;
+                    parsed.timeout = Some(Duration::from_secs_f32(seconds));
+                }
+                "--obd" => parsed.obd = Some(args.value(&arg)?.into()),
+                "--baud" => {
+                    parsed.baud_rate =
+                        Some(args.parse(&arg, "a baud rate from 4800 to 230400", |rate| {
+                            BAUD_RATES.contains(rate)
+                        })?);
+                }
+                "--pids" => parsed.pids = args.value(&arg)?.parse()?,
+                "--nmea" => parsed.nmea = Some(args.value(&arg)?.into()),
+                "--udp" => parsed.udp = Some(crate::udp::listen_address(&args.value(&arg)?)?),
+                "--layout" => parsed.layout = Some(args.value(&arg)?.parse()?),
+                _ => return Err(arg.unknown().into()),
+            }
+            given.push(arg.flag().to_owned());
+        }
+
+        let is_given = |flag: &str| given.iter().any(|given| given == flag);
+        if SOURCES
+            .into_iter()
+            .filter(|&source| is_given(source))
+            .count()
+            > 1
+        {
+            return Err(ArgsError::ConflictingSources);
+        }
+        for (flag, sources) in SOURCE_OPTIONS {
+            if is_given(flag) && !sources.iter().any(|&source| is_given(source)) {
+                return Err(ArgsError::UnusedFlag(flag, sources));
+            }
+        }
+        if parsed.can.is_some() && parsed.dbc.is_none() {
+            return Err(ArgError::MissingValue("--dbc".to_owned()).into());
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn source(&self) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        if let Some(interface) = &self.can {
+            return self.can_source(interface);
+        }
+        if let Some(port) = &self.obd {
+            return self.obd_source(port);
+        }
+        if let Some(path) = &self.nmea {
+            return self.nmea_source(path);
+        }
+        if let Some(address) = self.udp {
+            return self.udp_source(address);
+        }
+        let Some(path) = &self.replay else {
+            return Ok(self.generator.source());
+        };
+
+        let log = Log::open(path, &self.columns)?;
+        let replay = Replay::new(log)
+            .with_rate(self.rate.unwrap_or(1.0))
+            .with_looping(self.looping);
+
+        Ok(Box::new(replay))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn can_source(&self, interface: &str) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        use crate::can::{default_signals, CanDecoder, CanSource, Dbc};
+
+        let path = self
+            .dbc
+            .as_ref()
+            .ok_or_else(|| ArgError::MissingValue("--dbc".to_owned()))?;
+        let dbc = Dbc::open(path).map_err(CanError::from)?;
+        let signals = match &self.signals {
+            Some(pairs) => default_signals()
+                .with_pairs(pairs)
+                .map_err(CanError::from)?,
+            None => default_signals(),
+        };
+
+        let decoder = CanDecoder::new(&dbc, &signals)?;
+        let mut source = CanSource::open(interface, decoder)?;
+        if let Some(timeout) = self.timeout {
+            source = source.with_timeout(timeout);
+        }
+
+        Ok(Box::new(source))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn can_source(&self, _interface: &str) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        Err(CanError::Unsupported.into())
+    }
+
+    #[cfg(unix)]
+    fn obd_source(&self, port: &Path) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        use crate::obd::{Elm327, ObdSource, DEFAULT_BAUD_RATE};
+        use crate::serial::SerialPort;
+
+        let port = SerialPort::open(port, self.baud_rate.unwrap_or(DEFAULT_BAUD_RATE))
+            .map_err(ObdError::from)?;
+        let mut source = ObdSource::start(Elm327::new(port), self.pids.clone())?;
+        if let Some(timeout) = self.timeout {
+            source = source.with_timeout(timeout);
+        }
+
+        Ok(Box::new(source))
+    }
+
+    #[cfg(not(unix))]
+    fn obd_source(&self, _port: &Path) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        Err(ObdError::Io(std::io::ErrorKind::Unsupported.into()).into())
+    }
+
+    /// A character device is read as a receiver, anything else as a recording.
+    fn nmea_source(&self, path: &Path) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        #[cfg(unix)]
+        let source = {
+            use crate::nmea::DEFAULT_BAUD_RATE;
+            use crate::serial::SerialPort;
+            use std::os::unix::fs::FileTypeExt;
+
+            let device = std::fs::metadata(path)
+                .map_err(NmeaError::from)?
+                .file_type()
+                .is_char_device();
+            if device {
+                let port = SerialPort::open(path, self.baud_rate.unwrap_or(DEFAULT_BAUD_RATE))
+                    .map_err(NmeaError::from)?;
+                NmeaSource::start(port)
+            } else {
+                NmeaSource::replay(path, self.looping)?
+            }
+        };
+        #[cfg(not(unix))]
+        let source = NmeaSource::replay(path, self.looping)?;
+
+        Ok(Box::new(match self.timeout {
+            Some(timeout) => source.with_timeout(timeout),
+            None => source,
+        }))
+    }
+
+    fn udp_source(&self, address: SocketAddr) -> Result<Box<dyn VehicleDataSource>, ArgsError> {
+        let source = UdpSource::bind(address, self.layout.unwrap_or_default())?;
+
+        Ok(Box::new(match self.timeout {
+            Some(timeout) => source.with_timeout(timeout),
+            None => source,
+        }))
+    }
+}
+
+/// Parses the command line and builds the source, see [`SourceArgs::parse`].
+pub fn source_from_args<I>(args: I) -> Result<Box<dyn VehicleDataSource>, ArgsError>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    SourceArgs::parse(args)?.source()
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    Args(ArgError),
+    /// More than one of `--source`, `--replay`, `--can`, `--obd`, `--nmea` and `--udp`.
+    ConflictingSources,
+    /// A flag given without any of the sources it applies to, e.g. `--rate`
+    /// without `--replay`.
+    UnusedFlag(&'static str, &'static [&'static str]),
+    UnknownGenerator(UnknownGenerator),
+    Replay(ReplayError),
+    Can(CanError),
+    Obd(ObdError),
+    Nmea(NmeaError),
+    Udp(UdpError),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Args(err) => err.fmt(f),
+            ArgsError::ConflictingSources => f.write_str(
+                "only one of `--source`, `--replay`, `--can`, `--obd`, `--nmea` and `--udp` can be given",
+            ),
+            ArgsError::UnusedFlag(flag, sources) => {
+                write!(f, "`{flag}` only applies with `{}`", sources.join("` or `"))
+            }
+            ArgsError::UnknownGenerator(err) => err.fmt(f),
+            ArgsError::Replay(err) => err.fmt(f),
+            ArgsError::Can(err) => err.fmt(f),
+            ArgsError::Obd(err) => err.fmt(f),
+            ArgsError::Nmea(err) => err.fmt(f),
+            ArgsError::Udp(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ArgsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArgsError::Args(err) => Some(err),
+            ArgsError::UnknownGenerator(err) => Some(err),
+            ArgsError::Replay(err) => Some(err),
+            ArgsError::Can(err) => Some(err),
+            ArgsError::Obd(err) => Some(err),
+            ArgsError::Nmea(err) => Some(err),
+            ArgsError::Udp(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ArgError> for ArgsError {
+    fn from(err: ArgError) -> Self {
+        ArgsError::Args(err)
+    }
+}
+
+impl From<UnknownGenerator> for ArgsError {
+    fn from(err: UnknownGenerator) -> Self {
+        ArgsError::UnknownGenerator(err)
+    }
+}
+
+impl From<ReplayError> for ArgsError {
+    fn from(err: ReplayError) -> Self {
+        ArgsError::Replay(err)
+    }
+}
+
+impl From<CanError> for ArgsError {
+    fn from(err: CanError) -> Self {
+        ArgsError::Can(err)
+    }
+}
+
+impl From<ObdError> for ArgsError {
+    fn from(err: ObdError) -> Self {
+        ArgsError::Obd(err)
+    }
+}
+
+impl From<NmeaError> for ArgsError {
+    fn from(err: NmeaError) -> Self {
+        ArgsError::Nmea(err)
+    }
+}
+
+impl From<UdpError> for ArgsError {
+    fn from(err: UdpError) -> Self {
+        ArgsError::Udp(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::Channel;
+    use crate::obd::Pid;
+
+    #[test]
+    fn picks_a_generator() {
+        let sweep = SourceArgs::parse(["--source", "sweep"]).unwrap();
+        let random = SourceArgs::parse(["--source=random"]).unwrap();
+
+        assert_eq!(sweep.generator, Generator::Sweep);
+        assert_eq!(random.generator, Generator::RandomWalk);
+        assert_eq!(
+            SourceArgs::parse(Vec::<String>::new()).unwrap(),
+            SourceArgs::default()
+        );
+    }
+
+    #[test]
+    fn reads_replay_options() {
+        let args = SourceArgs::parse([
+            "--replay",
+            "drive.csv",
+            "--columns=time=ts,speed=vss",
+            "--rate",
+            "4",
+            "--loop",
+        ])
+        .unwrap();
+
+        assert_eq!(args.replay, Some(PathBuf::from("drive.csv")));
+        assert_eq!(args.columns.column(Channel::Speed), Some("vss"));
+        assert_eq!(args.rate, Some(4.0));
+        assert!(args.looping);
+    }
+
+    #[test]
+    fn reads_can_options() {
+        let args = SourceArgs::parse([
+            "--can",
+            "vcan0",
+            "--dbc=vehicle.dbc",
+            "--signals",
+            "speed=WheelSpeed",
+            "--timeout",
+            "0.5",
+        ])
+        .unwrap();
+
+        assert_eq!(args.can.as_deref(), Some("vcan0"));
+        assert_eq!(args.dbc, Some(PathBuf::from("vehicle.dbc")));
+        assert_eq!(args.signals.as_deref(), Some("speed=WheelSpeed"));
+        assert_eq!(args.timeout, Some(Duration::from_millis(500)));
+
+        assert!(matches!(
+            SourceArgs::parse(["--can", "vcan0"]),
+            Err(ArgsError::Args(ArgError::MissingValue(flag))) if flag == "--dbc"
+        ));
+        assert!(matches!(
+            SourceArgs::parse(["--can", "vcan0", "--dbc", "a.dbc", "--replay", "a.csv"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+        assert!(matches!(
+            SourceArgs::parse(["--can", "vcan0", "--dbc", "a.dbc", "--timeout", "-1"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+        ));
+        assert!(matches!(
+            SourceArgs::parse(["--can", "vcan0", "--dbc", "a.dbc", "--timeout", "0"]),
+            Err(ArgsError::Args(ArgError::InvalidValue { flag, .. })) if flag == "--timeout"
+        ));
+    }
+
+    #[test]
+    fn reads_obd_options() {
+        let args = SourceArgs::parse([
+            "--obd",
+            "/dev/ttyUSB0",
+            "--baud",
+            "115200",
+            "--pids=speed=0.05",
+        ])
+        .unwrap();
+
+        assert_eq!(args.obd, Some(PathBuf::from("/dev/ttyUSB0")));
+        assert_eq!(args.baud_rate, Some(115_200));
+        assert_eq!(
+            args.pids.period(Pid::Speed),
+            Some(Duration::from_millis(50))
+        );
+
+        assert!(matches!(
+            SourceArgs::parse(["--obd", "/dev/ttyUSB0", "--replay", "a.csv"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+        for baud in ["0", "1200", "115201", "fast"] {
+            assert!(matches!(
+                SourceArgs::parse(["--obd", "/dev/ttyUSB0", "--baud", baud]),
+                Err(ArgsError::Args(ArgError::InvalidValue { flag, .. })) if flag == "--baud"
+            ));
+        }
+        assert!(matches!(
+            SourceArgs::parse(["--pids", "gear=1"]),
+            Err(ArgsError::Obd(ObdError::InvalidSchedule(_)))
+        ));
+    }
+
+    #[test]
+    fn replays_nmea_recordings() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/drive.nmea");
+        let args = SourceArgs::parse(["--nmea", path, "--loop"]).unwrap();
+
+        assert_eq!(args.nmea, Some(PathBuf::from(path)));
+        assert!(args.source().is_ok());
+        assert!(matches!(
+            SourceArgs::parse(["--nmea", path, "--obd", "/dev/ttyUSB0"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+    }
+
+    #[test]
+    fn reads_udp_options() {
+        let args = SourceArgs::parse(["--udp", "127.0.0.1:0", "--layout=forza"]).unwrap();
+
+        assert_eq!(args.udp, Some("127.0.0.1:0".parse().unwrap()));
+        assert_eq!(args.layout, Some(Layout::Forza));
+        assert!(args.source().is_ok());
+        assert!(matches!(
+            SourceArgs::parse(["--layout", "gt7"]),
+            Err(ArgsError::Udp(UdpError::UnknownLayout(_)))
+        ));
+        assert!(matches!(
+            SourceArgs::parse(["--udp", "20800", "--replay", "a.csv"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+    }
+
+    #[test]
+    fn options_require_their_source() {
+        let option = |flag| match flag {
+            "--columns" => vec![flag, "speed=vss"],
+            "--rate" => vec![flag, "2"],
+            "--loop" => vec![flag],
+            "--dbc" => vec![flag, "vehicle.dbc"],
+            "--signals" => vec![flag, "speed=WheelSpeed"],
+            "--timeout" => vec![flag, "1"],
+            "--baud" => vec![flag, "9600"],
+            "--pids" => vec![flag, "speed=0.1"],
+            "--layout" => vec![flag, "forza"],
+            _ => unreachable!("{flag}"),
+        };
+        let source = |flag| match flag {
+            "--source" => vec![flag, "sweep"],
+            "--replay" => vec![flag, "drive.csv"],
+            "--can" => vec![flag, "vcan0", "--dbc", "vehicle.dbc"],
+            "--obd" => vec![flag, "/dev/ttyUSB0"],
+            "--nmea" => vec![flag, "drive.nmea"],
+            "--udp" => vec![flag, "20800"],
+            _ => unreachable!("{flag}"),
+        };
+
+        for (flag, sources) in SOURCE_OPTIONS {
+            for other in SOURCES {
+                let args = [source(other), option(flag)].concat();
+                let parsed = SourceArgs::parse(args);
+                if sources.contains(&other) {
+                    assert!(parsed.is_ok(), "{flag} with {other}: {parsed:?}");
+                } else {
+                    assert!(
+                        matches!(parsed, Err(ArgsError::UnusedFlag(unused, _)) if unused == flag),
+                        "{flag} with {other}: {parsed:?}"
+                    );
+                }
+            }
+            assert!(matches!(
+                SourceArgs::parse(option(flag)),
+                Err(ArgsError::UnusedFlag(unused, _)) if unused == flag
+            ));
+        }
+        assert_eq!(
+            ArgsError::UnusedFlag("--baud", &["--obd", "--nmea"]).to_string(),
+            "`--baud` only applies with `--obd` or `--nmea`"
+        );
+    }
+
+    #[test]
+    fn sources_exclude_each_other() {
+        assert!(matches!(
+            SourceArgs::parse(["--source", "sweep", "--replay", "a.csv"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+        assert!(matches!(
+            SourceArgs::parse(["--udp", "20800", "--source", "urban"]),
+            Err(ArgsError::ConflictingSources)
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let err = SourceArgs::parse(["--source", "nurburgring"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("urban highway combined wltp sweep random"));
+
+        assert!(matches!(
+            SourceArgs::parse(["--source"]),
+            Err(ArgsError::Args(ArgError::MissingValue(_)))
+        ));
+        for rate in ["fast", "NaN", "inf", "-1", "0", "0.25", "16"] {
+            assert!(matches!(
+                SourceArgs::parse(["--replay", "a.csv", "--rate", rate]),
+                Err(ArgsError::Args(ArgError::InvalidValue { .. }))
+            ));
+        }
+        assert!(matches!(
+            SourceArgs::parse(["--verbose"]),
+            Err(ArgsError::Args(ArgError::UnknownArgument(_)))
+        ));
+    }
+}
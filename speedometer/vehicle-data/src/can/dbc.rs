@@ -0,0 +1,430 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Bit 31 of a DBC message id marks an extended (29-bit) CAN id.
+const DBC_EXTENDED_FLAG: u32 = 0x8000_0000;
+const EXTENDED_ID_MASK: u32 = 0x1FFF_FFFF;
+
+/// Classic CAN frame, the payload is padded with zeros to eight bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CanFrame {
+    pub id: u32,
+    pub extended: bool,
+    pub len: u8,
+    pub data: [u8; 8],
+}
+
+impl CanFrame {
+    pub fn new(id: u32, extended: bool, payload: &[u8]) -> Self {
+        let len = payload.len().min(8);
+        let mut data = [0; 8];
+        data[..len].copy_from_slice(&payload[..len]);
+
+        Self {
+            id,
+            extended,
+            len: len as u8,
+            data,
+        }
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// `@1` in a DBC file, Intel.
+    LittleEndian,
+    /// `@0` in a DBC file, Motorola. The start bit is the most significant one.
+    BigEndian,
+}
+
+/// Part a signal plays in a multiplexed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Multiplex {
+    #[default]
+    None,
+    /// `M` in a DBC file, tells which of the multiplexed signals a frame carries.
+    Multiplexor,
+    /// `mN` in a DBC file, only sent in frames whose multiplexor is `N`.
+    ///
+    /// An extended multiplexor `mNM`, itself multiplexed, is read as `mN`: the
+    /// signals it multiplexes in turn are only told apart by the top one.
+    Multiplexed(u64),
+}
+
+/// Signal definition, an `SG_` line of a DBC file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+    pub name: String,
+    pub start_bit: u8,
+    pub length: u8,
+    pub byte_order: ByteOrder,
+    pub signed: bool,
+    pub factor: f64,
+    pub offset: f64,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub multiplex: Multiplex,
+}
+
+impl Signal {
+    /// Physical value of the signal in `payload`.
+    pub fn decode(&self, payload: &[u8]) -> f64 {
+        let raw = self.raw(payload);
+        let raw = if self.signed && self.length < 64 && raw >> (self.length - 1) & 1 == 1 {
+            raw as i64 - (1i64 << self.length)
+        } else {
+            raw as i64
+        };
+
+        raw as f64 * self.factor + self.offset
+    }
+
+    /// Unscaled bits of the signal in `payload`, e.g. the value of a multiplexor.
+    pub fn raw(&self, payload: &[u8]) -> u64 {
+        (self.bits(payload) >> self.shift()) & self.mask()
+    }
+
+    /// Writes `value` into `payload`, rounded to the signal resolution and
+    /// clamped to what the raw bits can hold.
+    pub fn encode(&self, value: f64, payload: &mut [u8; 8]) {
+        let raw = ((value - self.offset) / self.factor).round();
+        let (low, high) = if self.signed {
+            let half = 2f64.powi(self.length as i32 - 1);
+            (-half, half - 1.0)
+        } else {
+            (0.0, 2f64.powi(self.length as i32) - 1.0)
+        };
+        let raw = (raw.clamp(low, high) as i64 as u64) & self.mask();
+
+        let shift = self.shift();
+        let bits = (self.bits(payload) & !(self.mask() << shift)) | raw << shift;
+        *payload = match self.byte_order {
+            ByteOrder::LittleEndian => bits.to_le_bytes(),
+            ByteOrder::BigEndian => bits.to_be_bytes(),
+        };
+    }
+
+    fn bits(&self, payload: &[u8]) -> u64 {
+        let mut data = [0; 8];
+        let len = payload.len().min(8);
+        data[..len].copy_from_slice(&payload[..len]);
+
+        match self.byte_order {
+            ByteOrder::LittleEndian => u64::from_le_bytes(data),
+            ByteOrder::BigEndian => u64::from_be_bytes(data),
+        }
+    }
+
+    /// Position of the least significant bit in [`Signal::bits`].
+    fn shift(&self) -> u32 {
+        let length = self.length as u32;
+        match self.byte_order {
+            ByteOrder::LittleEndian => self.start_bit as u32,
+            ByteOrder::BigEndian => {
+                // Bits counted from the most significant one of the big-endian word.
+                let msb = (self.start_bit as u32 / 8) * 8 + (7 - self.start_bit as u32 % 8);
+                64u32.saturating_sub(msb + length)
+            }
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.length as u32)
+    }
+}
+
+/// Frame definition, a `BO_` line of a DBC file and its signals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbcMessage {
+    pub id: u32,
+    pub extended: bool,
+    pub name: String,
+    pub size: u8,
+    pub signals: Vec<Signal>,
+}
+
+impl DbcMessage {
+    pub fn matches(&self, frame: &CanFrame) -> bool {
+        self.id == frame.id && self.extended == frame.extended
+    }
+
+    /// The signal telling which multiplexed signals a frame carries.
+    pub fn multiplexor(&self) -> Option<&Signal> {
+        self.signals
+            .iter()
+            .find(|signal| signal.multiplex == Multiplex::Multiplexor)
+    }
+}
+
+/// Messages and signals of a DBC file. Everything but `BO_` and `SG_` is ignored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dbc {
+    pub messages: Vec<DbcMessage>,
+}
+
+impl Dbc {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DbcError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, DbcError> {
+        let mut dbc = Self::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let syntax = |message: &str| DbcError::Syntax {
+                line: index + 1,
+                message: message.to_owned(),
+            };
+
+            if let Some(rest) = line.strip_prefix("BO_ ") {
+                dbc.messages
+                    .push(parse_message(rest).ok_or_else(|| syntax("invalid message"))?);
+            } else if let Some(rest) = line.strip_prefix("SG_ ") {
+                let signal = parse_signal(rest).ok_or_else(|| syntax("invalid signal"))?;
+                dbc.messages
+                    .last_mut()
+                    .ok_or_else(|| syntax("signal outside of a message"))?
+                    .signals
+                    .push(signal);
+            }
+        }
+
+        Ok(dbc)
+    }
+
+    /// Message carrying the signal called `name`.
+    pub fn find_signal(&self, name: &str) -> Option<(&DbcMessage, &Signal)> {
+        self.messages.iter().find_map(|message| {
+            message
+                .signals
+                .iter()
+                .find(|signal| signal.name == name)
+                .map(|signal| (message, signal))
+        })
+    }
+}
+
+/// `256 Speed: 8 ECU`
+fn parse_message(rest: &str) -> Option<DbcMessage> {
+    let (head, tail) = rest.split_once(':')?;
+    let mut head = head.split_whitespace();
+    let id: u32 = head.next()?.parse().ok()?;
+    let name = head.next()?.to_owned();
+    let size = tail.split_whitespace().next()?.parse().ok()?;
+
+    Some(DbcMessage {
+        id: id & EXTENDED_ID_MASK,
+        extended: id & DBC_EXTENDED_FLAG != 0,
+        name,
+        size,
+        signals: Vec::new(),
+    })
+}
+
+/// `VehicleSpeed : 0|16@1+ (0.01,0) [0|655.35] "km/h" Dashboard`, multiplexed
+/// signals have an `M`, `mN` or `mNM` before the colon.
+fn parse_signal(rest: &str) -> Option<Signal> {
+    let (head, tail) = rest.split_once(':')?;
+    let mut head = head.split_whitespace();
+    let name = head.next()?.to_owned();
+    let multiplex = match head.next() {
+        None => Multiplex::None,
+        Some("M") => Multiplex::Multiplexor,
+        Some(value) => {
+            let value = value.strip_prefix('m')?;
+            let value = value.strip_suffix('M').unwrap_or(value);
+            Multiplex::Multiplexed(value.parse().ok()?)
+        }
+    };
+
+    let mut fields = tail.split_whitespace();
+    let (position, format) = fields.next()?.split_once('@')?;
+    let (start_bit, length) = position.split_once('|')?;
+    let (start_bit, length): (u8, u8) = (start_bit.parse().ok()?, length.parse().ok()?);
+    if !(1..=64).contains(&length) || start_bit > 63 {
+        return None;
+    }
+
+    let mut format = format.chars();
+    let byte_order = match format.next()? {
+        '1' => ByteOrder::LittleEndian,
+        '0' => ByteOrder::BigEndian,
+        _ => return None,
+    };
+    let signed = match format.next()? {
+        '+' => false,
+        '-' => true,
+        _ => return None,
+    };
+
+    let (factor, offset) = fields
+        .next()?
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split_once(',')?;
+    let (min, max) = fields
+        .next()?
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .split_once('|')?;
+    let unit = tail.split('"').nth(1).unwrap_or_default().to_owned();
+
+    Some(Signal {
+        name,
+        start_bit,
+        length,
+        byte_order,
+        signed,
+        factor: factor.parse().ok()?,
+        offset: offset.parse().ok()?,
+        min: min.parse().ok()?,
+        max: max.parse().ok()?,
+        unit,
+        multiplex,
+    })
+}
+
+#[derive(Debug)]
+pub enum DbcError {
+    Io(io::Error),
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for DbcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbcError::Io(err) => write!(f, "cannot read the DBC file: {err}"),
+            DbcError::Syntax { line, message } => write!(f, "DBC line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for DbcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DbcError::Io(err) => Some(err),
+            DbcError::Syntax { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for DbcError {
+    fn from(err: io::Error) -> Self {
+        DbcError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DBC: &str = r#"
+VERSION ""
+
+BU_: Engine Dashboard
+
+BO_ 256 Speed: 8 Engine
+ SG_ VehicleSpeed : 0|16@1+ (0.01,0) [0|655.35] "km/h" Dashboard
+ SG_ Gear : 16|4@1+ (1,0) [0|15] "" Dashboard
+
+BO_ 2147484160 Engine: 8 Engine
+ SG_ Page M : 56|8@1+ (1,0) [0|255] "" Dashboard
+ SG_ EngineRPM : 7|16@0+ (0.25,0) [0|16383.75] "rpm" Dashboard
+ SG_ CoolantTemp : 23|8@0+ (1,-40) [-40|215] "degC" Dashboard
+ SG_ Torque m1 : 35|12@0- (0.5,0) [-1024|1023.5] "Nm" Dashboard
+
+CM_ SG_ 256 VehicleSpeed "Vehicle speed over ground";
+"#;
+
+    #[test]
+    fn parses_messages_and_signals() {
+        let dbc = Dbc::parse(DBC).unwrap();
+
+        assert_eq!(dbc.messages.len(), 2);
+        assert_eq!(dbc.messages[1].id, 512);
+        assert!(dbc.messages[1].extended);
+
+        let (message, speed) = dbc.find_signal("VehicleSpeed").unwrap();
+        assert_eq!(message.name, "Speed");
+        assert_eq!(speed.length, 16);
+        assert_eq!(speed.byte_order, ByteOrder::LittleEndian);
+        assert_eq!(speed.factor, 0.01);
+        assert_eq!(speed.unit, "km/h");
+
+        let (engine, torque) = dbc.find_signal("Torque").unwrap();
+        assert!(torque.signed);
+        assert_eq!(torque.byte_order, ByteOrder::BigEndian);
+        assert_eq!(torque.multiplex, Multiplex::Multiplexed(1));
+        assert_eq!(engine.multiplexor().unwrap().name, "Page");
+        assert_eq!(speed.multiplex, Multiplex::None);
+    }
+
+    #[test]
+    fn decodes_both_byte_orders() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let signal = |name| dbc.find_signal(name).unwrap().1;
+
+        // 123.45 km/h = 12345 = 0x3039, little endian.
+        let speed = [0x39, 0x30, 0x03, 0, 0, 0, 0, 0];
+        assert!((signal("VehicleSpeed").decode(&speed) - 123.45).abs() < 1e-9);
+        assert_eq!(signal("Gear").decode(&speed), 3.0);
+
+        // 3000 rpm = 12000 = 0x2EE0 big endian, 90 °C = 130.
+        let engine = [0x2E, 0xE0, 130, 0, 0, 0, 0, 0];
+        assert_eq!(signal("EngineRPM").decode(&engine), 3000.0);
+        assert_eq!(signal("CoolantTemp").decode(&engine), 90.0);
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let mut payload = [0; 8];
+
+        for (name, value) in [
+            ("EngineRPM", 2450.25),
+            ("CoolantTemp", -12.0),
+            ("Torque", -300.5),
+        ] {
+            dbc.find_signal(name).unwrap().1.encode(value, &mut payload);
+        }
+
+        for (name, value) in [
+            ("EngineRPM", 2450.25),
+            ("CoolantTemp", -12.0),
+            ("Torque", -300.5),
+        ] {
+            assert_eq!(dbc.find_signal(name).unwrap().1.decode(&payload), value);
+        }
+    }
+
+    #[test]
+    fn reads_extended_multiplexors_as_multiplexed() {
+        let dbc = Dbc::parse(
+            "BO_ 1 A: 8 X\n SG_ Page M : 0|8@1+ (1,0) [0|255] \"\" X\n\
+             SG_ SubPage m1M : 8|8@1+ (1,0) [0|255] \"\" X\n",
+        )
+        .unwrap();
+
+        let (message, sub_page) = dbc.find_signal("SubPage").unwrap();
+        assert_eq!(sub_page.multiplex, Multiplex::Multiplexed(1));
+        assert_eq!(message.multiplexor().unwrap().name, "Page");
+        assert!(Dbc::parse("BO_ 1 A: 8 X\n SG_ Bad mM : 0|8@1+ (1,0) [0|1] \"\" X\n").is_err());
+    }
+
+    #[test]
+    fn reports_the_faulty_line() {
+        let err =
+            Dbc::parse("BO_ 1 A: 8 X\n SG_ Broken : 0|16@2+ (1,0) [0|1] \"\" X\n").unwrap_err();
+
+        assert!(matches!(err, DbcError::Syntax { line: 2, .. }));
+    }
+}
@@ -0,0 +1,328 @@
+//! Live data from a CAN bus, decoded with the signal definitions of a DBC file.
+
+mod dbc;
+#[cfg(target_os = "linux")]
+mod socket;
+
+pub use dbc::{ByteOrder, CanFrame, Dbc, DbcError, DbcMessage, Multiplex, Signal};
+#[cfg(target_os = "linux")]
+pub use socket::CanSocket;
+
+use crate::log::{Channel, ColumnMap, ReplayError};
+use crate::sample::Sample;
+#[cfg(target_os = "linux")]
+use crate::source::{SourceStatus, VehicleDataSource, Watchdog};
+use std::error::Error;
+use std::fmt;
+use std::io;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+
+/// DBC signals read by default, the same names as in `examples/vehicle.dbc`.
+pub fn default_signals() -> ColumnMap {
+    ColumnMap::empty()
+        .with(Channel::Speed, "VehicleSpeed")
+        .with(Channel::Rpm, "EngineRPM")
+        .with(Channel::Gear, "Gear")
+        .with(Channel::Fuel, "FuelLevel")
+        .with(Channel::CoolantTemp, "CoolantTemp")
+        .with(Channel::OilTemp, "OilTemp")
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    id: u32,
+    extended: bool,
+    signal: Signal,
+    /// Multiplexor of the message and the value it has when the signal is sent.
+    multiplexor: Option<(Signal, u64)>,
+    channel: Channel,
+    scale: f32,
+}
+
+/// Turns CAN frames into a [`Sample`] using the signals mapped to each [`Channel`].
+#[derive(Debug, Clone)]
+pub struct CanDecoder {
+    bindings: Vec<Binding>,
+    sample: Sample,
+}
+
+impl CanDecoder {
+    /// Looks the signals of `signals` up in `dbc`. The speed signal is required,
+    /// the other channels are skipped when the DBC doesn't define them.
+    pub fn new(dbc: &Dbc, signals: &ColumnMap) -> Result<Self, CanError> {
+        let mut bindings = Vec::new();
+
+        for (channel, name, scale) in signals.iter() {
+            if channel == Channel::Time {
+                continue;
+            }
+            match dbc.find_signal(name) {
+                Some((message, signal)) => bindings.push(Binding {
+                    id: message.id,
+                    extended: message.extended,
+                    signal: signal.clone(),
+                    multiplexor: match signal.multiplex {
+                        Multiplex::Multiplexed(value) => {
+                            let multiplexor = message
+                                .multiplexor()
+                                .ok_or_else(|| CanError::MissingMultiplexor(name.to_owned()))?;
+                            Some((multiplexor.clone(), value))
+                        }
+                        _ => None,
+                    },
+                    channel,
+                    scale,
+                }),
+                None if channel == Channel::Speed => {
+                    return Err(CanError::MissingSignal(name.to_owned()))
+                }
+                None => {}
+            }
+        }
+
+        if !bindings
+            .iter()
+            .any(|binding| binding.channel == Channel::Speed)
+        {
+            return Err(CanError::MissingSignal(Channel::Speed.name().to_owned()));
+        }
+
+        Ok(Self {
+            bindings,
+            sample: Sample::default(),
+        })
+    }
+
+    /// Updates the channels carried by `frame`, `false` if none is. Multiplexed
+    /// signals are only read from frames with their multiplexor value.
+    pub fn decode(&mut self, frame: &CanFrame) -> bool {
+        let mut matched = false;
+
+        for binding in &self.bindings {
+            let multiplexed = binding
+                .multiplexor
+                .as_ref()
+                .is_none_or(|(multiplexor, value)| multiplexor.raw(frame.payload()) == *value);
+            if binding.id == frame.id && binding.extended == frame.extended && multiplexed {
+                let value = binding.signal.decode(frame.payload()) as f32 * binding.scale;
+                binding.channel.apply(&mut self.sample, value);
+                matched = true;
+            }
+        }
+
+        matched
+    }
+
+    /// Latest decoded values, channels that haven't been received keep their
+    /// [`Sample::default`] value.
+    pub fn sample(&self) -> &Sample {
+        &self.sample
+    }
+}
+
+/// Source reading a SocketCAN interface, e.g. `vcan0`.
+///
+/// Goes [`SourceStatus::TimedOut`] when none of the mapped frames arrived for
+/// longer than the timeout, and [`SourceStatus::Failed`] while the socket can't
+/// be read.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct CanSource {
+    socket: CanSocket,
+    decoder: CanDecoder,
+    watchdog: Watchdog,
+    error: Option<io::Error>,
+}
+
+#[cfg(target_os = "linux")]
+impl CanSource {
+    pub fn open(interface: &str, decoder: CanDecoder) -> Result<Self, CanError> {
+        Ok(Self {
+            socket: CanSocket::open(interface)?,
+            decoder,
+            watchdog: Watchdog::default(),
+            error: None,
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Watchdog::new(timeout);
+        self
+    }
+
+    /// Why the socket couldn't be read at the last poll, if it couldn't.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl VehicleDataSource for CanSource {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        let mut received = false;
+
+        self.error = loop {
+            match self.socket.read() {
+                Ok(Some(frame)) => received |= self.decoder.decode(&frame),
+                Ok(None) => break None,
+                Err(err) => break Some(err),
+            }
+        };
+        if !received {
+            return None;
+        }
+
+        self.watchdog.feed(elapsed);
+        Some(Sample {
+            time: elapsed,
+            ..*self.decoder.sample()
+        })
+    }
+
+    fn status(&self, elapsed: Duration) -> SourceStatus {
+        match self.error {
+            Some(_) => SourceStatus::Failed,
+            None => self.watchdog.status(elapsed),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CanError {
+    Io(io::Error),
+    Dbc(DbcError),
+    /// The signal is not defined in the DBC file.
+    MissingSignal(String),
+    /// The signal is multiplexed but its message has no multiplexor.
+    MissingMultiplexor(String),
+    InvalidSignalMap(ReplayError),
+    /// SocketCAN is only available on Linux.
+    Unsupported,
+}
+
+impl fmt::Display for CanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanError::Io(err) => write!(f, "CAN socket: {err}"),
+            CanError::Dbc(err) => err.fmt(f),
+            CanError::MissingSignal(name) => write!(f, "signal `{name}` is not in the DBC file"),
+            CanError::MissingMultiplexor(name) => {
+                write!(
+                    f,
+                    "signal `{name}` is multiplexed but its message has no multiplexor"
+                )
+            }
+            CanError::InvalidSignalMap(err) => err.fmt(f),
+            CanError::Unsupported => f.write_str("SocketCAN is only available on Linux"),
+        }
+    }
+}
+
+impl Error for CanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CanError::Io(err) => Some(err),
+            CanError::Dbc(err) => Some(err),
+            CanError::InvalidSignalMap(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CanError {
+    fn from(err: io::Error) -> Self {
+        CanError::Io(err)
+    }
+}
+
+impl From<DbcError> for CanError {
+    fn from(err: DbcError) -> Self {
+        CanError::Dbc(err)
+    }
+}
+
+impl From<ReplayError> for CanError {
+    fn from(err: ReplayError) -> Self {
+        CanError::InvalidSignalMap(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DBC: &str = include_str!("../../examples/vehicle.dbc");
+
+    #[test]
+    fn decodes_mapped_signals() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let mut decoder = CanDecoder::new(&dbc, &default_signals()).unwrap();
+
+        let (message, speed) = dbc.find_signal("VehicleSpeed").unwrap();
+        let mut payload = [0; 8];
+        speed.encode(88.5, &mut payload);
+        dbc.find_signal("Gear").unwrap().1.encode(4.0, &mut payload);
+
+        assert!(decoder.decode(&CanFrame::new(message.id, message.extended, &payload)));
+        assert!(!decoder.decode(&CanFrame::new(0x7FF, false, &[0; 8])));
+        assert!((decoder.sample().speed - 88.5).abs() < 0.01);
+        assert_eq!(decoder.sample().gear, 4);
+        assert_eq!(decoder.sample().rpm, Sample::default().rpm);
+    }
+
+    #[test]
+    fn scales_and_renames_signals() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let signals = default_signals()
+            .with_pairs("speed=VehicleSpeed*0.5")
+            .unwrap();
+        let mut decoder = CanDecoder::new(&dbc, &signals).unwrap();
+
+        let (message, speed) = dbc.find_signal("VehicleSpeed").unwrap();
+        let mut payload = [0; 8];
+        speed.encode(100.0, &mut payload);
+        decoder.decode(&CanFrame::new(message.id, message.extended, &payload));
+
+        assert!((decoder.sample().speed - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn requires_the_speed_signal() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let signals = default_signals().with(Channel::Speed, "WheelSpeed");
+
+        assert!(matches!(
+            CanDecoder::new(&dbc, &signals),
+            Err(CanError::MissingSignal(name)) if name == "WheelSpeed"
+        ));
+    }
+
+    #[test]
+    fn reads_multiplexed_signals_of_their_page_only() {
+        let dbc = Dbc::parse(
+            r#"
+BO_ 256 Dashboard: 8 Engine
+ SG_ Page M : 0|8@1+ (1,0) [0|255] "" Dashboard
+ SG_ VehicleSpeed m0 : 8|16@1+ (0.01,0) [0|655.35] "km/h" Dashboard
+ SG_ OilTemp m1 : 8|8@1+ (1,-40) [-40|215] "degC" Dashboard
+"#,
+        )
+        .unwrap();
+        let mut decoder = CanDecoder::new(&dbc, &default_signals()).unwrap();
+
+        assert!(decoder.decode(&CanFrame::new(256, false, &[0, 0x39, 0x30])));
+        assert!(decoder.decode(&CanFrame::new(256, false, &[1, 130, 0])));
+        assert!(!decoder.decode(&CanFrame::new(256, false, &[2, 0, 0])));
+        assert!((decoder.sample().speed - 123.45).abs() < 0.01);
+        assert_eq!(decoder.sample().oil_temp, 90.0);
+
+        let orphan =
+            Dbc::parse("BO_ 1 A: 8 X\n SG_ VehicleSpeed m3 : 0|8@1+ (1,0) [0|255] \"\" X\n")
+                .unwrap();
+        assert!(matches!(
+            CanDecoder::new(&orphan, &default_signals()),
+            Err(CanError::MissingMultiplexor(name)) if name == "VehicleSpeed"
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use super::dbc::CanFrame;
+use std::ffi::CString;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+/// Non-blocking raw SocketCAN socket bound to one interface, e.g. `can0` or `vcan0`.
+#[derive(Debug)]
+pub struct CanSocket {
+    fd: OwnedFd,
+}
+
+impl CanSocket {
+    pub fn open(interface: &str) -> io::Result<Self> {
+        let name = CString::new(interface)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid interface name"))?;
+
+        // SAFETY: plain FFI calls, the descriptor is owned as soon as it is valid.
+        unsafe {
+            let index = libc::if_nametoindex(name.as_ptr());
+            if index == 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let fd = libc::socket(
+                libc::PF_CAN,
+                libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                libc::CAN_RAW,
+            );
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let fd = OwnedFd::from_raw_fd(fd);
+
+            let mut address: libc::sockaddr_can = mem::zeroed();
+            address.can_family = libc::AF_CAN as libc::sa_family_t;
+            address.can_ifindex = index as libc::c_int;
+            let bound = libc::bind(
+                fd.as_raw_fd(),
+                &address as *const libc::sockaddr_can as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_can>() as libc::socklen_t,
+            );
+            if bound < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self { fd })
+        }
+    }
+
+    /// Next queued data frame, `None` when the queue is empty. Remote and error
+    /// frames are skipped.
+    pub fn read(&self) -> io::Result<Option<CanFrame>> {
+        loop {
+            // SAFETY: `can_frame` is plain old data and the kernel writes at most its size.
+            let (read, frame) = unsafe {
+                let mut frame: libc::can_frame = mem::zeroed();
+                let read = libc::read(
+                    self.fd.as_raw_fd(),
+                    &mut frame as *mut libc::can_frame as *mut libc::c_void,
+                    mem::size_of::<libc::can_frame>(),
+                );
+                (read, frame)
+            };
+
+            if read < 0 {
+                let err = io::Error::last_os_error();
+                return match err.kind() {
+                    io::ErrorKind::WouldBlock => Ok(None),
+                    io::ErrorKind::Interrupted => continue,
+                    _ => Err(err),
+                };
+            }
+            if (read as usize) < mem::size_of::<libc::can_frame>() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "short CAN frame",
+                ));
+            }
+            if frame.can_id & (libc::CAN_RTR_FLAG | libc::CAN_ERR_FLAG) != 0 {
+                continue;
+            }
+
+            let extended = frame.can_id & libc::CAN_EFF_FLAG != 0;
+            let id = if extended {
+                frame.can_id & libc::CAN_EFF_MASK
+            } else {
+                frame.can_id & libc::CAN_SFF_MASK
+            };
+            let len = (frame.can_dlc as usize).min(8);
+
+            return Ok(Some(CanFrame::new(id, extended, &frame.data[..len])));
+        }
+    }
+
+    pub fn write(&self, frame: &CanFrame) -> io::Result<()> {
+        // SAFETY: see `read`, the padding is zeroed.
+        let written = unsafe {
+            let mut raw: libc::can_frame = mem::zeroed();
+            raw.can_id = if frame.extended {
+                frame.id & libc::CAN_EFF_MASK | libc::CAN_EFF_FLAG
+            } else {
+                frame.id & libc::CAN_SFF_MASK
+            };
+            raw.can_dlc = frame.len;
+            raw.data = frame.data;
+
+            libc::write(
+                self.fd.as_raw_fd(),
+                &raw as *const libc::can_frame as *const libc::c_void,
+                mem::size_of::<libc::can_frame>(),
+            )
+        };
+
+        if written < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
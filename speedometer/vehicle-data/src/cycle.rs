@@ -0,0 +1,238 @@
+use crate::simulated::SpeedProfile;
+
+/// Elementary urban cycle of NEDC (ECE-15): three stop-and-go runs up to 50 km/h in 195 s.
+const URBAN: &[(f32, f32)] = &[
+    (0.0, 0.0),
+    (11.0, 0.0),
+    (15.0, 15.0),
+    (23.0, 15.0),
+    (28.0, 0.0),
+    (49.0, 0.0),
+    (61.0, 32.0),
+    (85.0, 32.0),
+    (96.0, 0.0),
+    (117.0, 0.0),
+    (143.0, 50.0),
+    (155.0, 50.0),
+    (163.0, 35.0),
+    (176.0, 35.0),
+    (188.0, 0.0),
+    (195.0, 0.0),
+];
+
+/// Extra-urban part of NEDC (EUDC): 400 s with a stretch at 120 km/h.
+const HIGHWAY: &[(f32, f32)] = &[
+    (0.0, 0.0),
+    (20.0, 0.0),
+    (41.0, 70.0),
+    (91.0, 70.0),
+    (99.0, 50.0),
+    (168.0, 50.0),
+    (181.0, 70.0),
+    (231.0, 70.0),
+    (266.0, 100.0),
+    (296.0, 100.0),
+    (316.0, 120.0),
+    (326.0, 120.0),
+    (360.0, 0.0),
+    (400.0, 0.0),
+];
+
+/// WLTC class 3, the cycle of WLTP for most cars, cut down to its main speed
+/// changes. The low, medium, high and extra-high phases keep their official
+/// lengths of 589, 433, 455 and 323 s and their top speeds.
+const WLTC_CLASS_3: &[(f32, f32)] = &[
+    // Low.
+    (0.0, 0.0),
+    (11.0, 0.0),
+    (25.0, 18.0),
+    (50.0, 25.0),
+    (65.0, 0.0),
+    (78.0, 0.0),
+    (100.0, 32.0),
+    (140.0, 40.0),
+    (175.0, 20.0),
+    (195.0, 0.0),
+    (205.0, 0.0),
+    (240.0, 45.0),
+    (275.0, 35.0),
+    (300.0, 0.0),
+    (330.0, 0.0),
+    (375.0, 56.5),
+    (410.0, 45.0),
+    (450.0, 50.0),
+    (490.0, 20.0),
+    (520.0, 30.0),
+    (560.0, 0.0),
+    (589.0, 0.0),
+    // Medium.
+    (600.0, 0.0),
+    (640.0, 50.0),
+    (700.0, 60.0),
+    (740.0, 35.0),
+    (770.0, 0.0),
+    (790.0, 0.0),
+    (840.0, 76.6),
+    (900.0, 60.0),
+    (950.0, 70.0),
+    (990.0, 30.0),
+    (1010.0, 0.0),
+    (1022.0, 0.0),
+    // High.
+    (1035.0, 0.0),
+    (1075.0, 70.0),
+    (1140.0, 85.0),
+    (1190.0, 60.0),
+    (1240.0, 97.4),
+    (1300.0, 80.0),
+    (1360.0, 90.0),
+    (1420.0, 50.0),
+    (1460.0, 0.0),
+    (1477.0, 0.0),
+    // Extra high.
+    (1490.0, 0.0),
+    (1530.0, 80.0),
+    (1580.0, 110.0),
+    (1640.0, 100.0),
+    (1700.0, 131.3),
+    (1740.0, 120.0),
+    (1780.0, 40.0),
+    (1790.0, 0.0),
+    (1800.0, 0.0),
+];
+
+/// Looped speed trace, linear between its points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriveCycle {
+    /// `(seconds, km/h)` pairs with increasing times, starting at zero.
+    points: Vec<(f32, f32)>,
+}
+
+impl DriveCycle {
+    /// Trace through `(seconds, km/h)` points. Times must increase; the cycle
+    /// restarts after the last one.
+    pub fn new(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let mut points: Vec<_> = points.into_iter().collect();
+        if points.first().is_none_or(|&(time, _)| time > 0.0) {
+            let speed = points.first().map_or(0.0, |&(_, speed)| speed);
+            points.insert(0, (0.0, speed));
+        }
+
+        Self { points }
+    }
+
+    /// NEDC-like city driving.
+    pub fn urban() -> Self {
+        Self::new(URBAN.iter().copied())
+    }
+
+    /// NEDC-like extra-urban driving.
+    pub fn highway() -> Self {
+        Self::new(HIGHWAY.iter().copied())
+    }
+
+    /// Four urban runs followed by a highway one, like the full NEDC.
+    pub fn combined() -> Self {
+        let mut cycle = Self::new(std::iter::empty());
+        for _ in 0..4 {
+            cycle = cycle.then(&Self::urban());
+        }
+        cycle.then(&Self::highway())
+    }
+
+    /// WLTP-like driving, from city streets to the motorway in 30 minutes.
+    pub fn wltp() -> Self {
+        Self::new(WLTC_CLASS_3.iter().copied())
+    }
+
+    /// Appends `next`, shifted to start when this one ends.
+    pub fn then(mut self, next: &DriveCycle) -> Self {
+        let offset = self.duration();
+        self.points.extend(
+            next.points
+                .iter()
+                .skip(1)
+                .map(|&(time, speed)| (offset + time, speed)),
+        );
+        self
+    }
+
+    /// Length of one loop in seconds.
+    pub fn duration(&self) -> f32 {
+        self.points.last().map_or(0.0, |&(time, _)| time)
+    }
+
+    /// Speed `time` seconds into the cycle, looping after [`Self::duration`].
+    pub fn speed(&self, time: f32) -> f32 {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return self.points[0].1;
+        }
+
+        let time = time.rem_euclid(duration);
+        let next = self.points.partition_point(|&(t, _)| t <= time);
+        if next == self.points.len() {
+            return self.points[next - 1].1;
+        }
+
+        let (t0, v0) = self.points[next - 1];
+        let (t1, v1) = self.points[next];
+        v0 + (v1 - v0) * (time - t0) / (t1 - t0)
+    }
+}
+
+impl SpeedProfile for DriveCycle {
+    fn speed_at(&mut self, time: f32) -> f32 {
+        self.speed(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_between_points() {
+        let cycle = DriveCycle::urban();
+
+        assert_eq!(cycle.duration(), 195.0);
+        assert_eq!(cycle.speed(5.0), 0.0);
+        assert_eq!(cycle.speed(13.0), 7.5);
+        assert_eq!(cycle.speed(20.0), 15.0);
+    }
+
+    #[test]
+    fn loops_after_the_end() {
+        let cycle = DriveCycle::highway();
+
+        assert_eq!(cycle.speed(400.0 + 320.0), 120.0);
+    }
+
+    #[test]
+    fn combined_chains_the_parts() {
+        let cycle = DriveCycle::combined();
+
+        assert_eq!(cycle.duration(), 4.0 * 195.0 + 400.0);
+        assert_eq!(cycle.speed(195.0 + 20.0), 15.0);
+        assert_eq!(cycle.speed(4.0 * 195.0 + 320.0), 120.0);
+    }
+
+    #[test]
+    fn wltp_phases_keep_their_top_speeds() {
+        let cycle = DriveCycle::wltp();
+        let top = |from: u16, to: u16| {
+            (from..=to)
+                .map(|time| cycle.speed(time as f32))
+                .fold(0.0, f32::max)
+        };
+
+        assert_eq!(cycle.duration(), 1800.0);
+        assert_eq!(top(0, 589), 56.5);
+        assert_eq!(top(589, 1022), 76.6);
+        assert_eq!(top(1022, 1477), 97.4);
+        assert_eq!(top(1477, 1800), 131.3);
+        for end in [589.0, 1022.0, 1477.0] {
+            assert_eq!(cycle.speed(end), 0.0);
+        }
+    }
+}
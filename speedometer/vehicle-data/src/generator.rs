@@ -0,0 +1,118 @@
+use crate::cycle::DriveCycle;
+use crate::random_walk::RandomWalk;
+use crate::simulated::Simulated;
+use crate::source::VehicleDataSource;
+use crate::sweep::SineSweep;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Top speed of the sweep and the random walk, within every demo dial.
+const SIMULATED_MAX_SPEED: f32 = 180.0;
+const RANDOM_WALK_SEED: u64 = 0x5EED;
+
+/// Built-in simulated sources, picked with `--source <name>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Generator {
+    #[default]
+    Urban,
+    Highway,
+    Combined,
+    Wltp,
+    Sweep,
+    RandomWalk,
+}
+
+impl Generator {
+    pub const ALL: [Generator; 6] = [
+        Generator::Urban,
+        Generator::Highway,
+        Generator::Combined,
+        Generator::Wltp,
+        Generator::Sweep,
+        Generator::RandomWalk,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Generator::Urban => "urban",
+            Generator::Highway => "highway",
+            Generator::Combined => "combined",
+            Generator::Wltp => "wltp",
+            Generator::Sweep => "sweep",
+            Generator::RandomWalk => "random",
+        }
+    }
+
+    pub fn source(self) -> Box<dyn VehicleDataSource> {
+        match self {
+            Generator::Urban => Box::new(Simulated::new(DriveCycle::urban())),
+            Generator::Highway => Box::new(Simulated::new(DriveCycle::highway())),
+            Generator::Combined => Box::new(Simulated::new(DriveCycle::combined())),
+            Generator::Wltp => Box::new(Simulated::new(DriveCycle::wltp())),
+            Generator::Sweep => Box::new(Simulated::new(SineSweep::new(0.0, SIMULATED_MAX_SPEED))),
+            Generator::RandomWalk => Box::new(Simulated::new(RandomWalk::new(
+                SIMULATED_MAX_SPEED,
+                RANDOM_WALK_SEED,
+            ))),
+        }
+    }
+}
+
+impl FromStr for Generator {
+    type Err = UnknownGenerator;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|generator| generator.name() == name)
+            .ok_or_else(|| UnknownGenerator(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Name passed to `--source` that matches no [`Generator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGenerator(pub String);
+
+impl fmt::Display for UnknownGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown source `{}`, expected one of:", self.0)?;
+        for generator in Generator::ALL {
+            write!(f, " {generator}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnknownGenerator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("sweep".parse(), Ok(Generator::Sweep));
+        assert_eq!("random".parse(), Ok(Generator::RandomWalk));
+
+        for generator in Generator::ALL {
+            assert_eq!(generator.name().parse(), Ok(generator));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let err = "nurburgring".parse::<Generator>().unwrap_err();
+
+        assert_eq!(err, UnknownGenerator("nurburgring".into()));
+        assert!(err
+            .to_string()
+            .contains("urban highway combined wltp sweep random"));
+    }
+}
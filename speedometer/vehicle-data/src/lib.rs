@@ -0,0 +1,41 @@
+//! Vehicle data feeding the egui, iced and Slint dashboards.
+//!
+//! A [`VehicleDataSource`] is polled by the dashboard with the time elapsed since it
+//! started and hands back the newest [`Sample`]. [`source_from_args`] picks one of
+//! the simulated sources, a recorded [`Log`], a CAN bus ([`can`]), an OBD-II
+//! adapter ([`obd`]), a GPS receiver ([`nmea`]) or UDP telemetry from a game or
+//! simulator ([`udp`]) from the command line.
+
+mod args;
+pub mod can;
+mod cycle;
+mod generator;
+mod log;
+mod model;
+pub mod nmea;
+pub mod obd;
+mod random_walk;
+mod replay;
+mod sample;
+#[cfg(unix)]
+mod serial;
+mod simulated;
+mod source;
+mod sweep;
+pub mod udp;
+
+pub use args::{source_from_args, ArgsError, SourceArgs};
+pub use cycle::DriveCycle;
+pub use generator::{Generator, UnknownGenerator};
+pub use log::{Channel, ColumnMap, Log, ReplayError};
+pub use model::VehicleModel;
+pub use random_walk::RandomWalk;
+pub use replay::{Replay, MAX_PLAYBACK_RATE, MIN_PLAYBACK_RATE};
+pub use sample::Sample;
+#[cfg(unix)]
+pub use serial::{open_pty, SerialPort};
+pub use simulated::{Simulated, SpeedProfile, SAMPLE_PERIOD};
+pub use source::{
+    Playback, SourceStatus, VehicleDataSource, Watchdog, DEFAULT_TIMEOUT, PLAYBACK_RATES,
+};
+pub use sweep::SineSweep;
@@ -0,0 +1,654 @@
+use crate::sample::Sample;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Quantity a log column can feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Timestamp in seconds, the only required column.
+    Time,
+    Speed,
+    Rpm,
+    Gear,
+    Fuel,
+    CoolantTemp,
+    OilTemp,
+    /// Course over ground in degrees.
+    Heading,
+    /// Pedal positions from `0` to `1`.
+    Throttle,
+    Brake,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 10] = [
+        Channel::Time,
+        Channel::Speed,
+        Channel::Rpm,
+        Channel::Gear,
+        Channel::Fuel,
+        Channel::CoolantTemp,
+        Channel::OilTemp,
+        Channel::Heading,
+        Channel::Throttle,
+        Channel::Brake,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Time => "time",
+            Channel::Speed => "speed",
+            Channel::Rpm => "rpm",
+            Channel::Gear => "gear",
+            Channel::Fuel => "fuel",
+            Channel::CoolantTemp => "coolant_temp",
+            Channel::OilTemp => "oil_temp",
+            Channel::Heading => "heading",
+            Channel::Throttle => "throttle",
+            Channel::Brake => "brake",
+        }
+    }
+
+    /// Stores `value` in the matching field of `sample`, the time is left alone.
+    pub fn apply(self, sample: &mut Sample, value: f32) {
+        match self {
+            Channel::Time => {}
+            Channel::Speed => sample.speed = value,
+            Channel::Rpm => sample.rpm = value,
+            Channel::Gear => sample.gear = value.round().clamp(0.0, u8::MAX as f32) as u8,
+            Channel::Fuel => sample.fuel = value,
+            Channel::CoolantTemp => sample.coolant_temp = value,
+            Channel::OilTemp => sample.oil_temp = value,
+            Channel::Heading => sample.heading = Some(value.rem_euclid(360.0)),
+            Channel::Throttle => sample.throttle = Some(value.clamp(0.0, 1.0)),
+            Channel::Brake => sample.brake = Some(value.clamp(0.0, 1.0)),
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl FromStr for Channel {
+    type Err = ReplayError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.name() == name)
+            .ok_or_else(|| ReplayError::UnknownChannel(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Mapping {
+    column: String,
+    channel: Channel,
+    scale: f32,
+}
+
+/// Which log column feeds which [`Channel`].
+///
+/// Parses from `channel=column` pairs separated by commas, where the column can
+/// be followed by `*factor`, e.g. `time=timestamp_ms*0.001,speed=vss`. Channels
+/// that are not listed keep their default column, named after the channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMap {
+    mappings: Vec<Mapping>,
+}
+
+impl ColumnMap {
+    /// Map without any column, not even the time.
+    pub fn empty() -> Self {
+        Self {
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Reads `channel` from `column` as is.
+    pub fn with(self, channel: Channel, column: impl Into<String>) -> Self {
+        self.with_scaled(channel, column, 1.0)
+    }
+
+    /// Reads `channel` from `column` multiplied by `scale`, e.g. `0.01` for a fuel
+    /// level in percent.
+    pub fn with_scaled(mut self, channel: Channel, column: impl Into<String>, scale: f32) -> Self {
+        self.mappings.retain(|mapping| mapping.channel != channel);
+        self.mappings.push(Mapping {
+            column: column.into(),
+            channel,
+            scale,
+        });
+        self
+    }
+
+    /// `(channel, column, scale)` of every mapped channel.
+    pub fn iter(&self) -> impl Iterator<Item = (Channel, &str, f32)> {
+        self.mappings
+            .iter()
+            .map(|mapping| (mapping.channel, mapping.column.as_str(), mapping.scale))
+    }
+
+    pub fn column(&self, channel: Channel) -> Option<&str> {
+        self.mapping(channel).map(|mapping| mapping.column.as_str())
+    }
+
+    fn mapping(&self, channel: Channel) -> Option<&Mapping> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.channel == channel)
+    }
+}
+
+impl Default for ColumnMap {
+    fn default() -> Self {
+        Channel::ALL
+            .into_iter()
+            .fold(Self::empty(), |map, channel| {
+                map.with(channel, channel.name())
+            })
+    }
+}
+
+impl FromStr for ColumnMap {
+    type Err = ReplayError;
+
+    fn from_str(pairs: &str) -> Result<Self, Self::Err> {
+        Self::default().with_pairs(pairs)
+    }
+}
+
+impl ColumnMap {
+    /// Overrides the channels listed in `channel=column[*factor]` pairs, see [`ColumnMap`].
+    pub fn with_pairs(self, pairs: &str) -> Result<Self, ReplayError> {
+        let invalid = || ReplayError::InvalidColumnMap(pairs.to_owned());
+        let mut map = self;
+
+        for pair in pairs
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (channel, column) = pair.split_once('=').ok_or_else(invalid)?;
+            let channel = channel.trim().parse()?;
+            let (column, scale) = match column.split_once('*') {
+                Some((column, scale)) => (
+                    column,
+                    scale
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|scale: &f32| scale.is_finite())
+                        .ok_or_else(invalid)?,
+                ),
+                None => (column, 1.0),
+            };
+
+            map = map.with_scaled(channel, column.trim(), scale);
+        }
+
+        Ok(map)
+    }
+}
+
+/// Recorded drive, one time series per channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Log {
+    /// `(seconds, value)` points sorted by time, indexed by [`Channel`].
+    series: [Vec<(f32, f32)>; Channel::ALL.len()],
+    duration: f32,
+}
+
+impl Log {
+    /// Reads a CSV log, or a JSON one when the extension is `.json`, `.jsonl` or `.ndjson`.
+    pub fn open(path: impl AsRef<Path>, columns: &ColumnMap) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json" | "jsonl" | "ndjson") => Self::from_json(reader, columns),
+            _ => Self::from_csv(reader, columns),
+        }
+    }
+
+    /// Reads a CSV log with a header row. Empty cells are treated as missing samples.
+    pub fn from_csv(reader: impl Read, columns: &ColumnMap) -> Result<Self, ReplayError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers = reader.headers()?.clone();
+
+        let mut fields = Vec::new();
+        for channel in Channel::ALL {
+            let Some(mapping) = columns.mapping(channel) else {
+                continue;
+            };
+            match headers.iter().position(|header| header == mapping.column) {
+                Some(index) => fields.push((index, mapping)),
+                None if channel == Channel::Time => {
+                    return Err(ReplayError::MissingColumn(mapping.column.clone()))
+                }
+                None => {}
+            }
+        }
+
+        let mut builder = LogBuilder::default();
+        for (row, record) in reader.records().enumerate() {
+            let record = record?;
+            let values = fields.iter().map(|&(index, mapping)| {
+                let cell = record.get(index).unwrap_or_default();
+                let value = match cell {
+                    "" => None,
+                    cell => Some(
+                        cell.parse::<f32>()
+                            .ok()
+                            .filter(|value| value.is_finite())
+                            .ok_or_else(|| ReplayError::InvalidValue {
+                                row: row + 1,
+                                column: mapping.column.clone(),
+                                value: cell.to_owned(),
+                            })?,
+                    ),
+                };
+                Ok((mapping, value))
+            });
+
+            builder.push(row + 1, values)?;
+        }
+
+        builder.build()
+    }
+
+    /// Reads a JSON array of objects, or one object per line, keyed by column name.
+    pub fn from_json(mut reader: impl Read, columns: &ColumnMap) -> Result<Self, ReplayError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+
+        let records: Vec<serde_json::Value> = if text.trim_start().starts_with('[') {
+            serde_json::from_str(&text)?
+        } else {
+            serde_json::Deserializer::from_str(&text)
+                .into_iter()
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut builder = LogBuilder::default();
+        for (row, record) in records.iter().enumerate() {
+            let values = Channel::ALL
+                .into_iter()
+                .filter_map(|channel| columns.mapping(channel))
+                .map(|mapping| {
+                    let value = match record.get(&mapping.column) {
+                        None | Some(serde_json::Value::Null) => None,
+                        Some(value) => {
+                            Some(json_number(value).ok_or_else(|| ReplayError::InvalidValue {
+                                row: row + 1,
+                                column: mapping.column.clone(),
+                                value: value.to_string(),
+                            })?)
+                        }
+                    };
+                    Ok((mapping, value))
+                });
+
+            builder.push(row + 1, values)?;
+        }
+
+        builder.build()
+    }
+
+    /// Length of the recording in seconds.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    pub fn has(&self, channel: Channel) -> bool {
+        !self.series[channel.index()].is_empty()
+    }
+
+    /// Value of `channel` at `time` seconds, linear between samples and held
+    /// before the first and after the last one.
+    pub fn value(&self, channel: Channel, time: f32) -> Option<f32> {
+        let series = &self.series[channel.index()];
+        let next = series.partition_point(|&(t, _)| t <= time);
+
+        match (next.checked_sub(1).map(|i| series[i]), series.get(next)) {
+            (None, None) => None,
+            (Some((_, value)), None) | (None, Some(&(_, value))) => Some(value),
+            (Some((t0, v0)), Some(&(t1, v1))) => Some(v0 + (v1 - v0) * (time - t0) / (t1 - t0)),
+        }
+    }
+
+    /// Value of `channel` at `time` seconds, held until the next sample.
+    pub fn step_value(&self, channel: Channel, time: f32) -> Option<f32> {
+        let series = &self.series[channel.index()];
+        let next = series.partition_point(|&(t, _)| t <= time);
+
+        series.get(next.saturating_sub(1)).map(|&(_, value)| value)
+    }
+
+    /// Vehicle state `time` seconds into the recording. Channels missing from
+    /// the log keep their [`Sample::default`] value.
+    pub fn sample_at(&self, time: f32) -> Sample {
+        let mut sample = Sample {
+            time: Duration::try_from_secs_f32(time.max(0.0)).unwrap_or(Duration::MAX),
+            ..Sample::default()
+        };
+
+        for channel in Channel::ALL {
+            let value = match channel {
+                Channel::Gear | Channel::Heading => self.step_value(channel, time),
+                _ => self.value(channel, time),
+            };
+            if let Some(value) = value {
+                channel.apply(&mut sample, value);
+            }
+        }
+
+        sample
+    }
+}
+
+#[derive(Default)]
+struct LogBuilder {
+    /// Raw timestamps, shifted to start at zero in [`LogBuilder::build`].
+    series: [Vec<(f32, f32)>; Channel::ALL.len()],
+}
+
+impl LogBuilder {
+    fn push<'a>(
+        &mut self,
+        row: usize,
+        values: impl Iterator<Item = Result<(&'a Mapping, Option<f32>), ReplayError>>,
+    ) -> Result<(), ReplayError> {
+        let values = values.collect::<Result<Vec<_>, _>>()?;
+        // Finite cells can still overflow once scaled.
+        if let Some((mapping, value)) = values.iter().find_map(|&(mapping, value)| {
+            value
+                .filter(|value| !(value * mapping.scale).is_finite())
+                .map(|value| (mapping, value))
+        }) {
+            return Err(ReplayError::InvalidValue {
+                row,
+                column: mapping.column.clone(),
+                value: value.to_string(),
+            });
+        }
+        let time = values
+            .iter()
+            .find(|(mapping, _)| mapping.channel == Channel::Time)
+            .and_then(|&(mapping, value)| value.map(|value| value * mapping.scale))
+            .ok_or(ReplayError::MissingTime { row })?;
+
+        for (mapping, value) in values {
+            if let (Some(value), false) = (value, mapping.channel == Channel::Time) {
+                self.series[mapping.channel.index()].push((time, value * mapping.scale));
+            }
+        }
+        self.series[Channel::Time.index()].push((time, time));
+
+        Ok(())
+    }
+
+    fn build(mut self) -> Result<Log, ReplayError> {
+        let times = &self.series[Channel::Time.index()];
+        let start = times.iter().map(|&(time, _)| time).reduce(f32::min);
+        let end = times.iter().map(|&(time, _)| time).reduce(f32::max);
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(ReplayError::Empty);
+        };
+        // Finite timestamps can still be too far apart to play back.
+        let duration = end - start;
+        if Duration::try_from_secs_f32(duration).is_err() {
+            return Err(ReplayError::TooLong(duration));
+        }
+
+        for series in &mut self.series {
+            for point in series.iter_mut() {
+                point.0 -= start;
+            }
+            series.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+
+        Ok(Log {
+            series: self.series,
+            duration,
+        })
+    }
+}
+
+fn json_number(value: &serde_json::Value) -> Option<f32> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64().map(|number| number as f32),
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        serde_json::Value::Bool(flag) => Some(f32::from(u8::from(*flag))),
+        _ => None,
+    }
+    .filter(|number| number.is_finite())
+}
+
+/// Failure to read or parse a log.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    /// The log has no column for the time.
+    MissingColumn(String),
+    /// A row, counted from one after the header, has no timestamp.
+    MissingTime {
+        row: usize,
+    },
+    InvalidValue {
+        row: usize,
+        column: String,
+        value: String,
+    },
+    UnknownChannel(String),
+    InvalidColumnMap(String),
+    Empty,
+    /// The timestamps span more seconds than a [`Duration`] holds.
+    TooLong(f32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "cannot read the log: {err}"),
+            ReplayError::Csv(err) => write!(f, "invalid CSV log: {err}"),
+            ReplayError::Json(err) => write!(f, "invalid JSON log: {err}"),
+            ReplayError::MissingColumn(column) => write!(f, "the log has no `{column}` column"),
+            ReplayError::MissingTime { row } => write!(f, "row {row} has no timestamp"),
+            ReplayError::InvalidValue { row, column, value } => {
+                write!(
+                    f,
+                    "row {row}: `{value}` in `{column}` is not a finite number"
+                )
+            }
+            ReplayError::UnknownChannel(name) => {
+                write!(f, "unknown channel `{name}`, expected one of:")?;
+                for channel in Channel::ALL {
+                    write!(f, " {channel}")?;
+                }
+                Ok(())
+            }
+            ReplayError::InvalidColumnMap(map) => write!(
+                f,
+                "invalid column map `{map}`, expected e.g. `time=timestamp_ms*0.001,speed=vss`"
+            ),
+            ReplayError::Empty => write!(f, "the log has no samples"),
+            ReplayError::TooLong(duration) => {
+                write!(f, "the log spans {duration} seconds, too long to replay")
+            }
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(err) => Some(err),
+            ReplayError::Csv(err) => Some(err),
+            ReplayError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+impl From<csv::Error> for ReplayError {
+    fn from(err: csv::Error) -> Self {
+        ReplayError::Csv(err)
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(err: serde_json::Error) -> Self {
+        ReplayError::Json(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+ts_ms, vss, rpm, gear, fuel_pct
+1000, 0, 800, 0, 50
+2000, 10, 1600, 1,
+3000, 30, 2400, 2, 48
+";
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn columns() -> ColumnMap {
+        "time=ts_ms*0.001, speed=vss, fuel=fuel_pct*0.01"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_the_column_map() {
+        let map = columns();
+
+        assert_eq!(map.column(Channel::Time), Some("ts_ms"));
+        assert_eq!(map.column(Channel::Speed), Some("vss"));
+        assert_eq!(map.column(Channel::Rpm), Some("rpm"));
+        assert!(matches!(
+            "speed".parse::<ColumnMap>(),
+            Err(ReplayError::InvalidColumnMap(_))
+        ));
+        assert!(matches!(
+            "velocity=vss".parse::<ColumnMap>(),
+            Err(ReplayError::UnknownChannel(_))
+        ));
+    }
+
+    #[test]
+    fn interpolates_csv_channels() {
+        let log = Log::from_csv(CSV.as_bytes(), &columns()).unwrap();
+
+        assert_close(log.duration(), 2.0);
+        assert!(!log.has(Channel::CoolantTemp));
+
+        let sample = log.sample_at(1.5);
+        assert_close(sample.speed, 20.0);
+        assert_close(sample.rpm, 2000.0);
+        assert_eq!(sample.gear, 1);
+        assert_eq!(sample.coolant_temp, Sample::default().coolant_temp);
+
+        // The empty fuel cell is skipped, so fuel goes straight from 50% to 48%.
+        assert_close(log.sample_at(1.0).fuel, 0.49);
+        assert_eq!(log.sample_at(-1.0).speed, 0.0);
+        assert_eq!(log.sample_at(10.0).speed, 30.0);
+    }
+
+    #[test]
+    fn reads_json_arrays_and_lines() {
+        let columns = ColumnMap::default();
+        let array = r#"[{"time": 0, "speed": 10}, {"time": 2, "speed": "30"}]"#;
+        let lines = "{\"time\": 0, \"speed\": 10}\n{\"time\": 2, \"speed\": 30}\n";
+
+        for text in [array, lines] {
+            let log = Log::from_json(text.as_bytes(), &columns).unwrap();
+            assert_eq!(log.sample_at(1.0).speed, 20.0);
+        }
+    }
+
+    #[test]
+    fn reports_bad_logs() {
+        let columns = ColumnMap::default();
+
+        assert!(matches!(
+            Log::from_csv("speed\n10\n".as_bytes(), &columns),
+            Err(ReplayError::MissingColumn(column)) if column == "time"
+        ));
+        assert!(matches!(
+            Log::from_csv("time,speed\n0,fast\n".as_bytes(), &columns),
+            Err(ReplayError::InvalidValue { row: 1, .. })
+        ));
+        for cell in ["NaN", "inf", "-inf", "1e39"] {
+            assert!(matches!(
+                Log::from_csv(format!("time,speed\n0,1\n{cell},2\n").as_bytes(), &columns),
+                Err(ReplayError::InvalidValue { row: 2, .. })
+            ));
+        }
+        assert!(matches!(
+            Log::from_json(r#"[{"time": 0, "speed": "inf"}]"#.as_bytes(), &columns),
+            Err(ReplayError::InvalidValue { row: 1, .. })
+        ));
+        assert!(matches!(
+            Log::from_csv(
+                "time,speed\n0,3e38\n".as_bytes(),
+                &"speed=speed*10".parse().unwrap()
+            ),
+            Err(ReplayError::InvalidValue { row: 1, .. })
+        ));
+        assert!(matches!(
+            "time=ts*inf".parse::<ColumnMap>(),
+            Err(ReplayError::InvalidColumnMap(_))
+        ));
+        assert!(matches!(
+            Log::from_csv("time,speed\n".as_bytes(), &columns),
+            Err(ReplayError::Empty)
+        ));
+        for times in ["0\n1e20", "-3e38\n3e38"] {
+            assert!(matches!(
+                Log::from_csv(format!("time\n{times}\n").as_bytes(), &columns),
+                Err(ReplayError::TooLong(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn samples_far_past_the_end() {
+        let log = Log::from_csv(CSV.as_bytes(), &columns()).unwrap();
+
+        for time in [1e20, f32::INFINITY] {
+            assert_eq!(log.sample_at(time).speed, 30.0);
+        }
+        assert_eq!(log.sample_at(f32::NAN).time, Duration::ZERO);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::sample::Sample;
+use std::time::Duration;
+
+const IDLE_RPM: f32 = 800.0;
+/// Engine rpm per km/h in each gear.
+const GEAR_RATIOS: [f32; 6] = [140.0, 80.0, 55.0, 42.0, 33.0, 27.0];
+/// Speed above which the gearbox shifts out of each gear but the last.
+const UPSHIFT_SPEEDS: [f32; 5] = [20.0, 35.0, 55.0, 75.0, 100.0];
+/// Downshift this much below the upshift speed so the gear doesn't hunt.
+const SHIFT_HYSTERESIS: f32 = 5.0;
+
+const AMBIENT_TEMP: f32 = 20.0;
+const COOLANT_TEMP: f32 = 90.0;
+const COOLANT_TIME_CONSTANT: f32 = 120.0;
+const OIL_TEMP: f32 = 95.0;
+const OIL_TIME_CONSTANT: f32 = 300.0;
+
+/// Tank fraction burnt per kilometre, high enough to see the gauge move.
+const FUEL_PER_KM: f32 = 0.002;
+/// Tank fraction burnt per second at idle.
+const FUEL_PER_IDLE_SECOND: f32 = 0.000_01;
+
+/// Car driven at a given speed: picks the gear, derives the rpm and integrates
+/// fuel use and engine temperatures over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehicleModel {
+    sample: Sample,
+}
+
+impl VehicleModel {
+    /// Parked car with a cold engine and `fuel` left in the tank.
+    pub fn new(fuel: f32) -> Self {
+        Self {
+            sample: Sample {
+                rpm: IDLE_RPM,
+                fuel: fuel.clamp(0.0, 1.0),
+                coolant_temp: AMBIENT_TEMP,
+                oil_temp: AMBIENT_TEMP,
+                ..Sample::default()
+            },
+        }
+    }
+
+    pub fn sample(&self) -> Sample {
+        self.sample
+    }
+
+    /// Drives at `speed` km/h until `time`.
+    pub fn update(&mut self, time: Duration, speed: f32) -> Sample {
+        let dt = time.saturating_sub(self.sample.time).as_secs_f32();
+        let speed = speed.max(0.0);
+        let gear = next_gear(self.sample.gear, speed);
+        let rpm = match gear {
+            0 => IDLE_RPM,
+            gear => (speed * GEAR_RATIOS[gear as usize - 1]).max(IDLE_RPM),
+        };
+
+        let mut fuel =
+            self.sample.fuel - FUEL_PER_KM * speed * dt / 3600.0 - FUEL_PER_IDLE_SECOND * dt;
+        if fuel <= 0.0 {
+            // Refuel, so a looping demo never runs dry.
+            fuel = 1.0;
+        }
+
+        // Oil runs hotter under load.
+        let oil_target = OIL_TEMP + (rpm - IDLE_RPM) / 500.0;
+
+        self.sample = Sample {
+            time,
+            speed,
+            rpm,
+            gear,
+            fuel,
+            coolant_temp: approach(
+                self.sample.coolant_temp,
+                COOLANT_TEMP,
+                COOLANT_TIME_CONSTANT,
+                dt,
+            ),
+            oil_temp: approach(self.sample.oil_temp, oil_target, OIL_TIME_CONSTANT, dt),
+            heading: None,
+            throttle: None,
+            brake: None,
+        };
+        self.sample
+    }
+}
+
+impl Default for VehicleModel {
+    fn default() -> Self {
+        Self::new(0.75)
+    }
+}
+
+fn next_gear(gear: u8, speed: f32) -> u8 {
+    if speed < 1.0 {
+        return 0;
+    }
+
+    let mut gear = gear.max(1) as usize;
+    while gear <= UPSHIFT_SPEEDS.len() && speed > UPSHIFT_SPEEDS[gear - 1] {
+        gear += 1;
+    }
+    while gear > 1 && speed < UPSHIFT_SPEEDS[gear - 2] - SHIFT_HYSTERESIS {
+        gear -= 1;
+    }
+    gear as u8
+}
+
+/// First-order lag of `value` towards `target`.
+fn approach(value: f32, target: f32, time_constant: f32, dt: f32) -> f32 {
+    value + (target - value) * (1.0 - (-dt / time_constant).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neutral_at_standstill() {
+        let mut model = VehicleModel::default();
+        let sample = model.update(Duration::from_secs(1), 0.0);
+
+        assert_eq!(sample.gear, 0);
+        assert_eq!(sample.rpm, IDLE_RPM);
+    }
+
+    #[test]
+    fn shifts_up_and_down_with_hysteresis() {
+        assert_eq!(next_gear(0, 10.0), 1);
+        assert_eq!(next_gear(1, 21.0), 2);
+        assert_eq!(next_gear(2, 18.0), 2);
+        assert_eq!(next_gear(2, 14.0), 1);
+        assert_eq!(next_gear(1, 130.0), 6);
+        assert_eq!(next_gear(6, 40.0), 3);
+    }
+
+    #[test]
+    fn engine_warms_up_and_burns_fuel() {
+        let mut model = VehicleModel::default();
+        let sample = model.update(Duration::from_secs(600), 100.0);
+
+        assert!(sample.coolant_temp > 85.0 && sample.coolant_temp < COOLANT_TEMP);
+        assert!(sample.oil_temp > AMBIENT_TEMP && sample.oil_temp < sample.coolant_temp);
+        assert!(sample.fuel < 0.75);
+    }
+}
@@ -0,0 +1,414 @@
+//! Speed and heading from a GPS receiver talking NMEA-0183.
+
+mod sentence;
+
+pub use sentence::{FixQuality, Sentence};
+
+use crate::sample::Sample;
+use crate::simulated::SAMPLE_PERIOD;
+use crate::source::{SourceStatus, VehicleDataSource, Watchdog};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Baud rate of the NMEA-0183 standard, most USB receivers use it out of the box.
+pub const DEFAULT_BAUD_RATE: u32 = 4_800;
+
+/// Longest pause between two epochs of a replayed recording.
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(1);
+/// Pause after a read that timed out on an idle port.
+const IDLE_WAIT: Duration = Duration::from_millis(10);
+
+/// What the receiver reported so far.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GpsFix {
+    speed: Option<f32>,
+    heading: Option<f32>,
+    quality: Option<FixQuality>,
+    satellites: Option<u8>,
+    /// Status of the last RMC or VTG sentence.
+    valid: Option<bool>,
+}
+
+impl GpsFix {
+    pub fn update(&mut self, sentence: &Sentence) {
+        match *sentence {
+            Sentence::Rmc {
+                valid,
+                speed,
+                course,
+                ..
+            }
+            | Sentence::Vtg {
+                valid,
+                speed,
+                course,
+            } => {
+                self.valid = Some(valid);
+                if valid {
+                    self.speed = speed.or(self.speed);
+                    self.heading = course.or(self.heading);
+                }
+            }
+            Sentence::Gga {
+                quality,
+                satellites,
+                ..
+            } => {
+                self.quality = Some(quality);
+                self.satellites = satellites;
+            }
+            Sentence::Other => {}
+        }
+    }
+
+    /// Speed over ground in km/h, from the last sentence with a fix.
+    pub fn speed(&self) -> Option<f32> {
+        self.speed
+    }
+
+    /// Course over ground in degrees, from the last sentence with a fix.
+    pub fn heading(&self) -> Option<f32> {
+        self.heading
+    }
+
+    /// Fix quality of the last GGA sentence.
+    pub fn quality(&self) -> FixQuality {
+        self.quality.unwrap_or_default()
+    }
+
+    pub fn satellites(&self) -> Option<u8> {
+        self.satellites
+    }
+
+    /// Whether neither the RMC/VTG status nor the GGA quality report a lost fix.
+    pub fn has_fix(&self) -> bool {
+        (self.valid.is_some() || self.quality.is_some())
+            && self.valid != Some(false)
+            && self.quality.is_none_or(FixQuality::is_fix)
+    }
+}
+
+/// Source reading NMEA sentences on a background thread.
+///
+/// Goes [`SourceStatus::NoSignal`] while the receiver has no fix,
+/// [`SourceStatus::TimedOut`] when it stops talking and [`SourceStatus::Failed`]
+/// once the port can't be read.
+#[derive(Debug)]
+pub struct NmeaSource {
+    sentences: Receiver<io::Result<Sentence>>,
+    fix: GpsFix,
+    sample: Sample,
+    watchdog: Watchdog,
+    error: Option<io::Error>,
+}
+
+impl NmeaSource {
+    /// Reads a serial port, or anything that returns `0` while waiting for data.
+    pub fn start<R: Read + Send + 'static>(mut port: R) -> Self {
+        let (sender, sentences) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut line = Vec::new();
+            let mut buffer = [0; 256];
+
+            loop {
+                let read = match port.read(&mut buffer) {
+                    Ok(0) => {
+                        thread::sleep(IDLE_WAIT);
+                        continue;
+                    }
+                    Ok(read) => read,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        let _ = sender.send(Err(err));
+                        return;
+                    }
+                };
+
+                for &byte in &buffer[..read] {
+                    if byte != b'\n' {
+                        line.push(byte);
+                        continue;
+                    }
+                    // Corrupted sentences are dropped, like a receiver would.
+                    let sentence = String::from_utf8_lossy(&line).parse();
+                    line.clear();
+                    if let Ok(sentence) = sentence {
+                        if sender.send(Ok(sentence)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self::new(sentences)
+    }
+
+    /// Plays a recorded file at the pace of its timestamps.
+    pub fn replay(path: impl AsRef<Path>, looping: bool) -> Result<Self, NmeaError> {
+        let recording = fs::read_to_string(path)?;
+        let (sender, sentences) = mpsc::channel();
+
+        thread::spawn(move || {
+            play(&recording, looping, |line| match line.parse() {
+                Ok(sentence) => sender.send(Ok(sentence)).is_ok(),
+                Err(_) => true,
+            })
+        });
+
+        Ok(Self::new(sentences))
+    }
+
+    fn new(sentences: Receiver<io::Result<Sentence>>) -> Self {
+        Self {
+            sentences,
+            fix: GpsFix::default(),
+            sample: Sample::default(),
+            watchdog: Watchdog::default(),
+            error: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Watchdog::new(timeout);
+        self
+    }
+
+    pub fn fix(&self) -> &GpsFix {
+        &self.fix
+    }
+
+    /// Why the port stopped being read, if it did.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+}
+
+impl VehicleDataSource for NmeaSource {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        let mut received = false;
+
+        for sentence in self.sentences.try_iter() {
+            match sentence {
+                Ok(sentence) => {
+                    self.fix.update(&sentence);
+                    received = true;
+                }
+                Err(err) => self.error = Some(err),
+            }
+        }
+        if !received {
+            return None;
+        }
+
+        self.watchdog.feed(elapsed);
+        self.sample.time = elapsed;
+        self.sample.speed = self.fix.speed().unwrap_or(self.sample.speed);
+        self.sample.heading = self.fix.heading();
+        Some(self.sample)
+    }
+
+    fn status(&self, elapsed: Duration) -> SourceStatus {
+        match self.watchdog.status(elapsed) {
+            _ if self.error.is_some() => SourceStatus::Failed,
+            SourceStatus::Live if !self.fix.has_fix() => SourceStatus::NoSignal,
+            status => status,
+        }
+    }
+}
+
+/// Hands the lines of a recording to `emit`, waiting between epochs as long as
+/// their timestamps are apart, until `emit` returns `false` or the recording
+/// ends. Gaps longer than a second are shortened. Lines before the first
+/// timestamp are an epoch of [`SAMPLE_PERIOD`] each, and so is the pause before
+/// a loop starts over.
+pub fn play(recording: &str, looping: bool, mut emit: impl FnMut(&str) -> bool) {
+    if recording.trim().is_empty() {
+        return;
+    }
+
+    loop {
+        let mut last_time = None;
+
+        for line in recording.lines().filter(|line| !line.trim().is_empty()) {
+            let time = line.parse::<Sentence>().ok().and_then(|s| s.time());
+            if let Some(time) = time {
+                if let Some(last) = last_time.filter(|&last| time > last) {
+                    thread::sleep(Duration::from_secs_f32(time - last).min(MAX_REPLAY_GAP));
+                }
+                last_time = Some(time);
+            }
+
+            if !emit(line) {
+                return;
+            }
+            if last_time.is_none() {
+                thread::sleep(SAMPLE_PERIOD);
+            }
+        }
+
+        if !looping {
+            return;
+        }
+        thread::sleep(SAMPLE_PERIOD);
+    }
+}
+
+#[derive(Debug)]
+pub enum NmeaError {
+    Io(io::Error),
+    Malformed(String),
+    Checksum(String),
+}
+
+impl fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NmeaError::Io(err) => write!(f, "cannot read the GPS: {err}"),
+            NmeaError::Malformed(line) => write!(f, "`{line}` is not an NMEA sentence"),
+            NmeaError::Checksum(line) => write!(f, "wrong checksum in `{line}`"),
+        }
+    }
+}
+
+impl Error for NmeaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NmeaError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NmeaError {
+    fn from(err: io::Error) -> Self {
+        NmeaError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_FIX: [&str; 2] = [
+        "$GPRMC,120000.00,V,,,,,,,180326,,,N*70",
+        "$GPGGA,120000.00,,,,,0,00,99.99,,,,,,*65",
+    ];
+    const FIX: [&str; 3] = [
+        "$GPRMC,120000.20,A,5130.000,N,00007.000,W,27.0,90.0,180326,,,A*4F",
+        "$GPVTG,90.0,T,,M,27.0,N,50.0,K,A*34",
+        "$GPGGA,120000.20,5130.000,N,00007.000,W,1,09,0.9,20.0,M,47.0,M,,*4E",
+    ];
+
+    fn update(fix: &mut GpsFix, lines: &[&str]) {
+        for line in lines {
+            fix.update(&line.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn tracks_the_fix() {
+        let mut fix = GpsFix::default();
+        assert!(!fix.has_fix());
+
+        update(&mut fix, &NO_FIX);
+        assert!(!fix.has_fix());
+        assert_eq!(fix.speed(), None);
+
+        update(&mut fix, &FIX);
+        assert!(fix.has_fix());
+        assert_eq!(fix.speed(), Some(50.0));
+        assert_eq!(fix.heading(), Some(90.0));
+        assert_eq!(fix.quality(), FixQuality::Gps);
+        assert_eq!(fix.satellites(), Some(9));
+
+        update(&mut fix, &NO_FIX);
+        assert!(!fix.has_fix());
+        assert_eq!(fix.speed(), Some(50.0));
+    }
+
+    #[test]
+    fn plays_recordings_in_order() {
+        let recording = [NO_FIX.as_slice(), &FIX].concat().join("\r\n");
+        let mut lines = Vec::new();
+
+        play(&recording, false, |line| {
+            lines.push(line.to_owned());
+            true
+        });
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[4], FIX[2]);
+    }
+
+    #[test]
+    fn paces_recordings_without_timestamps() {
+        use std::time::Instant;
+
+        let started = Instant::now();
+        let mut count = 0;
+        play(FIX[1], true, |_| {
+            count += 1;
+            count < 3
+        });
+
+        assert!(started.elapsed() >= 4 * SAMPLE_PERIOD);
+    }
+
+    #[test]
+    fn reports_read_errors() {
+        struct Unplugged;
+
+        impl Read for Unplugged {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+        }
+
+        let mut source = NmeaSource::start(Unplugged);
+        let started = std::time::Instant::now();
+        while source.error().is_none() {
+            source.poll(started.elapsed());
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(source.status(started.elapsed()), SourceStatus::Failed);
+        assert_eq!(source.error().unwrap().kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_a_pty() {
+        use crate::serial::{open_pty, SerialPort};
+        use std::io::Write;
+        use std::time::Instant;
+
+        let (mut master, path) = open_pty().unwrap();
+        let port = SerialPort::open(&path, DEFAULT_BAUD_RATE).unwrap();
+        let mut source = NmeaSource::start(port);
+
+        let started = Instant::now();
+        let mut send = |lines: &[&str], status| {
+            for line in lines {
+                write!(master, "{line}\r\n").unwrap();
+            }
+            while source.status(started.elapsed()) != status {
+                source.poll(started.elapsed());
+                assert!(started.elapsed() < Duration::from_secs(5), "{status:?}");
+                thread::sleep(Duration::from_millis(10));
+            }
+        };
+
+        send(&NO_FIX, SourceStatus::NoSignal);
+        send(&FIX, SourceStatus::Live);
+        assert_eq!(source.fix().speed(), Some(50.0));
+    }
+}
@@ -0,0 +1,228 @@
+use super::NmeaError;
+use std::fmt;
+use std::str::FromStr;
+
+const KNOTS_TO_KMH: f32 = 1.852;
+
+/// GGA fix quality indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixQuality {
+    #[default]
+    Invalid,
+    Gps,
+    Dgps,
+    Pps,
+    Rtk,
+    FloatRtk,
+    /// Dead reckoning.
+    Estimated,
+    Manual,
+    Simulation,
+}
+
+impl FixQuality {
+    fn from_digit(digit: &str) -> Option<Self> {
+        Some(match digit {
+            "0" => FixQuality::Invalid,
+            "1" => FixQuality::Gps,
+            "2" => FixQuality::Dgps,
+            "3" => FixQuality::Pps,
+            "4" => FixQuality::Rtk,
+            "5" => FixQuality::FloatRtk,
+            "6" => FixQuality::Estimated,
+            "7" => FixQuality::Manual,
+            "8" => FixQuality::Simulation,
+            _ => return None,
+        })
+    }
+
+    pub fn is_fix(self) -> bool {
+        self != FixQuality::Invalid
+    }
+}
+
+impl fmt::Display for FixQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FixQuality::Invalid => "no fix",
+            FixQuality::Gps => "GPS",
+            FixQuality::Dgps => "DGPS",
+            FixQuality::Pps => "PPS",
+            FixQuality::Rtk => "RTK",
+            FixQuality::FloatRtk => "float RTK",
+            FixQuality::Estimated => "estimated",
+            FixQuality::Manual => "manual",
+            FixQuality::Simulation => "simulation",
+        })
+    }
+}
+
+/// NMEA-0183 sentence the source understands, from any talker (`GP`, `GN`, `GL`…).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sentence {
+    /// Recommended minimum data.
+    Rmc {
+        /// Seconds since midnight UTC.
+        time: Option<f32>,
+        valid: bool,
+        /// km/h.
+        speed: Option<f32>,
+        course: Option<f32>,
+    },
+    /// Course and speed over ground.
+    Vtg {
+        valid: bool,
+        /// km/h.
+        speed: Option<f32>,
+        course: Option<f32>,
+    },
+    /// Fix data.
+    Gga {
+        time: Option<f32>,
+        quality: FixQuality,
+        satellites: Option<u8>,
+    },
+    /// Well-formed sentence of another type, e.g. `GSV`.
+    Other,
+}
+
+impl Sentence {
+    /// UTC time of the fix, when the sentence carries one.
+    pub fn time(&self) -> Option<f32> {
+        match self {
+            Sentence::Rmc { time, .. } | Sentence::Gga { time, .. } => *time,
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Sentence {
+    type Err = NmeaError;
+
+    /// Parses `$GPRMC,…*hh`, the checksum is verified when present.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let malformed = || NmeaError::Malformed(line.to_owned());
+        let body = line.strip_prefix('$').ok_or_else(malformed)?;
+
+        let body = match body.split_once('*') {
+            Some((body, checksum)) => {
+                let expected = u8::from_str_radix(checksum, 16).map_err(|_| malformed())?;
+                let actual = body.bytes().fold(0, |checksum, byte| checksum ^ byte);
+                if expected != actual {
+                    return Err(NmeaError::Checksum(line.to_owned()));
+                }
+                body
+            }
+            None => body,
+        };
+
+        let mut fields = body.split(',');
+        let address = fields.next().unwrap_or_default();
+        let fields: Vec<&str> = fields.collect();
+        let field = |index: usize| fields.get(index).copied().unwrap_or_default();
+
+        // Talker id, then the sentence formatter.
+        let formatter = address.get(2..).filter(|_| address.len() == 5);
+        Ok(match formatter {
+            Some("RMC") => Sentence::Rmc {
+                time: time(field(0)),
+                valid: field(1) == "A" && field(11) != "N",
+                speed: number(field(6)).map(|knots| knots * KNOTS_TO_KMH),
+                course: number(field(7)),
+            },
+            Some("VTG") => Sentence::Vtg {
+                valid: field(8) != "N",
+                speed: number(field(6))
+                    .or_else(|| number(field(4)).map(|knots| knots * KNOTS_TO_KMH)),
+                course: number(field(0)),
+            },
+            Some("GGA") => Sentence::Gga {
+                time: time(field(0)),
+                quality: FixQuality::from_digit(field(5)).ok_or_else(malformed)?,
+                satellites: field(6).parse().ok(),
+            },
+            Some(_) => Sentence::Other,
+            None => return Err(malformed()),
+        })
+    }
+}
+
+fn number(field: &str) -> Option<f32> {
+    field.parse().ok()
+}
+
+/// `hhmmss.ss` as seconds since midnight.
+fn time(field: &str) -> Option<f32> {
+    let hours: f32 = field.get(0..2)?.parse().ok()?;
+    let minutes: f32 = field.get(2..4)?.parse().ok()?;
+    let seconds: f32 = field.get(4..)?.parse().ok()?;
+
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rmc() {
+        let rmc: Sentence = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A"
+            .parse()
+            .unwrap();
+
+        let Sentence::Rmc {
+            time,
+            valid,
+            speed,
+            course,
+        } = rmc
+        else {
+            panic!("{rmc:?}");
+        };
+        assert_eq!(time, Some(12.0 * 3600.0 + 35.0 * 60.0 + 19.0));
+        assert!(valid);
+        assert!((speed.unwrap() - 41.4848).abs() < 1e-3);
+        assert_eq!(course, Some(84.4));
+    }
+
+    #[test]
+    fn parses_vtg_and_gga() {
+        assert_eq!(
+            "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48"
+                .parse::<Sentence>()
+                .unwrap(),
+            Sentence::Vtg {
+                valid: true,
+                speed: Some(10.2),
+                course: Some(54.7),
+            }
+        );
+        assert_eq!(
+            "$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59"
+                .parse::<Sentence>()
+                .unwrap(),
+            Sentence::Gga {
+                time: Some(45319.0),
+                quality: FixQuality::Gps,
+                satellites: Some(8),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_corrupted_sentences() {
+        assert!(matches!(
+            "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*49".parse::<Sentence>(),
+            Err(NmeaError::Checksum(_))
+        ));
+        assert!(matches!(
+            "GPVTG,054.7".parse::<Sentence>(),
+            Err(NmeaError::Malformed(_))
+        ));
+        assert_eq!(
+            "$GPGSV,1,1,00*79".parse::<Sentence>().unwrap(),
+            Sentence::Other
+        );
+    }
+}
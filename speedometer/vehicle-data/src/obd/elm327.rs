@@ -0,0 +1,165 @@
+use super::pid::Pid;
+use super::ObdError;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+/// Longest wait for the `>` prompt, the adapter gives up on a silent ECU sooner.
+pub const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Commands sent after the reset: echo, linefeeds, spaces and headers off,
+/// automatic protocol.
+const SETUP: [&str; 5] = ["ATE0", "ATL0", "ATS0", "ATH0", "ATSP0"];
+
+/// Client of the ELM327 AT-command protocol over any byte stream, usually a
+/// [`SerialPort`](crate::SerialPort).
+#[derive(Debug)]
+pub struct Elm327<T> {
+    port: T,
+    timeout: Duration,
+}
+
+impl<T: Read + Write> Elm327<T> {
+    pub fn new(port: T) -> Self {
+        Self {
+            port,
+            timeout: DEFAULT_RESPONSE_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Resets the adapter and sets it up for [`Elm327::query`].
+    pub fn init(&mut self) -> Result<(), ObdError> {
+        self.command("ATZ")?;
+
+        for setup in SETUP {
+            let response = self.command(setup)?;
+            if !response.iter().any(|line| line == "OK") {
+                return Err(ObdError::InvalidResponse(response.join(" ")));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Current value of a Mode 01 PID.
+    pub fn query(&mut self, pid: Pid) -> Result<f32, ObdError> {
+        let response = self.command(&format!("01{pid}"))?;
+        parse_response(pid, &response)
+    }
+
+    /// Sends `command` and returns the lines of the response, without the echo
+    /// and the prompt.
+    pub fn command(&mut self, command: &str) -> Result<Vec<String>, ObdError> {
+        self.port.write_all(command.as_bytes())?;
+        self.port.write_all(b"\r")?;
+        self.port.flush()?;
+
+        let deadline = Instant::now() + self.timeout;
+        let mut response = Vec::new();
+        let mut buffer = [0; 64];
+
+        while !response.contains(&b'>') {
+            if Instant::now() > deadline {
+                return Err(ObdError::Timeout);
+            }
+            match self.port.read(&mut buffer) {
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock
+                            | io::ErrorKind::TimedOut
+                            | io::ErrorKind::Interrupted
+                    ) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        let (response, _) = response.split_once('>').unwrap_or_default();
+
+        Ok(response
+            .split(['\r', '\n'])
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && *line != command && *line != "SEARCHING...")
+            .map(str::to_owned)
+            .collect())
+    }
+}
+
+/// Data bytes of the `41 <pid> …` line, or the error reported by the adapter.
+fn parse_response(pid: Pid, response: &[String]) -> Result<f32, ObdError> {
+    let expected = format!("41{pid}");
+
+    for line in response {
+        let hex: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(match line.as_str() {
+                "NO DATA" => ObdError::NoData,
+                "STOPPED" => ObdError::Stopped,
+                _ => ObdError::Adapter(line.clone()),
+            });
+        }
+
+        if let Some(data) = hex.strip_prefix(&expected) {
+            let bytes: Option<Vec<u8>> = (0..data.len() / 2)
+                .map(|i| u8::from_str_radix(&data[i * 2..i * 2 + 2], 16).ok())
+                .collect();
+
+            return bytes
+                .and_then(|bytes| pid.decode(&bytes))
+                .ok_or_else(|| ObdError::InvalidResponse(line.clone()));
+        }
+    }
+
+    Err(ObdError::InvalidResponse(response.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_data_with_or_without_spaces() {
+        assert_eq!(
+            parse_response(Pid::Speed, &lines(&["41 0D 3C"])).unwrap(),
+            60.0
+        );
+        assert_eq!(
+            parse_response(Pid::Rpm, &lines(&["410C1AF8"])).unwrap(),
+            1726.0
+        );
+        assert_eq!(
+            parse_response(Pid::Speed, &lines(&["7E8", "41 0D 50"])).unwrap(),
+            80.0
+        );
+    }
+
+    #[test]
+    fn reports_adapter_errors() {
+        assert!(matches!(
+            parse_response(Pid::Speed, &lines(&["NO DATA"])),
+            Err(ObdError::NoData)
+        ));
+        assert!(matches!(
+            parse_response(Pid::Speed, &lines(&["STOPPED"])),
+            Err(ObdError::Stopped)
+        ));
+        assert!(matches!(
+            parse_response(Pid::Speed, &lines(&["UNABLE TO CONNECT"])),
+            Err(ObdError::Adapter(_))
+        ));
+        assert!(matches!(
+            parse_response(Pid::Rpm, &lines(&["41 0C 1A"])),
+            Err(ObdError::InvalidResponse(_))
+        ));
+    }
+}
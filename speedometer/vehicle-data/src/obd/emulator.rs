@@ -0,0 +1,216 @@
+use super::pid::Pid;
+use crate::sample::Sample;
+use crate::source::VehicleDataSource;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+const IDENTIFICATION: &str = "ELM327 v1.5";
+
+/// ELM327 adapter answering from a [`VehicleDataSource`] instead of a car, to
+/// test the OBD-II source on a pseudo-terminal, see `examples/elm327_emulator.rs`.
+pub struct Elm327Emulator {
+    source: Box<dyn VehicleDataSource>,
+    started: Instant,
+    sample: Sample,
+    echo: bool,
+    spaces: bool,
+    linefeeds: bool,
+    fault_every: Option<u32>,
+    queries: u32,
+}
+
+impl Elm327Emulator {
+    pub fn new(source: Box<dyn VehicleDataSource>) -> Self {
+        Self {
+            source,
+            started: Instant::now(),
+            sample: Sample::default(),
+            echo: true,
+            spaces: true,
+            linefeeds: true,
+            fault_every: None,
+            queries: 0,
+        }
+    }
+
+    /// Fails every `every`-th PID query, alternately with `NO DATA` and `STOPPED`.
+    pub fn with_faults(mut self, every: u32) -> Self {
+        self.fault_every = (every > 0).then_some(every);
+        self
+    }
+
+    /// Everything the adapter sends back for `command`, up to the `>` prompt.
+    pub fn respond(&mut self, command: &str) -> String {
+        let command = command.trim();
+        let normalized: String = command
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        let body = match normalized.strip_prefix("AT") {
+            Some(setting) => self.configure(setting),
+            None => self.obd(&normalized),
+        };
+
+        let end = if self.linefeeds { "\r\n" } else { "\r" };
+        let echo = if self.echo {
+            format!("{command}{end}")
+        } else {
+            String::new()
+        };
+
+        format!("{echo}{body}{end}{end}>")
+    }
+
+    /// Answers the commands read from `port` until it is closed.
+    pub fn serve(&mut self, mut port: impl Read + Write) -> io::Result<()> {
+        let mut command = Vec::new();
+        let mut buffer = [0; 64];
+
+        loop {
+            let read = port.read(&mut buffer)?;
+            if read == 0 {
+                return Ok(());
+            }
+
+            for &byte in &buffer[..read] {
+                if byte != b'\r' {
+                    command.push(byte);
+                    continue;
+                }
+                let response = self.respond(&String::from_utf8_lossy(&command));
+                command.clear();
+                port.write_all(response.as_bytes())?;
+                port.flush()?;
+            }
+        }
+    }
+
+    fn configure(&mut self, setting: &str) -> String {
+        match setting {
+            "Z" | "D" => {
+                self.echo = true;
+                self.spaces = true;
+                self.linefeeds = true;
+                return IDENTIFICATION.to_owned();
+            }
+            "I" => return IDENTIFICATION.to_owned(),
+            "RV" => return "12.6V".to_owned(),
+            "E0" => self.echo = false,
+            "E1" => self.echo = true,
+            "S0" => self.spaces = false,
+            "S1" => self.spaces = true,
+            "L0" => self.linefeeds = false,
+            "L1" => self.linefeeds = true,
+            _ if setting.starts_with('H') || setting.starts_with("SP") => {}
+            _ => return "?".to_owned(),
+        }
+
+        "OK".to_owned()
+    }
+
+    fn obd(&mut self, request: &str) -> String {
+        let Some(code) = request
+            .strip_prefix("01")
+            .and_then(|code| u8::from_str_radix(code, 16).ok())
+        else {
+            return "?".to_owned();
+        };
+
+        if let Some(sample) = self.source.poll(self.started.elapsed()) {
+            self.sample = sample;
+        }
+
+        if let Some(fault) = self.fault_every {
+            self.queries += 1;
+            if self.queries.is_multiple_of(fault) {
+                return if (self.queries / fault) % 2 == 1 {
+                    "NO DATA"
+                } else {
+                    "STOPPED"
+                }
+                .to_owned();
+            }
+        }
+
+        let data = match code {
+            // Supported PIDs 01-20.
+            0x00 => Pid::ALL
+                .into_iter()
+                .filter(|pid| (0x01..=0x20).contains(&pid.code()))
+                .fold(0u32, |bits, pid| bits | 1 << (32 - pid.code()))
+                .to_be_bytes()
+                .to_vec(),
+            _ => match Pid::from_code(code) {
+                Some(pid) => pid.encode(value(&self.sample, pid)),
+                None => return "NO DATA".to_owned(),
+            },
+        };
+
+        let bytes: Vec<String> = [0x41, code]
+            .iter()
+            .chain(&data)
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        bytes.join(if self.spaces { " " } else { "" })
+    }
+}
+
+fn value(sample: &Sample, pid: Pid) -> f32 {
+    match pid {
+        Pid::CoolantTemp => sample.coolant_temp,
+        Pid::Rpm => sample.rpm,
+        Pid::Speed => sample.speed,
+        Pid::FuelLevel => sample.fuel,
+        Pid::OilTemp => sample.oil_temp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct Fixed(Sample);
+
+    impl VehicleDataSource for Fixed {
+        fn poll(&mut self, _elapsed: Duration) -> Option<Sample> {
+            Some(self.0)
+        }
+    }
+
+    fn emulator() -> Elm327Emulator {
+        Elm327Emulator::new(Box::new(Fixed(Sample {
+            speed: 60.0,
+            coolant_temp: 83.0,
+            ..Sample::default()
+        })))
+    }
+
+    #[test]
+    fn follows_the_settings() {
+        let mut emulator = emulator();
+
+        assert_eq!(emulator.respond("ATZ"), "ATZ\r\nELM327 v1.5\r\n\r\n>");
+        assert_eq!(emulator.respond("ATE0"), "OK\r\n\r\n>");
+        assert_eq!(emulator.respond("ATL0"), "OK\r\r>");
+        assert_eq!(emulator.respond("01 05"), "41 05 7B\r\r>");
+        assert_eq!(emulator.respond("ATS0"), "OK\r\r>");
+        assert_eq!(emulator.respond("010D"), "410D3C\r\r>");
+        assert_eq!(emulator.respond("0100"), "410008180000\r\r>");
+        assert_eq!(emulator.respond("0199"), "NO DATA\r\r>");
+        assert_eq!(emulator.respond("ATXYZ"), "?\r\r>");
+    }
+
+    #[test]
+    fn injects_faults() {
+        let mut emulator = emulator().with_faults(2);
+        emulator.respond("ATE0");
+
+        let responses: Vec<String> = (0..4).map(|_| emulator.respond("010D")).collect();
+        assert!(responses[0].starts_with("41 0D 3C"));
+        assert!(responses[1].starts_with("NO DATA"));
+        assert!(responses[3].starts_with("STOPPED"));
+    }
+}
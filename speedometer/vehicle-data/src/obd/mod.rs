@@ -0,0 +1,349 @@
+//! Live data from the OBD-II port through an ELM327 adapter.
+
+mod elm327;
+mod emulator;
+mod pid;
+
+pub use elm327::{Elm327, DEFAULT_RESPONSE_TIMEOUT};
+pub use emulator::Elm327Emulator;
+pub use pid::Pid;
+
+use crate::log::Channel;
+use crate::sample::Sample;
+use crate::source::{SourceStatus, VehicleDataSource, Watchdog};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Baud rate of most ELM327 clones.
+pub const DEFAULT_BAUD_RATE: u32 = 38_400;
+
+/// How often each PID is requested.
+///
+/// Parses from `channel=seconds` pairs separated by commas, e.g.
+/// `speed=0.1,coolant_temp=5`. Channels that are not listed keep their default
+/// period, `0` stops polling a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PidSchedule {
+    periods: Vec<(Pid, Duration)>,
+}
+
+impl PidSchedule {
+    pub fn empty() -> Self {
+        Self {
+            periods: Vec::new(),
+        }
+    }
+
+    /// Requests `pid` every `period`, a zero period removes it.
+    pub fn with(mut self, pid: Pid, period: Duration) -> Self {
+        self.periods.retain(|&(scheduled, _)| scheduled != pid);
+        if !period.is_zero() {
+            self.periods.push((pid, period));
+        }
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pid, Duration)> + '_ {
+        self.periods.iter().copied()
+    }
+
+    pub fn period(&self, pid: Pid) -> Option<Duration> {
+        self.iter()
+            .find(|&(scheduled, _)| scheduled == pid)
+            .map(|(_, period)| period)
+    }
+}
+
+impl Default for PidSchedule {
+    /// The needle and the tachometer at 10 Hz, the slow channels every few seconds.
+    fn default() -> Self {
+        Self::empty()
+            .with(Pid::Speed, Duration::from_millis(100))
+            .with(Pid::Rpm, Duration::from_millis(100))
+            .with(Pid::CoolantTemp, Duration::from_secs(2))
+            .with(Pid::OilTemp, Duration::from_secs(2))
+            .with(Pid::FuelLevel, Duration::from_secs(10))
+    }
+}
+
+impl FromStr for PidSchedule {
+    type Err = ObdError;
+
+    fn from_str(pairs: &str) -> Result<Self, Self::Err> {
+        let invalid = || ObdError::InvalidSchedule(pairs.to_owned());
+        let mut schedule = Self::default();
+
+        for pair in pairs
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (channel, seconds) = pair.split_once('=').ok_or_else(invalid)?;
+            let pid = channel
+                .trim()
+                .parse::<Channel>()
+                .ok()
+                .and_then(Pid::from_channel)
+                .ok_or_else(invalid)?;
+            let period = seconds
+                .trim()
+                .parse()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(invalid)?;
+
+            schedule = schedule.with(pid, period);
+        }
+
+        Ok(schedule)
+    }
+}
+
+/// Source polling an ELM327 adapter on a background thread, so a slow or silent
+/// ECU never holds the dashboard up.
+///
+/// `NO DATA` and `STOPPED` answers leave the channel at its last value, the
+/// source is [`SourceStatus::NoSignal`] while the speed gets no other answer.
+/// When nothing comes back for longer than the timeout the source goes
+/// [`SourceStatus::TimedOut`], and [`SourceStatus::Failed`] once the port fails.
+#[derive(Debug)]
+pub struct ObdSource {
+    values: Receiver<(Channel, Result<f32, ObdError>)>,
+    sample: Sample,
+    watchdog: Watchdog,
+    /// Last answer to the speed request if it was an error, or the port error
+    /// that stopped the polling.
+    error: Option<ObdError>,
+}
+
+impl ObdSource {
+    /// Initialises the adapter, then polls it following `schedule`.
+    pub fn start<T>(mut adapter: Elm327<T>, schedule: PidSchedule) -> Result<Self, ObdError>
+    where
+        T: Read + Write + Send + 'static,
+    {
+        adapter.init()?;
+
+        let (sender, values) = mpsc::channel();
+        thread::spawn(move || poll_adapter(adapter, schedule, sender));
+
+        Ok(Self {
+            values,
+            sample: Sample::default(),
+            watchdog: Watchdog::default(),
+            error: None,
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Watchdog::new(timeout);
+        self
+    }
+
+    /// Why the speed couldn't be read, or why the adapter stopped being polled.
+    pub fn error(&self) -> Option<&ObdError> {
+        self.error.as_ref()
+    }
+}
+
+impl VehicleDataSource for ObdSource {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        let mut received = false;
+
+        for (channel, value) in self.values.try_iter() {
+            match value {
+                Ok(value) => {
+                    channel.apply(&mut self.sample, value);
+                    received = true;
+                    if channel == Channel::Speed {
+                        self.error = None;
+                    }
+                }
+                Err(err) if channel == Channel::Speed || matches!(err, ObdError::Io(_)) => {
+                    self.error = Some(err);
+                }
+                Err(_) => {}
+            }
+        }
+        if !received {
+            return None;
+        }
+
+        self.watchdog.feed(elapsed);
+        self.sample.time = elapsed;
+        Some(self.sample)
+    }
+
+    fn status(&self, elapsed: Duration) -> SourceStatus {
+        match (&self.error, self.watchdog.status(elapsed)) {
+            (Some(ObdError::Io(_)), _) => SourceStatus::Failed,
+            (Some(_), SourceStatus::Live) => SourceStatus::NoSignal,
+            (_, status) => status,
+        }
+    }
+}
+
+/// Requests the PID that is due the soonest, until the source is dropped or the
+/// port fails.
+fn poll_adapter<T: Read + Write>(
+    mut adapter: Elm327<T>,
+    schedule: PidSchedule,
+    values: Sender<(Channel, Result<f32, ObdError>)>,
+) {
+    let now = Instant::now();
+    let mut due: Vec<(Pid, Duration, Instant)> = schedule
+        .iter()
+        .map(|(pid, period)| (pid, period, now))
+        .collect();
+
+    while let Some((pid, period, at)) = due.iter_mut().min_by_key(|(_, _, at)| *at) {
+        thread::sleep(at.saturating_duration_since(Instant::now()));
+        // A late query doesn't make the next ones bunch up.
+        *at = (*at + *period).max(Instant::now());
+
+        let result = adapter.query(*pid);
+        let failed = matches!(result, Err(ObdError::Io(_)));
+        if values.send((pid.channel(), result)).is_err() || failed {
+            return;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ObdError {
+    Io(io::Error),
+    /// No `>` prompt within the response timeout.
+    Timeout,
+    /// The ECU didn't answer the request.
+    NoData,
+    /// The request was interrupted, e.g. by another command.
+    Stopped,
+    /// Any other error reported by the adapter, such as `UNABLE TO CONNECT`.
+    Adapter(String),
+    InvalidResponse(String),
+    InvalidSchedule(String),
+}
+
+impl fmt::Display for ObdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObdError::Io(err) => write!(f, "OBD-II adapter: {err}"),
+            ObdError::Timeout => f.write_str("the OBD-II adapter didn't answer"),
+            ObdError::NoData => f.write_str("NO DATA"),
+            ObdError::Stopped => f.write_str("STOPPED"),
+            ObdError::Adapter(message) => write!(f, "OBD-II adapter error: {message}"),
+            ObdError::InvalidResponse(response) => {
+                write!(f, "unexpected OBD-II response `{response}`")
+            }
+            ObdError::InvalidSchedule(pairs) => write!(
+                f,
+                "invalid PID schedule `{pairs}`, expected e.g. `speed=0.1,coolant_temp=5`"
+            ),
+        }
+    }
+}
+
+impl Error for ObdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObdError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ObdError {
+    fn from(err: io::Error) -> Self {
+        ObdError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    #[cfg(unix)]
+    use crate::serial::{open_pty, SerialPort};
+
+    #[test]
+    fn parses_schedules() {
+        let schedule: PidSchedule = "speed=0.05, fuel=0".parse().unwrap();
+
+        assert_eq!(schedule.period(Pid::Speed), Some(Duration::from_millis(50)));
+        assert_eq!(schedule.period(Pid::FuelLevel), None);
+        assert_eq!(schedule.period(Pid::Rpm), Some(Duration::from_millis(100)));
+
+        assert!("gear=1".parse::<PidSchedule>().is_err());
+        assert!("speed=fast".parse::<PidSchedule>().is_err());
+    }
+
+    #[test]
+    fn reports_adapter_errors() {
+        let (sender, values) = mpsc::channel();
+        let mut source = ObdSource {
+            values,
+            sample: Sample::default(),
+            watchdog: Watchdog::default(),
+            error: None,
+        };
+        let mut send = |channel, value| {
+            sender.send((channel, value)).unwrap();
+            source.poll(Duration::ZERO);
+            source.status(Duration::ZERO)
+        };
+
+        assert_eq!(send(Channel::Speed, Ok(50.0)), SourceStatus::Live);
+        assert_eq!(
+            send(Channel::OilTemp, Err(ObdError::NoData)),
+            SourceStatus::Live
+        );
+        assert_eq!(
+            send(Channel::Speed, Err(ObdError::Stopped)),
+            SourceStatus::NoSignal
+        );
+        assert_eq!(send(Channel::Rpm, Ok(900.0)), SourceStatus::NoSignal);
+        assert_eq!(send(Channel::Speed, Ok(52.0)), SourceStatus::Live);
+        assert_eq!(
+            send(
+                Channel::Rpm,
+                Err(ObdError::Io(io::ErrorKind::BrokenPipe.into()))
+            ),
+            SourceStatus::Failed
+        );
+        assert_eq!(source.sample.speed, 52.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_the_emulator_through_a_pty() {
+        let (master, path) = open_pty().unwrap();
+        thread::spawn(move || {
+            let mut emulator = Elm327Emulator::new(Generator::Sweep.source()).with_faults(3);
+            emulator.serve(master)
+        });
+
+        let port = SerialPort::open(&path, DEFAULT_BAUD_RATE).unwrap();
+        let schedule = PidSchedule::empty()
+            .with(Pid::Speed, Duration::from_millis(10))
+            .with(Pid::CoolantTemp, Duration::from_millis(10));
+        let mut source = ObdSource::start(Elm327::new(port), schedule).unwrap();
+
+        let started = Instant::now();
+        let mut sample = None;
+        while started.elapsed() < Duration::from_secs(5) {
+            sample = source.poll(started.elapsed()).or(sample);
+            if sample.is_some_and(|sample| sample.speed > 0.0) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(sample.unwrap().speed > 0.0);
+        assert_eq!(source.status(started.elapsed()), SourceStatus::Live);
+    }
+}
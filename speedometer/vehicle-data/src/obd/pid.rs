@@ -0,0 +1,124 @@
+use crate::log::Channel;
+use std::fmt;
+
+/// Mode 01 parameters the dashboards understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pid {
+    /// `0x05`, °C.
+    CoolantTemp,
+    /// `0x0C`, rpm.
+    Rpm,
+    /// `0x0D`, km/h.
+    Speed,
+    /// `0x2F`, `0.0` to `1.0`.
+    FuelLevel,
+    /// `0x5C`, °C.
+    OilTemp,
+}
+
+impl Pid {
+    pub const ALL: [Pid; 5] = [
+        Pid::CoolantTemp,
+        Pid::Rpm,
+        Pid::Speed,
+        Pid::FuelLevel,
+        Pid::OilTemp,
+    ];
+
+    pub fn code(self) -> u8 {
+        match self {
+            Pid::CoolantTemp => 0x05,
+            Pid::Rpm => 0x0C,
+            Pid::Speed => 0x0D,
+            Pid::FuelLevel => 0x2F,
+            Pid::OilTemp => 0x5C,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|pid| pid.code() == code)
+    }
+
+    /// Dashboard channel the value goes to.
+    pub fn channel(self) -> Channel {
+        match self {
+            Pid::CoolantTemp => Channel::CoolantTemp,
+            Pid::Rpm => Channel::Rpm,
+            Pid::Speed => Channel::Speed,
+            Pid::FuelLevel => Channel::Fuel,
+            Pid::OilTemp => Channel::OilTemp,
+        }
+    }
+
+    pub fn from_channel(channel: Channel) -> Option<Self> {
+        Self::ALL.into_iter().find(|pid| pid.channel() == channel)
+    }
+
+    /// Number of data bytes in the response.
+    pub fn data_len(self) -> usize {
+        match self {
+            Pid::Rpm => 2,
+            _ => 1,
+        }
+    }
+
+    /// Value of the data bytes `A`, `B`… of a response, `None` if too short.
+    pub fn decode(self, data: &[u8]) -> Option<f32> {
+        let data = data.get(..self.data_len())?;
+        let a = data[0] as f32;
+
+        Some(match self {
+            Pid::CoolantTemp | Pid::OilTemp => a - 40.0,
+            Pid::Rpm => (a * 256.0 + data[1] as f32) / 4.0,
+            Pid::Speed => a,
+            Pid::FuelLevel => a / 255.0,
+        })
+    }
+
+    /// Data bytes carrying `value`, clamped to the range of the PID.
+    pub fn encode(self, value: f32) -> Vec<u8> {
+        let byte = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+
+        match self {
+            Pid::CoolantTemp | Pid::OilTemp => vec![byte(value + 40.0)],
+            Pid::Rpm => {
+                let raw = (value * 4.0).round().clamp(0.0, u16::MAX as f32) as u16;
+                raw.to_be_bytes().to_vec()
+            }
+            Pid::Speed => vec![byte(value)],
+            Pid::FuelLevel => vec![byte(value * 255.0)],
+        }
+    }
+}
+
+impl fmt::Display for Pid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02X}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_standard_formulas() {
+        assert_eq!(Pid::Speed.decode(&[0x3C]), Some(60.0));
+        assert_eq!(Pid::Rpm.decode(&[0x1A, 0xF8]), Some(1726.0));
+        assert_eq!(Pid::CoolantTemp.decode(&[0x7B]), Some(83.0));
+        assert_eq!(Pid::FuelLevel.decode(&[0xFF]), Some(1.0));
+        assert_eq!(Pid::Rpm.decode(&[0x1A]), None);
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        for (pid, value) in [
+            (Pid::Speed, 88.0),
+            (Pid::Rpm, 2450.25),
+            (Pid::CoolantTemp, -12.0),
+            (Pid::OilTemp, 105.0),
+        ] {
+            assert_eq!(pid.decode(&pid.encode(value)), Some(value));
+        }
+    }
+}
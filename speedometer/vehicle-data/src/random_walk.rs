@@ -0,0 +1,99 @@
+use crate::simulated::SpeedProfile;
+
+/// Speed driven by a random, bounded acceleration, so it wanders without jumps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomWalk {
+    max_speed: f32,
+    /// Largest change of the acceleration per second, in km/h/s².
+    max_jerk: f32,
+    /// Largest acceleration in km/h/s.
+    max_acceleration: f32,
+    state: u64,
+    speed: f32,
+    acceleration: f32,
+    time: f32,
+}
+
+impl RandomWalk {
+    /// Walk between standstill and `max_speed` km/h, the same `seed` gives the same trace.
+    pub fn new(max_speed: f32, seed: u64) -> Self {
+        Self {
+            max_speed,
+            max_jerk: 4.0,
+            max_acceleration: 8.0,
+            // Xorshift gets stuck on zero.
+            state: seed.max(1),
+            speed: 0.0,
+            acceleration: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_max_acceleration(mut self, max_acceleration: f32) -> Self {
+        self.max_acceleration = max_acceleration.abs();
+        self
+    }
+
+    pub fn with_max_jerk(mut self, max_jerk: f32) -> Self {
+        self.max_jerk = max_jerk.abs();
+        self
+    }
+
+    /// Uniform in `-1.0..1.0`.
+    fn next_unit(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        (self.state >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+impl SpeedProfile for RandomWalk {
+    fn speed_at(&mut self, time: f32) -> f32 {
+        let dt = (time - self.time).max(0.0);
+        self.time = time;
+
+        let jerk = self.next_unit() * self.max_jerk;
+        self.acceleration =
+            (self.acceleration + jerk * dt).clamp(-self.max_acceleration, self.max_acceleration);
+        self.speed += self.acceleration * dt;
+
+        if !(0.0..=self.max_speed).contains(&self.speed) {
+            // Bounce off the limits instead of sticking to them.
+            self.speed = self.speed.clamp(0.0, self.max_speed);
+            self.acceleration = -self.acceleration;
+        }
+
+        self.speed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_trace() {
+        let mut a = RandomWalk::new(180.0, 7);
+        let mut b = RandomWalk::new(180.0, 7);
+
+        for i in 0..1000 {
+            let time = i as f32 / 10.0;
+            assert_eq!(a.speed_at(time), b.speed_at(time));
+        }
+    }
+
+    #[test]
+    fn stays_within_bounds_without_jumps() {
+        let mut walk = RandomWalk::new(180.0, 42);
+        let mut previous = walk.speed_at(0.0);
+
+        for i in 1..10_000 {
+            let speed = walk.speed_at(i as f32 / 10.0);
+            assert!((0.0..=180.0).contains(&speed));
+            assert!((speed - previous).abs() <= 8.0 * 0.1 + 1e-2);
+            previous = speed;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::log::Log;
+use crate::sample::Sample;
+use crate::source::{Playback, VehicleDataSource};
+use std::time::Duration;
+
+pub const MIN_PLAYBACK_RATE: f32 = 0.5;
+pub const MAX_PLAYBACK_RATE: f32 = 8.0;
+
+/// Source that plays a recorded [`Log`] back.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    log: Log,
+    rate: f32,
+    paused: bool,
+    looping: bool,
+    position: f32,
+    last_poll: Option<Duration>,
+    /// Position changed without the clock running, e.g. after a seek.
+    moved: bool,
+}
+
+impl Replay {
+    /// Plays `log` once at real time from the start.
+    pub fn new(log: Log) -> Self {
+        Self {
+            log,
+            rate: 1.0,
+            paused: false,
+            looping: false,
+            position: 0.0,
+            last_poll: None,
+            moved: true,
+        }
+    }
+
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.set_rate(rate);
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn log(&self) -> &Log {
+        &self.log
+    }
+
+    fn advance(&mut self, seconds: f32) {
+        let duration = self.log.duration();
+        self.position += seconds * self.rate;
+
+        if self.position >= duration {
+            if self.looping && duration > 0.0 {
+                self.position = self.position.rem_euclid(duration);
+            } else {
+                self.position = duration;
+                self.paused = true;
+            }
+        }
+    }
+}
+
+impl VehicleDataSource for Replay {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        let seconds = self
+            .last_poll
+            .map_or(0.0, |last| elapsed.saturating_sub(last).as_secs_f32());
+        self.last_poll = Some(elapsed);
+
+        if self.paused && !self.moved {
+            return None;
+        }
+        if !self.paused {
+            self.advance(seconds);
+        }
+
+        self.moved = false;
+        Some(self.log.sample_at(self.position))
+    }
+
+    fn playback(&self) -> Option<&dyn Playback> {
+        Some(self)
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        Some(self)
+    }
+}
+
+impl Playback for Replay {
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        if !paused && self.position >= self.log.duration() {
+            self.seek(0.0);
+        }
+        self.paused = paused;
+    }
+
+    fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Rates outside [`MIN_PLAYBACK_RATE`] to [`MAX_PLAYBACK_RATE`] are pinned to
+    /// the nearest end, one that is not a number is ignored.
+    fn set_rate(&mut self, rate: f32) {
+        if !rate.is_nan() {
+            self.rate = rate.clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE);
+        }
+    }
+
+    fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    fn position(&self) -> f32 {
+        self.position
+    }
+
+    fn duration(&self) -> f32 {
+        self.log.duration()
+    }
+
+    fn seek(&mut self, position: f32) {
+        self.position = position.clamp(0.0, self.log.duration());
+        self.moved = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::ColumnMap;
+
+    fn replay() -> Replay {
+        let log = Log::from_csv(
+            "time,speed\n0,0\n10,100\n".as_bytes(),
+            &ColumnMap::default(),
+        )
+        .unwrap();
+        Replay::new(log)
+    }
+
+    fn at(seconds: f32) -> Duration {
+        Duration::from_secs_f32(seconds)
+    }
+
+    #[test]
+    fn plays_at_the_chosen_rate() {
+        let mut replay = replay().with_rate(2.0);
+
+        assert_eq!(replay.poll(at(0.0)).unwrap().speed, 0.0);
+        assert_eq!(replay.poll(at(2.0)).unwrap().speed, 40.0);
+        replay.set_rate(f32::NAN);
+        assert_eq!(replay.rate(), 2.0);
+        assert_eq!(replay.with_rate(20.0).rate(), MAX_PLAYBACK_RATE);
+    }
+
+    #[test]
+    fn pause_and_seek() {
+        let mut replay = replay();
+        replay.poll(at(0.0));
+        replay.set_paused(true);
+
+        assert_eq!(replay.poll(at(5.0)), None);
+
+        replay.seek(7.5);
+        assert_eq!(replay.poll(at(6.0)).unwrap().speed, 75.0);
+        assert_eq!(replay.poll(at(7.0)), None);
+
+        replay.set_paused(false);
+        assert_eq!(replay.poll(at(8.0)).unwrap().speed, 85.0);
+    }
+
+    #[test]
+    fn stops_at_the_end_or_loops() {
+        let mut once = replay();
+        once.poll(at(0.0));
+        assert_eq!(once.poll(at(12.0)).unwrap().speed, 100.0);
+        assert!(once.is_paused());
+
+        once.set_paused(false);
+        assert_eq!(once.position(), 0.0);
+
+        let mut looped = replay().with_looping(true);
+        looped.poll(at(0.0));
+        assert_eq!(looped.poll(at(12.0)).unwrap().speed, 20.0);
+        assert!(!looped.is_paused());
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Vehicle state at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Time since the source started.
+    pub time: Duration,
+    /// Speed in km/h.
+    pub speed: f32,
+    pub rpm: f32,
+    /// Engaged gear, `0` is neutral.
+    pub gear: u8,
+    /// Fuel level, `0.0` is empty and `1.0` is full.
+    pub fuel: f32,
+    /// Coolant temperature in °C.
+    pub coolant_temp: f32,
+    /// Oil temperature in °C.
+    pub oil_temp: f32,
+    /// Course over ground in degrees clockwise from north, for sources that know it.
+    pub heading: Option<f32>,
+    /// Accelerator pedal, `0.0` is released and `1.0` floored, for sources that know it.
+    pub throttle: Option<f32>,
+    /// Brake pedal, like the throttle.
+    pub brake: Option<f32>,
+}
+
+impl Default for Sample {
+    /// Parked car with a cold engine.
+    fn default() -> Self {
+        Self {
+            time: Duration::ZERO,
+            speed: 0.0,
+            rpm: 0.0,
+            gear: 0,
+            fuel: 1.0,
+            coolant_temp: 20.0,
+            oil_temp: 20.0,
+            heading: None,
+            throttle: None,
+            brake: None,
+        }
+    }
+}
+
+impl fmt::Display for Sample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.gear {
+            0 => write!(f, "N")?,
+            gear => write!(f, "{gear}")?,
+        }
+
+        write!(
+            f,
+            " · {:.0} rpm · fuel {:.0}% · coolant {:.0} °C · oil {:.0} °C",
+            self.rpm,
+            self.fuel * 100.0,
+            self.coolant_temp,
+            self.oil_temp
+        )?;
+
+        if let Some(heading) = self.heading {
+            write!(f, " · heading {heading:.0}°")?;
+        }
+        if let Some(throttle) = self.throttle {
+            write!(f, " · throttle {:.0}%", throttle * 100.0)?;
+        }
+        if let Some(brake) = self.brake {
+            write!(f, " · brake {:.0}%", brake * 100.0)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// Serial port in raw mode. Reads give up after a tenth of a second without data
+/// and return `0`.
+#[derive(Debug)]
+pub struct SerialPort {
+    file: File,
+}
+
+impl SerialPort {
+    pub fn open(path: impl AsRef<Path>, baud_rate: u32) -> io::Result<Self> {
+        let speed = speed(baud_rate).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported baud rate {baud_rate}"),
+            )
+        })?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(path)?;
+
+        // SAFETY: `termios` is plain old data filled in by `tcgetattr`.
+        unsafe {
+            let fd = file.as_raw_fd();
+            let mut termios: libc::termios = mem::zeroed();
+            if libc::tcgetattr(fd, &mut termios) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            libc::cfmakeraw(&mut termios);
+            termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+            termios.c_cc[libc::VMIN] = 0;
+            termios.c_cc[libc::VTIME] = 1;
+            libc::cfsetispeed(&mut termios, speed);
+            libc::cfsetospeed(&mut termios, speed);
+
+            if libc::tcsetattr(fd, libc::TCSANOW, &termios) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            libc::tcflush(fd, libc::TCIOFLUSH);
+        }
+
+        Ok(Self { file })
+    }
+}
+
+impl Read for SerialPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Write for SerialPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn speed(baud_rate: u32) -> Option<libc::speed_t> {
+    Some(match baud_rate {
+        4_800 => libc::B4800,
+        9_600 => libc::B9600,
+        19_200 => libc::B19200,
+        38_400 => libc::B38400,
+        57_600 => libc::B57600,
+        115_200 => libc::B115200,
+        230_400 => libc::B230400,
+        _ => return None,
+    })
+}
+
+/// Opens a pseudo-terminal for an emulated device: the emulator serves the
+/// returned master side, a [`SerialPort`] opens the returned path.
+pub fn open_pty() -> io::Result<(File, PathBuf)> {
+    // SAFETY: plain FFI calls, `ptsname` is read before anything else can call it
+    // on this descriptor.
+    unsafe {
+        let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = File::from_raw_fd(fd);
+
+        if libc::grantpt(fd) < 0 || libc::unlockpt(fd) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let name = libc::ptsname(fd);
+        if name.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let path = PathBuf::from(CStr::from_ptr(name).to_string_lossy().into_owned());
+
+        Ok((master, path))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::model::VehicleModel;
+use crate::sample::Sample;
+use crate::source::VehicleDataSource;
+use std::time::Duration;
+
+/// Rate of the simulated sensors, 10 Hz like a typical CAN speed message.
+pub const SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+/// Longest stretch simulated in one poll, a dashboard waking up later skips the rest.
+const MAX_CATCH_UP: Duration = Duration::from_secs(10);
+
+/// Vehicle speed over time.
+pub trait SpeedProfile {
+    /// Speed in km/h, `time` seconds after the start. Called with increasing times.
+    fn speed_at(&mut self, time: f32) -> f32;
+}
+
+/// Source that drives a [`VehicleModel`] along a [`SpeedProfile`].
+#[derive(Debug, Clone)]
+pub struct Simulated<P> {
+    profile: P,
+    model: VehicleModel,
+    next_sample: Duration,
+}
+
+impl<P: SpeedProfile> Simulated<P> {
+    pub fn new(profile: P) -> Self {
+        Self::with_model(profile, VehicleModel::default())
+    }
+
+    pub fn with_model(profile: P, model: VehicleModel) -> Self {
+        Self {
+            profile,
+            model,
+            next_sample: Duration::ZERO,
+        }
+    }
+}
+
+impl<P: SpeedProfile> VehicleDataSource for Simulated<P> {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        if elapsed < self.next_sample {
+            return None;
+        }
+
+        if elapsed - self.next_sample > MAX_CATCH_UP {
+            self.next_sample = elapsed - MAX_CATCH_UP;
+        }
+
+        let mut sample = self.model.sample();
+        while self.next_sample <= elapsed {
+            let speed = self.profile.speed_at(self.next_sample.as_secs_f32());
+            sample = self.model.update(self.next_sample, speed);
+            self.next_sample += SAMPLE_PERIOD;
+        }
+
+        Some(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Constant(f32);
+
+    impl SpeedProfile for Constant {
+        fn speed_at(&mut self, _time: f32) -> f32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn samples_at_fixed_rate() {
+        let mut source = Simulated::new(Constant(50.0));
+
+        let first = source.poll(Duration::ZERO).unwrap();
+        assert_eq!(first.time, Duration::ZERO);
+        assert_eq!(first.speed, 50.0);
+
+        assert_eq!(source.poll(Duration::from_millis(50)), None);
+
+        let next = source.poll(Duration::from_millis(350)).unwrap();
+        assert_eq!(next.time, Duration::from_millis(300));
+    }
+
+    #[test]
+    fn long_pauses_are_skipped() {
+        let mut source = Simulated::new(Constant(50.0));
+
+        let sample = source.poll(Duration::from_secs(3600)).unwrap();
+
+        assert_eq!(sample.time, Duration::from_secs(3600));
+    }
+}
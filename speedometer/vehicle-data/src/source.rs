@@ -0,0 +1,159 @@
+use crate::sample::Sample;
+use std::fmt;
+use std::time::Duration;
+
+/// Rates offered by the dashboards' playback controls.
+pub const PLAYBACK_RATES: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Silence after which a live source is considered lost.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Whether the vehicle is still being heard from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceStatus {
+    #[default]
+    Live,
+    /// Nothing arrived for longer than the source timeout, the values shown are stale.
+    TimedOut,
+    /// The source is talking but can't measure, e.g. a GPS receiver without a fix.
+    NoSignal,
+    /// The source can't be read, e.g. a CAN interface that went down.
+    Failed,
+}
+
+impl fmt::Display for SourceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceStatus::Live => "Live",
+            SourceStatus::TimedOut => "No data from the vehicle",
+            SourceStatus::NoSignal => "No signal",
+            SourceStatus::Failed => "Cannot read the vehicle data",
+        })
+    }
+}
+
+/// Anything the dashboards can read vehicle samples from.
+pub trait VehicleDataSource {
+    /// Newest sample taken at or before `elapsed` since the dashboard started,
+    /// `None` when nothing new arrived since the previous poll.
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample>;
+
+    /// Live sources report [`SourceStatus::TimedOut`] when the vehicle goes silent
+    /// or [`SourceStatus::NoSignal`] when it can't measure, the dashboards grey the
+    /// gauges out until data comes back.
+    fn status(&self, _elapsed: Duration) -> SourceStatus {
+        SourceStatus::Live
+    }
+
+    /// Transport controls, for sources that play a recording.
+    fn playback(&self) -> Option<&dyn Playback> {
+        None
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        None
+    }
+}
+
+impl<S: VehicleDataSource + ?Sized> VehicleDataSource for Box<S> {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        (**self).poll(elapsed)
+    }
+
+    fn status(&self, elapsed: Duration) -> SourceStatus {
+        (**self).status(elapsed)
+    }
+
+    fn playback(&self) -> Option<&dyn Playback> {
+        (**self).playback()
+    }
+
+    fn playback_mut(&mut self) -> Option<&mut dyn Playback> {
+        (**self).playback_mut()
+    }
+}
+
+/// Pause, seek, speed and looping of a recorded source.
+pub trait Playback {
+    fn is_paused(&self) -> bool;
+
+    /// Resuming at the end of a recording that doesn't loop starts it over.
+    fn set_paused(&mut self, paused: bool);
+
+    /// Playback speed, `1.0` is real time.
+    fn rate(&self) -> f32;
+
+    fn set_rate(&mut self, rate: f32);
+
+    fn is_looping(&self) -> bool;
+
+    fn set_looping(&mut self, looping: bool);
+
+    /// Seconds from the start of the recording.
+    fn position(&self) -> f32;
+
+    /// Length of the recording in seconds.
+    fn duration(&self) -> f32;
+
+    fn seek(&mut self, position: f32);
+}
+
+/// Tracks when a live source last received data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watchdog {
+    timeout: Duration,
+    last_seen: Option<Duration>,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_seen: None,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Records data arriving `elapsed` after the dashboard started.
+    pub fn feed(&mut self, elapsed: Duration) {
+        self.last_seen = Some(elapsed);
+    }
+
+    /// Timed out until the first data arrives.
+    pub fn status(&self, elapsed: Duration) -> SourceStatus {
+        match self.last_seen {
+            Some(last) if elapsed.saturating_sub(last) <= self.timeout => SourceStatus::Live,
+            _ => SourceStatus::TimedOut,
+        }
+    }
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watchdog_times_out_without_data() {
+        let mut watchdog = Watchdog::new(Duration::from_millis(500));
+        assert_eq!(watchdog.status(Duration::ZERO), SourceStatus::TimedOut);
+
+        watchdog.feed(Duration::from_secs(1));
+        assert_eq!(
+            watchdog.status(Duration::from_millis(1400)),
+            SourceStatus::Live
+        );
+        assert_eq!(
+            watchdog.status(Duration::from_millis(1600)),
+            SourceStatus::TimedOut
+        );
+    }
+}
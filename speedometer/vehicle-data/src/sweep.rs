@@ -0,0 +1,69 @@
+use crate::simulated::SpeedProfile;
+use std::f32::consts::TAU;
+
+/// Speed oscillating between two values with a frequency rising linearly over
+/// each sweep, handy to see how the needle follows fast changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SineSweep {
+    min: f32,
+    max: f32,
+    start_frequency: f32,
+    end_frequency: f32,
+    sweep_seconds: f32,
+}
+
+impl SineSweep {
+    /// One minute from 0.05 Hz to 1 Hz between `min` and `max` km/h.
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            start_frequency: 0.05,
+            end_frequency: 1.0,
+            sweep_seconds: 60.0,
+        }
+    }
+
+    /// Frequencies in Hz at the start and the end of a sweep.
+    pub fn with_frequencies(mut self, start: f32, end: f32) -> Self {
+        self.start_frequency = start;
+        self.end_frequency = end;
+        self
+    }
+
+    pub fn with_sweep_seconds(mut self, seconds: f32) -> Self {
+        self.sweep_seconds = seconds.max(f32::EPSILON);
+        self
+    }
+
+    pub fn speed(&self, time: f32) -> f32 {
+        let t = time.rem_euclid(self.sweep_seconds);
+        let chirp = (self.end_frequency - self.start_frequency) / self.sweep_seconds;
+        let phase = TAU * (self.start_frequency * t + chirp * t * t / 2.0);
+
+        // Starts at `min` rather than mid-range.
+        let middle = (self.min + self.max) / 2.0;
+        middle - (self.max - self.min) / 2.0 * phase.cos()
+    }
+}
+
+impl SpeedProfile for SineSweep {
+    fn speed_at(&mut self, time: f32) -> f32 {
+        self.speed(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_within_bounds() {
+        let sweep = SineSweep::new(20.0, 180.0);
+
+        assert_eq!(sweep.speed(0.0), 20.0);
+        assert!((0..6000)
+            .map(|i| sweep.speed(i as f32 / 100.0))
+            .all(|speed| (20.0 - 1e-3..=180.0 + 1e-3).contains(&speed)));
+    }
+}
@@ -0,0 +1,280 @@
+use super::packet::{f32_at, Packet};
+use super::UdpError;
+use crate::sample::Sample;
+use std::fmt;
+use std::str::FromStr;
+
+const MS_TO_KMH: f32 = 3.6;
+
+/// OutGauge without and with the optional id.
+const OUTGAUGE_LEN: [usize; 2] = [92, 96];
+/// Forza Motorsport 7 "Dash" and Forza Horizon 4/5, which inserts twelve bytes
+/// after the "Sled" part.
+const FORZA_FM7_LEN: usize = 311;
+const FORZA_HORIZON_LEN: usize = 324;
+const FORZA_SLED_LEN: usize = 232;
+
+/// Values carried by one telemetry packet.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Telemetry {
+    /// Packet counter, for layouts that have one.
+    pub sequence: Option<u16>,
+    /// km/h.
+    pub speed: f32,
+    pub rpm: f32,
+    /// `0` is neutral, reverse is shown as neutral too.
+    pub gear: u8,
+    pub throttle: f32,
+    pub brake: f32,
+    pub fuel: Option<f32>,
+    pub coolant_temp: Option<f32>,
+    pub oil_temp: Option<f32>,
+}
+
+impl Telemetry {
+    pub fn from_sample(sample: &Sample) -> Self {
+        Self {
+            sequence: None,
+            speed: sample.speed,
+            rpm: sample.rpm,
+            gear: sample.gear,
+            throttle: sample.throttle.unwrap_or_default(),
+            brake: sample.brake.unwrap_or_default(),
+            fuel: Some(sample.fuel),
+            coolant_temp: Some(sample.coolant_temp),
+            oil_temp: Some(sample.oil_temp),
+        }
+    }
+
+    /// Copies the values into `sample`, the ones the layout lacks or that are
+    /// not finite are left alone.
+    pub fn apply(&self, sample: &mut Sample) {
+        let pedal = |value: f32| finite(value).map(|value| value.clamp(0.0, 1.0));
+
+        sample.speed = finite(self.speed).unwrap_or(sample.speed);
+        sample.rpm = finite(self.rpm).unwrap_or(sample.rpm);
+        sample.gear = self.gear;
+        sample.throttle = pedal(self.throttle).or(sample.throttle);
+        sample.brake = pedal(self.brake).or(sample.brake);
+        sample.fuel = self.fuel.and_then(finite).unwrap_or(sample.fuel);
+        sample.coolant_temp = self
+            .coolant_temp
+            .and_then(finite)
+            .unwrap_or(sample.coolant_temp);
+        sample.oil_temp = self.oil_temp.and_then(finite).unwrap_or(sample.oil_temp);
+    }
+}
+
+fn finite(value: f32) -> Option<f32> {
+    value.is_finite().then_some(value)
+}
+
+/// Packet format the listener expects, picked with `--layout <name>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// The versioned [`Packet`] of this crate.
+    #[default]
+    Native,
+    /// OutGauge, sent by Live for Speed and BeamNG.drive.
+    OutGauge,
+    /// "Data Out" of Forza Motorsport 7 (Dash format) and Forza Horizon 4/5.
+    Forza,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::Native, Layout::OutGauge, Layout::Forza];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Native => "native",
+            Layout::OutGauge => "outgauge",
+            Layout::Forza => "forza",
+        }
+    }
+
+    /// `None` for packets that carry no driving data, e.g. Forza in the menus.
+    pub fn decode(self, bytes: &[u8]) -> Result<Option<Telemetry>, UdpError> {
+        match self {
+            Layout::Native => Packet::decode(bytes).map(|packet| {
+                Some(Telemetry {
+                    sequence: Some(packet.sequence),
+                    speed: packet.speed,
+                    rpm: packet.rpm,
+                    gear: packet.gear,
+                    throttle: packet.throttle,
+                    brake: packet.brake,
+                    ..Telemetry::default()
+                })
+            }),
+            Layout::OutGauge => decode_outgauge(bytes).map(Some),
+            Layout::Forza => decode_forza(bytes),
+        }
+    }
+
+    /// Packet as the game would send it, for testing the listener.
+    pub fn encode(self, telemetry: &Telemetry) -> Vec<u8> {
+        match self {
+            Layout::Native => Packet {
+                sequence: telemetry.sequence.unwrap_or_default(),
+                speed: telemetry.speed,
+                rpm: telemetry.rpm,
+                gear: telemetry.gear,
+                throttle: telemetry.throttle,
+                brake: telemetry.brake,
+            }
+            .encode()
+            .to_vec(),
+            Layout::OutGauge => encode_outgauge(telemetry),
+            Layout::Forza => encode_forza(telemetry),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = UdpError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.name() == name)
+            .ok_or_else(|| UdpError::UnknownLayout(name.to_owned()))
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Gear `0` is reverse and `1` neutral.
+fn decode_outgauge(bytes: &[u8]) -> Result<Telemetry, UdpError> {
+    if !OUTGAUGE_LEN.contains(&bytes.len()) {
+        return Err(UdpError::TooShort(bytes.len()));
+    }
+
+    Ok(Telemetry {
+        sequence: None,
+        speed: f32_at(bytes, 12) * MS_TO_KMH,
+        rpm: f32_at(bytes, 16),
+        gear: bytes[10].saturating_sub(1),
+        throttle: f32_at(bytes, 48),
+        brake: f32_at(bytes, 52),
+        fuel: Some(f32_at(bytes, 28)),
+        coolant_temp: Some(f32_at(bytes, 24)),
+        oil_temp: Some(f32_at(bytes, 36)),
+    })
+}
+
+fn encode_outgauge(telemetry: &Telemetry) -> Vec<u8> {
+    let mut bytes = vec![0; OUTGAUGE_LEN[1]];
+    let mut put = |offset: usize, value: f32| {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    };
+
+    put(12, telemetry.speed / MS_TO_KMH);
+    put(16, telemetry.rpm);
+    put(24, telemetry.coolant_temp.unwrap_or_default());
+    put(28, telemetry.fuel.unwrap_or_default());
+    put(36, telemetry.oil_temp.unwrap_or_default());
+    put(48, telemetry.throttle);
+    put(52, telemetry.brake);
+    bytes[4..8].copy_from_slice(b"SPDT");
+    bytes[10] = telemetry.gear.saturating_add(1);
+
+    bytes
+}
+
+/// Pedals are `0` to `255`, gear `0` is reverse.
+fn decode_forza(bytes: &[u8]) -> Result<Option<Telemetry>, UdpError> {
+    let dash = match bytes.len() {
+        FORZA_FM7_LEN => FORZA_SLED_LEN,
+        FORZA_HORIZON_LEN => FORZA_SLED_LEN + 12,
+        // The "Sled" format has no speed.
+        FORZA_SLED_LEN => return Ok(None),
+        len => return Err(UdpError::TooShort(len)),
+    };
+    let race_on = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) != 0;
+    if !race_on {
+        return Ok(None);
+    }
+
+    Ok(Some(Telemetry {
+        sequence: None,
+        speed: f32_at(bytes, dash + 12) * MS_TO_KMH,
+        rpm: f32_at(bytes, 16),
+        gear: bytes[dash + 75],
+        throttle: bytes[dash + 71] as f32 / 255.0,
+        brake: bytes[dash + 72] as f32 / 255.0,
+        fuel: Some(f32_at(bytes, dash + 44)),
+        coolant_temp: None,
+        oil_temp: None,
+    }))
+}
+
+fn encode_forza(telemetry: &Telemetry) -> Vec<u8> {
+    let dash = FORZA_SLED_LEN;
+    let mut bytes = vec![0; FORZA_FM7_LEN];
+    let pedal = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    bytes[0..4].copy_from_slice(&1i32.to_le_bytes());
+    bytes[16..20].copy_from_slice(&telemetry.rpm.to_le_bytes());
+    bytes[dash + 12..dash + 16].copy_from_slice(&(telemetry.speed / MS_TO_KMH).to_le_bytes());
+    bytes[dash + 44..dash + 48].copy_from_slice(&telemetry.fuel.unwrap_or_default().to_le_bytes());
+    bytes[dash + 71] = pedal(telemetry.throttle);
+    bytes[dash + 72] = pedal(telemetry.brake);
+    bytes[dash + 75] = telemetry.gear;
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telemetry() -> Telemetry {
+        Telemetry {
+            sequence: None,
+            speed: 90.0,
+            rpm: 3500.0,
+            gear: 3,
+            throttle: 0.6,
+            brake: 0.0,
+            fuel: Some(0.5),
+            coolant_temp: Some(88.0),
+            oil_temp: Some(97.0),
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-2, "{a} != {b}");
+    }
+
+    #[test]
+    fn every_layout_round_trips() {
+        for layout in Layout::ALL {
+            let sent = telemetry();
+            let received = layout.decode(&layout.encode(&sent)).unwrap().unwrap();
+
+            assert_close(received.speed, sent.speed);
+            assert_close(received.rpm, sent.rpm);
+            assert_close(received.throttle, sent.throttle);
+            assert_eq!(received.gear, sent.gear, "{layout}");
+        }
+    }
+
+    #[test]
+    fn reads_forza_horizon_and_skips_menus() {
+        let fm7 = Layout::Forza.encode(&telemetry());
+        let mut horizon = fm7[..FORZA_SLED_LEN].to_vec();
+        horizon.extend([0; 12]);
+        horizon.extend(&fm7[FORZA_SLED_LEN..]);
+        horizon.push(0);
+
+        let received = Layout::Forza.decode(&horizon).unwrap().unwrap();
+        assert_close(received.speed, 90.0);
+
+        horizon[0] = 0;
+        assert_eq!(Layout::Forza.decode(&horizon).unwrap(), None);
+    }
+}
@@ -0,0 +1,308 @@
+//! Telemetry sent over UDP by a game, a simulator or any other program.
+//!
+//! The native layout is a little-endian packet of 28 bytes, version 1:
+//!
+//! | Offset | Type    | Field                                  |
+//! |--------|---------|----------------------------------------|
+//! | 0      | `[u8;4]`| magic, `SPDT`                          |
+//! | 4      | `u8`    | version, `1`                           |
+//! | 5      | `u8`    | flags, `0`                             |
+//! | 6      | `u16`   | sequence, incremented for every packet |
+//! | 8      | `f32`   | speed in km/h                          |
+//! | 12     | `f32`   | engine speed in rpm                    |
+//! | 16     | `u8`    | gear, `0` is neutral                   |
+//! | 17     | `[u8;3]`| reserved, `0`                          |
+//! | 20     | `f32`   | throttle, `0.0` to `1.0`               |
+//! | 24     | `f32`   | brake, `0.0` to `1.0`                  |
+//!
+//! Later versions only append fields, so a listener can read the start of any
+//! packet with a known version. The OutGauge (Live for Speed, BeamNG.drive) and
+//! Forza "Data Out" layouts are read as well, see [`Layout`].
+
+mod layout;
+mod packet;
+
+pub use layout::{Layout, Telemetry};
+pub use packet::Packet;
+
+use crate::sample::Sample;
+use crate::source::{SourceStatus, VehicleDataSource, Watchdog};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Port the listener and the `udp_send` example use unless told otherwise.
+pub const DEFAULT_PORT: u16 = 20_800;
+
+/// How far back a sequence number may jump before it is taken for a restarted
+/// sender rather than a late packet.
+const RESTART_GAP: i16 = 100;
+/// Time without a packet in order after which any sequence number is taken for a
+/// restarted sender, late packets arrive well within it.
+const RESTART_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Large enough for every layout.
+const MAX_PACKET_LEN: usize = 1_500;
+
+/// Source listening for telemetry packets on a UDP socket.
+///
+/// Each poll reads every packet that arrived since the last one and keeps the
+/// newest; native packets that arrive out of order are dropped, and so are values
+/// that are not finite. Goes [`SourceStatus::TimedOut`] when the sender goes quiet.
+#[derive(Debug)]
+pub struct UdpSource {
+    socket: UdpSocket,
+    layout: Layout,
+    /// Sequence number of the last packet in order and when it arrived.
+    sequence: Option<(u16, Duration)>,
+    sample: Sample,
+    watchdog: Watchdog,
+}
+
+impl UdpSource {
+    pub fn bind(address: impl ToSocketAddrs, layout: Layout) -> Result<Self, UdpError> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            layout,
+            sequence: None,
+            sample: Sample::default(),
+            watchdog: Watchdog::default(),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Watchdog::new(timeout);
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, UdpError> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Whether a packet numbered `sequence` arriving at `elapsed` is newer than the
+    /// last one, or comes from a restarted sender.
+    fn in_order(&self, sequence: Option<u16>, elapsed: Duration) -> bool {
+        match (self.sequence, sequence) {
+            (Some((last, at)), Some(sequence)) => {
+                let step = sequence.wrapping_sub(last) as i16;
+                !(-RESTART_GAP..=0).contains(&step) || elapsed.saturating_sub(at) > RESTART_TIMEOUT
+            }
+            _ => true,
+        }
+    }
+}
+
+impl VehicleDataSource for UdpSource {
+    fn poll(&mut self, elapsed: Duration) -> Option<Sample> {
+        let mut buffer = [0; MAX_PACKET_LEN];
+        let mut latest = None;
+
+        // Stops at `WouldBlock` once the queue is empty, other errors such as a
+        // refused ICMP message are only worth skipping.
+        while let Ok(len) = self.socket.recv(&mut buffer) {
+            let Ok(Some(telemetry)) = self.layout.decode(&buffer[..len]) else {
+                continue;
+            };
+            if self.in_order(telemetry.sequence, elapsed) {
+                self.sequence = telemetry.sequence.map(|sequence| (sequence, elapsed));
+                latest = Some(telemetry);
+            }
+        }
+
+        let telemetry = latest?;
+        self.watchdog.feed(elapsed);
+        self.sample.time = elapsed;
+        telemetry.apply(&mut self.sample);
+        Some(self.sample)
+    }
+
+    fn status(&self, elapsed: Duration) -> SourceStatus {
+        self.watchdog.status(elapsed)
+    }
+}
+
+/// Reads `<port>`, listening on every interface, or `<address>:<port>`.
+pub fn listen_address(value: &str) -> Result<SocketAddr, UdpError> {
+    if let Ok(port) = value.parse() {
+        return Ok((Ipv4Addr::UNSPECIFIED, port).into());
+    }
+
+    value
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or_else(|| UdpError::InvalidAddress(value.to_owned()))
+}
+
+#[derive(Debug)]
+pub enum UdpError {
+    Io(io::Error),
+    /// The packet is shorter than its layout, or of no length the layout knows.
+    TooShort(usize),
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownLayout(String),
+    InvalidAddress(String),
+}
+
+impl fmt::Display for UdpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UdpError::Io(err) => write!(f, "UDP telemetry: {err}"),
+            UdpError::TooShort(len) => write!(f, "unexpected telemetry packet of {len} bytes"),
+            UdpError::BadMagic => f.write_str("not a telemetry packet"),
+            UdpError::UnsupportedVersion(version) => {
+                write!(f, "unsupported telemetry packet version {version}")
+            }
+            UdpError::UnknownLayout(name) => {
+                let names: Vec<_> = Layout::ALL.iter().map(|layout| layout.name()).collect();
+                write!(
+                    f,
+                    "unknown telemetry layout `{name}`, expected one of: {}",
+                    names.join(" ")
+                )
+            }
+            UdpError::InvalidAddress(address) => {
+                write!(f, "`{address}` is neither a port nor an address")
+            }
+        }
+    }
+}
+
+impl Error for UdpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UdpError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for UdpError {
+    fn from(err: io::Error) -> Self {
+        UdpError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Instant;
+
+    fn packet(sequence: u16, speed: f32) -> [u8; Packet::LEN] {
+        Packet {
+            sequence,
+            speed,
+            ..Packet::default()
+        }
+        .encode()
+    }
+
+    /// Polls until a sample arrives, the packets may take a moment on loopback.
+    fn receive(source: &mut UdpSource) -> Option<Sample> {
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(2) {
+            if let Some(sample) = source.poll(started.elapsed()) {
+                return Some(sample);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        None
+    }
+
+    #[test]
+    fn keeps_the_newest_packet() {
+        let mut source = UdpSource::bind("127.0.0.1:0", Layout::Native).unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.connect(source.local_addr().unwrap()).unwrap();
+
+        sender.send(&packet(u16::MAX, 40.0)).unwrap();
+        assert_eq!(receive(&mut source).unwrap().speed, 40.0);
+
+        // Wraps around, then a late packet and garbage that are both ignored.
+        sender.send(&packet(1, 60.0)).unwrap();
+        sender.send(&packet(0, 50.0)).unwrap();
+        sender.send(b"hello").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(receive(&mut source).unwrap().speed, 60.0);
+
+        // A restarted sender counts from zero again.
+        sender.send(&packet(1_000, 70.0)).unwrap();
+        assert_eq!(receive(&mut source).unwrap().speed, 70.0);
+        sender.send(&packet(0, 10.0)).unwrap();
+        assert_eq!(receive(&mut source).unwrap().speed, 10.0);
+    }
+
+    #[test]
+    fn takes_a_quiet_step_back_for_a_restart() {
+        let mut source = UdpSource::bind("127.0.0.1:0", Layout::Native).unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.connect(source.local_addr().unwrap()).unwrap();
+
+        sender.send(&packet(50, 40.0)).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(source.poll(Duration::ZERO).unwrap().speed, 40.0);
+
+        sender.send(&packet(3, 20.0)).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(source.poll(Duration::from_millis(100)), None);
+        sender.send(&packet(4, 25.0)).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(source.poll(Duration::from_millis(400)).unwrap().speed, 25.0);
+    }
+
+    #[test]
+    fn ignores_values_that_are_not_finite() {
+        let mut source = UdpSource::bind("127.0.0.1:0", Layout::Native).unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.connect(source.local_addr().unwrap()).unwrap();
+
+        sender.send(&packet(1, 40.0)).unwrap();
+        assert_eq!(receive(&mut source).unwrap().speed, 40.0);
+
+        let nan = Packet {
+            sequence: 2,
+            speed: f32::NAN,
+            rpm: f32::INFINITY,
+            throttle: f32::NAN,
+            ..Packet::default()
+        };
+        sender.send(&nan.encode()).unwrap();
+        let sample = receive(&mut source).unwrap();
+        assert_eq!(sample.speed, 40.0);
+        assert_eq!(sample.rpm, Sample::default().rpm);
+        assert_eq!(sample.throttle, Some(0.0));
+    }
+
+    #[test]
+    fn times_out_without_packets() {
+        let mut source = UdpSource::bind("127.0.0.1:0", Layout::OutGauge)
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+
+        assert_eq!(source.poll(Duration::from_secs(1)), None);
+        assert_eq!(
+            source.status(Duration::from_secs(1)),
+            SourceStatus::TimedOut
+        );
+    }
+
+    #[test]
+    fn parses_listen_addresses() {
+        assert_eq!(
+            listen_address("20800").unwrap(),
+            "0.0.0.0:20800".parse().unwrap()
+        );
+        assert_eq!(
+            listen_address("127.0.0.1:4444").unwrap(),
+            "127.0.0.1:4444".parse().unwrap()
+        );
+        assert!(listen_address("port").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use super::UdpError;
+
+/// Telemetry packet of the native layout, see the [module docs](super).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Packet {
+    /// Incremented by the sender for every packet, wrapping.
+    pub sequence: u16,
+    /// km/h.
+    pub speed: f32,
+    pub rpm: f32,
+    /// `0` is neutral.
+    pub gear: u8,
+    /// `0.0` to `1.0`.
+    pub throttle: f32,
+    /// `0.0` to `1.0`.
+    pub brake: f32,
+}
+
+impl Packet {
+    pub const MAGIC: [u8; 4] = *b"SPDT";
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 28;
+
+    pub fn encode(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+
+        bytes[0..4].copy_from_slice(&Self::MAGIC);
+        bytes[4] = Self::VERSION;
+        bytes[6..8].copy_from_slice(&self.sequence.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.speed.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.rpm.to_le_bytes());
+        bytes[16] = self.gear;
+        bytes[20..24].copy_from_slice(&self.throttle.to_le_bytes());
+        bytes[24..28].copy_from_slice(&self.brake.to_le_bytes());
+
+        bytes
+    }
+
+    /// Reads a version 1 packet. Longer packets are accepted so later versions can
+    /// append fields.
+    pub fn decode(bytes: &[u8]) -> Result<Self, UdpError> {
+        if bytes.len() < Self::LEN {
+            return Err(UdpError::TooShort(bytes.len()));
+        }
+        if bytes[0..4] != Self::MAGIC {
+            return Err(UdpError::BadMagic);
+        }
+        if bytes[4] != Self::VERSION {
+            return Err(UdpError::UnsupportedVersion(bytes[4]));
+        }
+
+        Ok(Self {
+            sequence: u16::from_le_bytes([bytes[6], bytes[7]]),
+            speed: f32_at(bytes, 8),
+            rpm: f32_at(bytes, 12),
+            gear: bytes[16],
+            throttle: f32_at(bytes, 20),
+            brake: f32_at(bytes, 24),
+        })
+    }
+}
+
+/// Little-endian `f32` at `offset`, the caller checks the length.
+pub(super) fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&bytes[offset..offset + 4]);
+    f32::from_le_bytes(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let packet = Packet {
+            sequence: 513,
+            speed: 123.5,
+            rpm: 4200.0,
+            gear: 4,
+            throttle: 0.75,
+            brake: 0.0,
+        };
+        let bytes = packet.encode();
+
+        assert_eq!(&bytes[..8], b"SPDT\x01\x00\x01\x02");
+        assert_eq!(Packet::decode(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn rejects_foreign_packets() {
+        let mut bytes = Packet::default().encode();
+
+        assert!(matches!(
+            Packet::decode(&bytes[..20]),
+            Err(UdpError::TooShort(20))
+        ));
+        bytes[4] = 2;
+        assert!(matches!(
+            Packet::decode(&bytes),
+            Err(UdpError::UnsupportedVersion(2))
+        ));
+        bytes[0] = b'X';
+        assert!(matches!(Packet::decode(&bytes), Err(UdpError::BadMagic)));
+    }
+}